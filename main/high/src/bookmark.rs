@@ -1,7 +1,7 @@
 use crate::{Project, Reaper};
 use reaper_medium::{BookmarkId, EnumProjectMarkers3Result, NativeColor, PositionInSeconds};
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum BookmarkType {
     Marker,
     Region,
@@ -1,14 +1,18 @@
+use crate::envelope::EnvelopeFingerprint;
 use crate::{
-    get_media_track_guid, ControlSurfaceEvent, Fx, FxParameter, Guid, Project, Reaper, Track,
-    TrackRoute,
+    get_media_track_guid, BookmarkType, ControlSurfaceEvent, Envelope, Fx, FxParameter, Guid, Item,
+    Project, Reaper, Take, Track, TrackRoute,
 };
+use crossbeam_channel::{Receiver, Sender};
 use reaper_medium::ProjectContext::{CurrentProject, Proj};
 use reaper_medium::{
-    reaper_str, AutomationMode, Bpm, ExtSetFxParamArgs, GlobalAutomationModeOverride,
-    InputMonitoringMode, MediaTrack, Pan, PanMode, PlayState, PlaybackSpeedFactor, ReaProject,
-    ReaperNormalizedFxParamValue, ReaperPanValue, ReaperStr, ReaperVersion, ReaperVolumeValue,
-    RecordingInput, TrackAttributeKey, TrackFxChainType, TrackLocation, TrackSendCategory,
-    TrackSendDirection, VersionDependentFxLocation, VersionDependentTrackFxLocation,
+    reaper_str, AutomationMode, BeginLoadProjectStateArgs, BookmarkId, Bpm, DurationInSeconds,
+    ExtSetFxParamArgs, GlobalAutomationModeOverride, InputMonitoringMode, MediaItem, MediaItemTake,
+    MediaTrack, Pan, PanMode, PlayState, PlaybackSpeedFactor, PositionInSeconds,
+    ProjectConfigExtension, ReaProject, ReaperNormalizedFxParamValue, ReaperPanValue, ReaperStr,
+    ReaperVersion, ReaperVolumeValue, RecordingInput, SaveExtensionConfigArgs, TrackAttributeKey,
+    TrackEnvelope, TrackFxChainType, TrackLocation, TrackSendCategory, TrackSendDirection,
+    VersionDependentFxLocation, VersionDependentTrackFxLocation,
 };
 use std::cell::{Cell, RefCell, RefMut};
 use std::collections::{HashMap, HashSet};
@@ -19,12 +23,22 @@ pub struct ChangeDetectionMiddleware {
     last_active_project: Cell<Project>,
     last_global_automation_mode_override: Cell<Option<GlobalAutomationModeOverride>>,
     project_datas: RefCell<ProjectDataMap>,
+    bookmark_datas: RefCell<HashMap<ReaProject, BookmarkDataMap>>,
+    item_poll_budget: Cell<u32>,
+    item_poll_state: RefCell<ItemPollState>,
+    envelope_poll_budget: Cell<u32>,
+    envelope_poll_state: RefCell<EnvelopePollState>,
+    undo_state: RefCell<Option<UndoState>>,
+    dirty_state: Cell<Option<(ReaProject, bool)>>,
+    project_lifecycle_sender: Sender<ProjectLifecycleNotification>,
+    project_lifecycle_receiver: Receiver<ProjectLifecycleNotification>,
     // Capabilities depending on REAPER version
     supports_detection_of_input_fx: bool,
 }
 
 type ProjectDataMap = HashMap<ReaProject, TrackDataMap>;
 type TrackDataMap = HashMap<MediaTrack, TrackData>;
+type BookmarkDataMap = HashMap<(BookmarkType, BookmarkId), BookmarkData>;
 
 /// Keeps current track values for detecting real value changes.
 ///
@@ -133,6 +147,214 @@ struct TrackFxKey {
     param_index: u32,
 }
 
+/// Region length changes below this number of seconds are not reported as resize.
+const REGION_LENGTH_TOLERANCE: f64 = 1e-9;
+
+/// Keeps the last known marker and region values for detecting what exactly has changed.
+///
+/// REAPER just tells us that something about the bookmarks has changed.
+#[derive(Debug)]
+struct BookmarkData {
+    position: PositionInSeconds,
+    region_end_position: Option<PositionInSeconds>,
+    name: String,
+}
+
+fn capture_bookmarks(project: Project) -> BookmarkDataMap {
+    project
+        .bookmarks()
+        .map(|b| {
+            b.with_full_info(|res| {
+                let bookmark_type = if res.region_end_position.is_some() {
+                    BookmarkType::Region
+                } else {
+                    BookmarkType::Marker
+                };
+                let data = BookmarkData {
+                    position: res.position,
+                    region_end_position: res.region_end_position,
+                    name: res.name.to_str().to_owned(),
+                };
+                ((bookmark_type, res.id), data)
+            })
+        })
+        .collect()
+}
+
+/// Default number of items which are examined per main loop cycle.
+pub const DEFAULT_ITEM_POLL_BUDGET: u32 = 200;
+
+/// Keeps current item values for detecting item changes.
+///
+/// REAPER doesn't notify control surfaces about item changes, so they are detected by polling.
+/// The items of the current project are examined round-robin, a limited number per cycle.
+#[derive(Debug, Default)]
+struct ItemPollState {
+    project: Option<ReaProject>,
+    items: HashMap<MediaItem, ItemData>,
+    /// Index of the item which is going to be examined next.
+    next_index: u32,
+    /// Number of the current sweep through all items of the project.
+    sweep: u64,
+    /// Items encountered during the first sweep are captured without being reported as added.
+    initialized: bool,
+}
+
+#[derive(Debug)]
+struct ItemData {
+    position: PositionInSeconds,
+    length: DurationInSeconds,
+    selected: bool,
+    muted: bool,
+    track: Option<MediaTrack>,
+    active_take: Option<MediaItemTake>,
+    last_seen_sweep: u64,
+}
+
+impl ItemData {
+    fn capture(item: Item, sweep: u64) -> ItemData {
+        let reaper = Reaper::get().medium_reaper();
+        ItemData {
+            position: item.position(),
+            length: item.length(),
+            selected: item.is_selected(),
+            muted: item.is_muted(),
+            track: unsafe { reaper.get_media_item_track(item.raw()) },
+            active_take: unsafe { reaper.get_active_take(item.raw()) },
+            last_seen_sweep: sweep,
+        }
+    }
+}
+
+/// Default number of envelopes which are examined per main loop cycle.
+///
+/// Envelope change detection is opt-in because it requires loading envelope state chunks, see
+/// [`ChangeDetectionMiddleware::set_envelope_poll_budget()`].
+pub const DEFAULT_ENVELOPE_POLL_BUDGET: u32 = 0;
+
+/// Keeps envelope fingerprints for detecting envelope changes.
+///
+/// Like items, envelopes are examined round-robin, a limited number per cycle.
+#[derive(Debug, Default)]
+struct EnvelopePollState {
+    project: Option<ReaProject>,
+    /// Envelopes which still need to be examined in the current sweep.
+    pending: Vec<(MediaTrack, Envelope)>,
+    fingerprints: HashMap<TrackEnvelope, EnvelopeFingerprint>,
+}
+
+/// Maximum number of project load/save notifications which are buffered until the next
+/// invocation of [`ChangeDetectionMiddleware::run()`].
+const PROJECT_LIFECYCLE_NOTIFICATION_CAPACITY: usize = 100;
+
+/// Project config extension which forwards project load and save notifications to a
+/// [`ChangeDetectionMiddleware`].
+///
+/// Obtain it via [`ChangeDetectionMiddleware::project_lifecycle_extension()`] and register it via
+/// `ReaperSession::plugin_register_add_project_config()`. Without it, the middleware can't report
+/// [`ChangeEvent::ProjectLoaded`] and [`ChangeEvent::ProjectSaved`].
+#[derive(Debug)]
+pub struct ProjectLifecycleExtension {
+    sender: Sender<ProjectLifecycleNotification>,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum ProjectLifecycleNotification {
+    Loaded(Project),
+    Saved(Project),
+}
+
+impl ProjectLifecycleExtension {
+    fn notify(&self, create_notification: impl FnOnce(Project) -> ProjectLifecycleNotification) {
+        let reaper = Reaper::get();
+        let project = reaper
+            .currently_loading_or_saving_project()
+            .unwrap_or_else(|| reaper.current_project());
+        // If the middleware doesn't run, we rather drop notifications than pile them up.
+        let _ = self.sender.try_send(create_notification(project));
+    }
+}
+
+impl ProjectConfigExtension for ProjectLifecycleExtension {
+    fn save_extension_config(&mut self, args: SaveExtensionConfigArgs) {
+        // Saving as part of creating an undo point is not a project save
+        if args.is_undo {
+            return;
+        }
+        self.notify(ProjectLifecycleNotification::Saved);
+    }
+
+    fn begin_load_project_state(&mut self, args: BeginLoadProjectStateArgs) {
+        // Loading as part of undo/redo is not a project load
+        if args.is_undo {
+            return;
+        }
+        self.notify(ProjectLifecycleNotification::Loaded);
+    }
+}
+
+/// Keeps the last known undo state for detecting undo history changes.
+///
+/// REAPER doesn't expose the undo history itself, just the labels of the next undo and redo step
+/// and a state change count. So undo history changes are inferred from changes of these labels.
+#[derive(Debug)]
+struct UndoState {
+    project: ReaProject,
+    change_count: u32,
+    undo_label: Option<String>,
+    redo_label: Option<String>,
+}
+
+impl UndoState {
+    fn capture(project: Project) -> UndoState {
+        UndoState {
+            project: project.raw(),
+            change_count: project.state_change_count(),
+            undo_label: project
+                .label_of_last_undoable_action()
+                .map(|l| l.into_string()),
+            redo_label: project
+                .label_of_last_redoable_action()
+                .map(|l| l.into_string()),
+        }
+    }
+
+    /// Infers what happened to the undo history since the given old state.
+    ///
+    /// Returns `None` if the labels didn't change. The state change count also moves on changes
+    /// which don't create undo points (e.g. selection changes), so it alone is no indication.
+    /// As a consequence, changes which leave both labels untouched can't be detected, e.g.
+    /// creating an undo point with the same label as the previous one (when there's nothing to
+    /// redo) or undoing/redoing one of several consecutive actions with the same label.
+    fn infer_change(&self, old: &UndoState) -> Option<UndoHistoryChange> {
+        if self.undo_label == old.undo_label && self.redo_label == old.redo_label {
+            return None;
+        }
+        if self.redo_label.is_some() && self.redo_label == old.undo_label {
+            return self
+                .redo_label
+                .clone()
+                .map(UndoHistoryChange::UndoPerformed);
+        }
+        if self.undo_label.is_some() && self.undo_label == old.redo_label {
+            return self
+                .undo_label
+                .clone()
+                .map(UndoHistoryChange::RedoPerformed);
+        }
+        self.undo_label
+            .clone()
+            .map(UndoHistoryChange::UndoPointCreated)
+    }
+}
+
+#[derive(Eq, PartialEq, Debug)]
+enum UndoHistoryChange {
+    UndoPointCreated(String),
+    UndoPerformed(String),
+    RedoPerformed(String),
+}
+
 #[derive(PartialEq)]
 enum State {
     Normal,
@@ -144,6 +366,8 @@ impl Default for ChangeDetectionMiddleware {
         let version = Reaper::get().version();
         let last_active_project = Reaper::get().current_project();
         let reaper_version_5_95 = ReaperVersion::new("5.95");
+        let (project_lifecycle_sender, project_lifecycle_receiver) =
+            crossbeam_channel::bounded(PROJECT_LIFECYCLE_NOTIFICATION_CAPACITY);
         ChangeDetectionMiddleware {
             num_track_set_changes_left_to_be_propagated: Default::default(),
             last_active_project: Cell::new(last_active_project),
@@ -151,6 +375,15 @@ impl Default for ChangeDetectionMiddleware {
                 Reaper::get().global_automation_override(),
             ),
             project_datas: Default::default(),
+            bookmark_datas: Default::default(),
+            item_poll_budget: Cell::new(DEFAULT_ITEM_POLL_BUDGET),
+            item_poll_state: Default::default(),
+            envelope_poll_budget: Cell::new(DEFAULT_ENVELOPE_POLL_BUDGET),
+            envelope_poll_state: Default::default(),
+            undo_state: Default::default(),
+            dirty_state: Default::default(),
+            project_lifecycle_sender,
+            project_lifecycle_receiver,
             // since pre1,
             supports_detection_of_input_fx: version >= reaper_version_5_95,
         }
@@ -174,6 +407,59 @@ impl ChangeDetectionMiddleware {
         self.react_to_track_list_change(Reaper::get().current_project(), handle_change);
     }
 
+    /// Sets the maximum number of items which are examined per invocation of [`run()`].
+    ///
+    /// With a budget of 0, item change detection is paused.
+    ///
+    /// [`run()`]: #method.run
+    pub fn set_item_poll_budget(&self, budget: u32) {
+        self.item_poll_budget.set(budget);
+    }
+
+    /// Sets the maximum number of envelopes which are examined per invocation of [`run()`].
+    ///
+    /// Examining an envelope means loading its state chunk, so this is more expensive than
+    /// examining an item. The default budget is 0, which means envelope change detection is
+    /// disabled. Set it to a small value (e.g. 4) in order to receive envelope change events.
+    ///
+    /// [`run()`]: #method.run
+    pub fn set_envelope_poll_budget(&self, budget: u32) {
+        self.envelope_poll_budget.set(budget);
+    }
+
+    /// Returns a project config extension which needs to be registered in order to get notified
+    /// about loaded and saved projects.
+    ///
+    /// The notifications are turned into change events in [`run()`].
+    ///
+    /// [`run()`]: #method.run
+    pub fn project_lifecycle_extension(&self) -> ProjectLifecycleExtension {
+        ProjectLifecycleExtension {
+            sender: self.project_lifecycle_sender.clone(),
+        }
+    }
+
+    /// Detects project lifecycle, undo history, item, take and envelope changes in the current
+    /// project.
+    ///
+    /// Should be called in each main loop cycle (`ControlSurface::run()`). Each invocation
+    /// examines at most the configured number of items and envelopes, continuing where the last
+    /// invocation stopped, so the cost stays bounded even in large projects. The first sweep
+    /// through a project just captures its items without reporting them. Removed items are
+    /// reported when a sweep has been completed.
+    ///
+    /// Undo history changes are inferred from the labels of the next undo and redo step because
+    /// REAPER doesn't expose the undo history. Changes which don't alter these labels go
+    /// unnoticed, e.g. undoing one of several consecutive actions with the same label.
+    pub fn run(&self, mut handle_change: impl FnMut(ChangeEvent)) {
+        self.process_project_lifecycle_notifications(&mut handle_change);
+        let project = Reaper::get().current_project();
+        self.poll_dirty_state(project, &mut handle_change);
+        self.poll_undo_state(project, &mut handle_change);
+        self.poll_items(project, &mut handle_change);
+        self.poll_envelopes(project, &mut handle_change);
+    }
+
     pub fn process(
         &self,
         event: &ControlSurfaceEvent,
@@ -603,8 +889,10 @@ impl ChangeDetectionMiddleware {
                 }));
             }
             ExtSetProjectMarkerChange(_) => {
+                let project = Reaper::get().current_project();
+                self.detect_bookmark_changes(project, &mut handle_change);
                 handle_change(ChangeEvent::BookmarksChanged(BookmarksChangedEvent {
-                    project: Reaper::get().current_project()
+                    project
                 }));
             }
             CloseNoReset |
@@ -813,6 +1101,280 @@ impl ChangeDetectionMiddleware {
         }
     }
 
+    fn process_project_lifecycle_notifications(&self, handle_change: &mut impl FnMut(ChangeEvent)) {
+        for notification in self.project_lifecycle_receiver.try_iter() {
+            let event = match notification {
+                ProjectLifecycleNotification::Loaded(project) => {
+                    ChangeEvent::ProjectLoaded(ProjectLoadedEvent { project })
+                }
+                ProjectLifecycleNotification::Saved(project) => {
+                    ChangeEvent::ProjectSaved(ProjectSavedEvent { project })
+                }
+            };
+            handle_change(event);
+        }
+    }
+
+    fn poll_dirty_state(&self, project: Project, handle_change: &mut impl FnMut(ChangeEvent)) {
+        let is_dirty = project.is_dirty();
+        let old_state = self.dirty_state.replace(Some((project.raw(), is_dirty)));
+        // After a project switch, we just capture the state
+        if let Some((old_project, was_dirty)) = old_state {
+            if old_project == project.raw() && was_dirty != is_dirty {
+                handle_change(ChangeEvent::ProjectDirtyStateChanged(
+                    ProjectDirtyStateChangedEvent {
+                        project,
+                        new_value: is_dirty,
+                    },
+                ));
+            }
+        }
+    }
+
+    fn poll_undo_state(&self, project: Project, handle_change: &mut impl FnMut(ChangeEvent)) {
+        let mut undo_state = self.undo_state.borrow_mut();
+        let old = match undo_state.take() {
+            Some(s) if s.project == project.raw() => s,
+            _ => {
+                // Project switched, we just capture the state
+                *undo_state = Some(UndoState::capture(project));
+                return;
+            }
+        };
+        // Checking the change count first is cheap and spares us querying the labels
+        if project.state_change_count() == old.change_count {
+            *undo_state = Some(old);
+            return;
+        }
+        let new = UndoState::capture(project);
+        match new.infer_change(&old) {
+            None => {}
+            Some(UndoHistoryChange::UndoPointCreated(label)) => {
+                handle_change(ChangeEvent::UndoPointCreated(UndoPointCreatedEvent {
+                    project,
+                    label,
+                }));
+            }
+            Some(UndoHistoryChange::UndoPerformed(label)) => {
+                handle_change(ChangeEvent::UndoPerformed(UndoPerformedEvent {
+                    project,
+                    label,
+                }));
+            }
+            Some(UndoHistoryChange::RedoPerformed(label)) => {
+                handle_change(ChangeEvent::RedoPerformed(RedoPerformedEvent {
+                    project,
+                    label,
+                }));
+            }
+        }
+        *undo_state = Some(new);
+    }
+
+    fn poll_items(&self, project: Project, handle_change: &mut impl FnMut(ChangeEvent)) {
+        let budget = self.item_poll_budget.get();
+        if budget == 0 {
+            return;
+        }
+        let mut state = self.item_poll_state.borrow_mut();
+        if state.project != Some(project.raw()) {
+            *state = ItemPollState {
+                project: Some(project.raw()),
+                ..Default::default()
+            };
+        }
+        let item_count = project.item_count();
+        if state.next_index >= item_count {
+            self.complete_item_sweep(project, &mut state, handle_change);
+        }
+        // Don't examine the same item twice within one invocation
+        for _ in 0..budget.min(item_count) {
+            if state.next_index >= item_count {
+                break;
+            }
+            let index = state.next_index;
+            state.next_index += 1;
+            let raw_item = match Reaper::get()
+                .medium_reaper()
+                .get_media_item(Proj(project.raw()), index)
+            {
+                None => continue,
+                Some(i) => i,
+            };
+            self.examine_item(project, &mut state, Item::new(raw_item), handle_change);
+        }
+    }
+
+    fn poll_envelopes(&self, project: Project, handle_change: &mut impl FnMut(ChangeEvent)) {
+        let budget = self.envelope_poll_budget.get();
+        if budget == 0 {
+            return;
+        }
+        let mut state = self.envelope_poll_state.borrow_mut();
+        if state.project != Some(project.raw()) {
+            *state = EnvelopePollState {
+                project: Some(project.raw()),
+                ..Default::default()
+            };
+        }
+        if state.pending.is_empty() {
+            // Start a new sweep
+            state.pending = project
+                .master_track()
+                .into_iter()
+                .chain(project.tracks())
+                .flat_map(|t| t.envelopes().map(move |e| (t.raw(), e)))
+                .collect();
+            let existing: HashSet<_> = state.pending.iter().map(|(_, e)| e.raw()).collect();
+            state
+                .fingerprints
+                .retain(|envelope, _| existing.contains(envelope));
+        }
+        for _ in 0..budget {
+            let (media_track, envelope) = match state.pending.pop() {
+                None => break,
+                Some(p) => p,
+            };
+            if !Reaper::get()
+                .medium_reaper()
+                .validate_ptr_2(Proj(project.raw()), envelope.raw())
+            {
+                continue;
+            }
+            let new = match envelope.fingerprint(state.fingerprints.get(&envelope.raw())) {
+                Err(_) => continue,
+                Ok(f) => f,
+            };
+            let old = match state.fingerprints.insert(envelope.raw(), new) {
+                // Just captured
+                None => continue,
+                Some(f) => f,
+            };
+            if new == old {
+                continue;
+            }
+            let track = Track::new(media_track, Some(project.raw()));
+            if new.points_hash != old.points_hash {
+                handle_change(ChangeEvent::EnvelopePointsChanged(
+                    EnvelopePointsChangedEvent {
+                        track: track.clone(),
+                        envelope,
+                    },
+                ));
+            }
+            if new.visible != old.visible {
+                handle_change(ChangeEvent::EnvelopeVisibilityChanged(
+                    EnvelopeVisibilityChangedEvent {
+                        track: track.clone(),
+                        envelope,
+                        new_value: new.visible,
+                    },
+                ));
+            }
+            if new.armed != old.armed {
+                handle_change(ChangeEvent::EnvelopeArmChanged(EnvelopeArmChangedEvent {
+                    track: track.clone(),
+                    envelope,
+                    new_value: new.armed,
+                }));
+            }
+            if new.automation_items_hash != old.automation_items_hash {
+                handle_change(ChangeEvent::AutomationItemsChanged(
+                    AutomationItemsChangedEvent { track, envelope },
+                ));
+            }
+        }
+    }
+
+    fn examine_item(
+        &self,
+        project: Project,
+        state: &mut ItemPollState,
+        item: Item,
+        handle_change: &mut impl FnMut(ChangeEvent),
+    ) {
+        let new = ItemData::capture(item, state.sweep);
+        let old = match state.items.get_mut(&item.raw()) {
+            None => {
+                state.items.insert(item.raw(), new);
+                if state.initialized {
+                    handle_change(ChangeEvent::ItemAdded(ItemAddedEvent { item }));
+                }
+                return;
+            }
+            Some(old) => old,
+        };
+        if new.position != old.position || new.track != old.track {
+            let to_track = |t: Option<MediaTrack>| t.map(|t| Track::new(t, Some(project.raw())));
+            handle_change(ChangeEvent::ItemMoved(ItemMovedEvent {
+                item,
+                old_position: old.position,
+                new_position: new.position,
+                old_track: to_track(old.track),
+                new_track: to_track(new.track),
+            }));
+        }
+        if new.length != old.length {
+            handle_change(ChangeEvent::ItemResized(ItemResizedEvent {
+                item,
+                old_value: old.length,
+                new_value: new.length,
+            }));
+        }
+        if new.selected != old.selected {
+            handle_change(ChangeEvent::ItemSelectedChanged(ItemSelectedChangedEvent {
+                item,
+                old_value: old.selected,
+                new_value: new.selected,
+            }));
+        }
+        if new.muted != old.muted {
+            handle_change(ChangeEvent::ItemMuteChanged(ItemMuteChangedEvent {
+                item,
+                old_value: old.muted,
+                new_value: new.muted,
+            }));
+        }
+        if new.active_take != old.active_take {
+            handle_change(ChangeEvent::ItemActiveTakeChanged(
+                ItemActiveTakeChangedEvent {
+                    item,
+                    old_take: old.active_take.map(Take::new),
+                    new_take: new.active_take.map(Take::new),
+                },
+            ));
+        }
+        *old = new;
+    }
+
+    fn complete_item_sweep(
+        &self,
+        project: Project,
+        state: &mut ItemPollState,
+        handle_change: &mut impl FnMut(ChangeEvent),
+    ) {
+        let sweep = state.sweep;
+        state.items.retain(|raw_item, item_data| {
+            // An item which has been moved to a lower index while the sweep was in progress
+            // might have been skipped, so make sure it's really gone.
+            if item_data.last_seen_sweep == sweep
+                || Reaper::get()
+                    .medium_reaper()
+                    .validate_ptr_2(Proj(project.raw()), *raw_item)
+            {
+                return true;
+            }
+            handle_change(ChangeEvent::ItemRemoved(ItemRemovedEvent {
+                project,
+                item: Item::new(*raw_item),
+            }));
+            false
+        });
+        state.sweep += 1;
+        state.next_index = 0;
+        state.initialized = true;
+    }
+
     fn set_track_list_change(&self, handle_change: impl FnMut(ChangeEvent)) {
         // TODO-low Not multi-project compatible!
         let new_active_project = Reaper::get().current_project();
@@ -834,10 +1396,95 @@ impl ChangeDetectionMiddleware {
             }));
         }
         self.remove_invalid_rea_projects(&mut handle_change);
+        self.bookmark_datas
+            .borrow_mut()
+            .entry(new_active_project.raw())
+            .or_insert_with(|| capture_bookmarks(new_active_project));
         self.detect_track_set_changes(handle_change);
     }
 
+    fn detect_bookmark_changes(
+        &self,
+        project: Project,
+        handle_change: &mut impl FnMut(ChangeEvent),
+    ) {
+        let mut bookmark_datas = self.bookmark_datas.borrow_mut();
+        let old_datas = match bookmark_datas.insert(project.raw(), capture_bookmarks(project)) {
+            // Without previous snapshot we can't tell what has changed
+            None => return,
+            Some(d) => d,
+        };
+        let new_datas = &bookmark_datas[&project.raw()];
+        // Sorted in order to emit events in a deterministic order (markers first, then regions)
+        let mut old_keys: Vec<_> = old_datas.keys().copied().collect();
+        old_keys.sort_unstable();
+        for (bookmark_type, id) in old_keys {
+            let old = &old_datas[&(bookmark_type, id)];
+            let new = match new_datas.get(&(bookmark_type, id)) {
+                None => {
+                    handle_change(ChangeEvent::MarkerRemoved(MarkerRemovedEvent {
+                        project,
+                        bookmark_type,
+                        id,
+                    }));
+                    continue;
+                }
+                Some(new) => new,
+            };
+            if new.position != old.position {
+                handle_change(ChangeEvent::MarkerMoved(MarkerMovedEvent {
+                    project,
+                    bookmark_type,
+                    id,
+                    old_position: old.position,
+                    new_position: new.position,
+                }));
+            }
+            if let (Some(old_end), Some(new_end)) =
+                (old.region_end_position, new.region_end_position)
+            {
+                // Moving a region as a whole is reported as move only. Tolerance because the
+                // length is derived from two positions.
+                let old_length = old_end.get() - old.position.get();
+                let new_length = new_end.get() - new.position.get();
+                if (new_length - old_length).abs() > REGION_LENGTH_TOLERANCE {
+                    handle_change(ChangeEvent::RegionResized(RegionResizedEvent {
+                        project,
+                        id,
+                        old_end_position: old_end,
+                        new_end_position: new_end,
+                    }));
+                }
+            }
+            if new.name != old.name {
+                handle_change(ChangeEvent::MarkerRenamed(MarkerRenamedEvent {
+                    project,
+                    bookmark_type,
+                    id,
+                    old_name: old.name.clone(),
+                    new_name: new.name.clone(),
+                }));
+            }
+        }
+        let mut new_keys: Vec<_> = new_datas.keys().copied().collect();
+        new_keys.sort_unstable();
+        for (bookmark_type, id) in new_keys {
+            if !old_datas.contains_key(&(bookmark_type, id)) {
+                handle_change(ChangeEvent::MarkerAdded(MarkerAddedEvent {
+                    project,
+                    bookmark_type,
+                    id,
+                }));
+            }
+        }
+    }
+
     fn remove_invalid_rea_projects(&self, handle_change: &mut impl FnMut(ChangeEvent)) {
+        self.bookmark_datas.borrow_mut().retain(|rea_project, _| {
+            Reaper::get()
+                .medium_reaper()
+                .validate_ptr_2(CurrentProject, *rea_project)
+        });
         self.project_datas.borrow_mut().retain(|rea_project, _| {
             if Reaper::get()
                 .medium_reaper()
@@ -1229,6 +1876,28 @@ pub enum ChangeEvent {
     RepeatStateChanged(RepeatStateChangedEvent),
     ProjectClosed(ProjectClosedEvent),
     BookmarksChanged(BookmarksChangedEvent),
+    MarkerAdded(MarkerAddedEvent),
+    MarkerRemoved(MarkerRemovedEvent),
+    MarkerMoved(MarkerMovedEvent),
+    MarkerRenamed(MarkerRenamedEvent),
+    RegionResized(RegionResizedEvent),
+    ItemAdded(ItemAddedEvent),
+    ItemRemoved(ItemRemovedEvent),
+    ItemMoved(ItemMovedEvent),
+    ItemResized(ItemResizedEvent),
+    ItemSelectedChanged(ItemSelectedChangedEvent),
+    ItemMuteChanged(ItemMuteChangedEvent),
+    ItemActiveTakeChanged(ItemActiveTakeChangedEvent),
+    EnvelopePointsChanged(EnvelopePointsChangedEvent),
+    EnvelopeVisibilityChanged(EnvelopeVisibilityChangedEvent),
+    EnvelopeArmChanged(EnvelopeArmChangedEvent),
+    AutomationItemsChanged(AutomationItemsChangedEvent),
+    UndoPointCreated(UndoPointCreatedEvent),
+    UndoPerformed(UndoPerformedEvent),
+    RedoPerformed(RedoPerformedEvent),
+    ProjectLoaded(ProjectLoadedEvent),
+    ProjectSaved(ProjectSavedEvent),
+    ProjectDirtyStateChanged(ProjectDirtyStateChangedEvent),
 }
 
 impl ChangeEvent {
@@ -1282,6 +1951,28 @@ impl ChangeEvent {
             ChangeEvent::RepeatStateChanged(evt) => evt.project.is_available(),
             ChangeEvent::ProjectClosed(_) => true,
             ChangeEvent::BookmarksChanged(evt) => evt.project.is_available(),
+            ChangeEvent::MarkerAdded(evt) => evt.project.is_available(),
+            ChangeEvent::MarkerRemoved(evt) => evt.project.is_available(),
+            ChangeEvent::MarkerMoved(evt) => evt.project.is_available(),
+            ChangeEvent::MarkerRenamed(evt) => evt.project.is_available(),
+            ChangeEvent::RegionResized(evt) => evt.project.is_available(),
+            ChangeEvent::ItemAdded(evt) => evt.item.is_available(),
+            ChangeEvent::ItemRemoved(_) => true,
+            ChangeEvent::ItemMoved(evt) => evt.item.is_available(),
+            ChangeEvent::ItemResized(evt) => evt.item.is_available(),
+            ChangeEvent::ItemSelectedChanged(evt) => evt.item.is_available(),
+            ChangeEvent::ItemMuteChanged(evt) => evt.item.is_available(),
+            ChangeEvent::ItemActiveTakeChanged(evt) => evt.item.is_available(),
+            ChangeEvent::EnvelopePointsChanged(evt) => evt.track.is_available(),
+            ChangeEvent::EnvelopeVisibilityChanged(evt) => evt.track.is_available(),
+            ChangeEvent::EnvelopeArmChanged(evt) => evt.track.is_available(),
+            ChangeEvent::AutomationItemsChanged(evt) => evt.track.is_available(),
+            ChangeEvent::UndoPointCreated(evt) => evt.project.is_available(),
+            ChangeEvent::UndoPerformed(evt) => evt.project.is_available(),
+            ChangeEvent::RedoPerformed(evt) => evt.project.is_available(),
+            ChangeEvent::ProjectLoaded(evt) => evt.project.is_available(),
+            ChangeEvent::ProjectSaved(evt) => evt.project.is_available(),
+            ChangeEvent::ProjectDirtyStateChanged(evt) => evt.project.is_available(),
         }
     }
 }
@@ -1511,3 +2202,239 @@ pub struct ProjectClosedEvent {
 pub struct BookmarksChangedEvent {
     pub project: Project,
 }
+
+/// Fired for markers and regions.
+#[derive(Clone, Debug)]
+pub struct MarkerAddedEvent {
+    pub project: Project,
+    pub bookmark_type: BookmarkType,
+    pub id: BookmarkId,
+}
+
+/// Fired for markers and regions.
+#[derive(Clone, Debug)]
+pub struct MarkerRemovedEvent {
+    pub project: Project,
+    pub bookmark_type: BookmarkType,
+    pub id: BookmarkId,
+}
+
+/// Fired for markers and regions. For regions, the position is the start position.
+#[derive(Clone, Debug)]
+pub struct MarkerMovedEvent {
+    pub project: Project,
+    pub bookmark_type: BookmarkType,
+    pub id: BookmarkId,
+    pub old_position: PositionInSeconds,
+    pub new_position: PositionInSeconds,
+}
+
+/// Fired for markers and regions.
+#[derive(Clone, Debug)]
+pub struct MarkerRenamedEvent {
+    pub project: Project,
+    pub bookmark_type: BookmarkType,
+    pub id: BookmarkId,
+    pub old_name: String,
+    pub new_name: String,
+}
+
+/// Fired if the length of a region has changed.
+///
+/// If the start position has changed as well, a [`MarkerMovedEvent`] is fired in addition.
+#[derive(Clone, Debug)]
+pub struct RegionResizedEvent {
+    pub project: Project,
+    pub id: BookmarkId,
+    pub old_end_position: PositionInSeconds,
+    pub new_end_position: PositionInSeconds,
+}
+
+#[derive(Clone, Debug)]
+pub struct ItemAddedEvent {
+    pub item: Item,
+}
+
+#[derive(Clone, Debug)]
+pub struct ItemRemovedEvent {
+    pub project: Project,
+    pub item: Item,
+}
+
+/// Also fired if the item has been moved to another track.
+#[derive(Clone, Debug)]
+pub struct ItemMovedEvent {
+    pub item: Item,
+    pub old_position: PositionInSeconds,
+    pub new_position: PositionInSeconds,
+    pub old_track: Option<Track>,
+    pub new_track: Option<Track>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ItemResizedEvent {
+    pub item: Item,
+    pub old_value: DurationInSeconds,
+    pub new_value: DurationInSeconds,
+}
+
+#[derive(Clone, Debug)]
+pub struct ItemSelectedChangedEvent {
+    pub item: Item,
+    pub old_value: bool,
+    pub new_value: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct ItemMuteChangedEvent {
+    pub item: Item,
+    pub old_value: bool,
+    pub new_value: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct ItemActiveTakeChangedEvent {
+    pub item: Item,
+    pub old_take: Option<Take>,
+    pub new_take: Option<Take>,
+}
+
+/// Fired if envelope points have been added, removed, moved, changed or (de)selected.
+#[derive(Clone, Debug)]
+pub struct EnvelopePointsChangedEvent {
+    pub track: Track,
+    pub envelope: Envelope,
+}
+
+#[derive(Clone, Debug)]
+pub struct EnvelopeVisibilityChangedEvent {
+    pub track: Track,
+    pub envelope: Envelope,
+    pub new_value: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct EnvelopeArmChangedEvent {
+    pub track: Track,
+    pub envelope: Envelope,
+    pub new_value: bool,
+}
+
+/// Fired if automation items of the envelope have been added, removed, moved or changed.
+#[derive(Clone, Debug)]
+pub struct AutomationItemsChangedEvent {
+    pub track: Track,
+    pub envelope: Envelope,
+}
+
+/// Fired when the label of the next undo step changes due to a new undo point.
+///
+/// Consecutive undo points with the same label are reported only once.
+#[derive(Clone, Debug)]
+pub struct UndoPointCreatedEvent {
+    pub project: Project,
+    pub label: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct UndoPerformedEvent {
+    pub project: Project,
+    /// Label of the action which has been undone.
+    pub label: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct RedoPerformedEvent {
+    pub project: Project,
+    /// Label of the action which has been redone.
+    pub label: String,
+}
+
+/// Fired when a project has been loaded (this includes creating a new project).
+///
+/// Only reported if the [`ProjectLifecycleExtension`] is registered.
+#[derive(Clone, Debug)]
+pub struct ProjectLoadedEvent {
+    pub project: Project,
+}
+
+/// Fired when a project has been saved.
+///
+/// Only reported if the [`ProjectLifecycleExtension`] is registered.
+#[derive(Clone, Debug)]
+pub struct ProjectSavedEvent {
+    pub project: Project,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProjectDirtyStateChangedEvent {
+    pub project: Project,
+    pub new_value: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr::NonNull;
+
+    fn undo_state(undo_label: Option<&str>, redo_label: Option<&str>) -> UndoState {
+        UndoState {
+            project: NonNull::dangling(),
+            change_count: 0,
+            undo_label: undo_label.map(String::from),
+            redo_label: redo_label.map(String::from),
+        }
+    }
+
+    #[test]
+    fn infer_undo_point_created() {
+        // Given
+        let old = undo_state(Some("A"), None);
+        // When
+        let change = undo_state(Some("B"), None).infer_change(&old);
+        // Then
+        assert_eq!(
+            change,
+            Some(UndoHistoryChange::UndoPointCreated("B".into()))
+        );
+    }
+
+    #[test]
+    fn infer_undo_and_redo_performed() {
+        // Given
+        let initial = undo_state(Some("B"), None);
+        let undone = undo_state(Some("A"), Some("B"));
+        // When
+        let undo_change = undone.infer_change(&initial);
+        let redo_change = initial.infer_change(&undone);
+        // Then
+        assert_eq!(
+            undo_change,
+            Some(UndoHistoryChange::UndoPerformed("B".into()))
+        );
+        assert_eq!(
+            redo_change,
+            Some(UndoHistoryChange::RedoPerformed("B".into()))
+        );
+    }
+
+    #[test]
+    fn infer_undo_of_action_with_same_label() {
+        // Given
+        let old = undo_state(Some("A"), None);
+        // When
+        let change = undo_state(Some("A"), Some("A")).infer_change(&old);
+        // Then
+        assert_eq!(change, Some(UndoHistoryChange::UndoPerformed("A".into())));
+    }
+
+    #[test]
+    fn ignore_changes_without_label_change() {
+        // Given
+        let old = undo_state(Some("A"), None);
+        // When
+        let change = undo_state(Some("A"), None).infer_change(&old);
+        // Then
+        assert_eq!(change, None);
+    }
+}
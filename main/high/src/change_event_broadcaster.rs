@@ -0,0 +1,257 @@
+use crate::ChangeEvent;
+use futures::channel::mpsc;
+use futures::Stream;
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter};
+
+/// Number of events which each stream buffers by default, see
+/// [`ChangeEventBroadcaster::with_stream_capacity()`].
+pub const DEFAULT_CHANGE_EVENT_STREAM_CAPACITY: usize = 1000;
+
+/// Called with each broadcast event. Returns `false` if the subscribed stream has been dropped.
+type Subscriber = Box<dyn FnMut(&ChangeEvent) -> bool>;
+
+/// Distributes change events to any number of local `futures` streams.
+///
+/// This is an alternative to the reactive streams offered by *reaper-rx* for consumers who prefer
+/// `async`/`await`. Feed it with the events emitted by [`ChangeDetectionMiddleware`] and consume
+/// the streams in futures, e.g. spawned via
+/// [`FutureSupport::spawn_in_main_thread_from_main_thread()`]:
+///
+/// ```no_run
+/// use futures::StreamExt;
+/// use reaper_high::{ChangeDetectionMiddleware, ChangeEventBroadcaster};
+///
+/// let middleware = ChangeDetectionMiddleware::new();
+/// let broadcaster = ChangeEventBroadcaster::new();
+/// let mut volume_changes = broadcaster.track_volume_changed();
+/// let future = async move {
+///     while let Some(event) = volume_changes.next().await {
+///         println!("New volume: {:?}", event.new_value);
+///     }
+/// };
+/// // In ControlSurface::run()
+/// middleware.run(|event| broadcaster.broadcast(event));
+/// ```
+///
+/// All streams end when the broadcaster is dropped. A dropped stream is unsubscribed with the next
+/// broadcast.
+///
+/// Each stream buffers a limited number of events. If a stream isn't consumed fast enough, further
+/// events for this stream are dropped until there's space again. High-rate events such as volume
+/// changes during automation playback fill the buffer quickly, so consider putting a
+/// [`ChangeEventCoalescer`] in front of the broadcaster.
+pub struct ChangeEventBroadcaster {
+    stream_capacity: usize,
+    subscribers: RefCell<Vec<Subscriber>>,
+}
+
+impl Default for ChangeEventBroadcaster {
+    fn default() -> Self {
+        Self::with_stream_capacity(DEFAULT_CHANGE_EVENT_STREAM_CAPACITY)
+    }
+}
+
+impl Debug for ChangeEventBroadcaster {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Subscribers are closures which don't implement Debug.
+        f.debug_struct("ChangeEventBroadcaster")
+            .field("stream_capacity", &self.stream_capacity)
+            .field("subscriber_count", &self.subscribers.borrow().len())
+            .finish()
+    }
+}
+
+impl ChangeEventBroadcaster {
+    pub fn new() -> ChangeEventBroadcaster {
+        Default::default()
+    }
+
+    /// Creates a broadcaster whose streams buffer the given number of events each.
+    pub fn with_stream_capacity(capacity: usize) -> ChangeEventBroadcaster {
+        ChangeEventBroadcaster {
+            stream_capacity: capacity,
+            subscribers: Default::default(),
+        }
+    }
+
+    /// Sends the given event to all streams interested in it.
+    ///
+    /// Supposed to be called from the `handle_change` callback passed to
+    /// [`ChangeDetectionMiddleware`].
+    pub fn broadcast(&self, event: ChangeEvent) {
+        self.subscribers
+            .borrow_mut()
+            .retain_mut(|subscriber| subscriber(&event));
+    }
+
+    /// Returns a stream of all change events.
+    pub fn events(&self) -> impl Stream<Item = ChangeEvent> {
+        self.filtered_events(|event| Some(event.clone()))
+    }
+
+    /// Returns a stream of the change events picked by the given function.
+    ///
+    /// Events for which the function returns `None` are not buffered at all.
+    pub fn filtered_events<T: 'static>(
+        &self,
+        mut pick: impl FnMut(&ChangeEvent) -> Option<T> + 'static,
+    ) -> impl Stream<Item = T> {
+        let (mut sender, receiver) = mpsc::channel(self.stream_capacity);
+        let subscriber = move |event: &ChangeEvent| match pick(event) {
+            // Dropping events is okay if the stream is too slow
+            Some(value) => match sender.try_send(value) {
+                Ok(_) => true,
+                Err(e) => !e.is_disconnected(),
+            },
+            None => !sender.is_closed(),
+        };
+        self.subscribers.borrow_mut().push(Box::new(subscriber));
+        receiver
+    }
+}
+
+/// Generates one stream method per change event category.
+macro_rules! change_event_streams {
+    ($($name:ident => $variant:ident($event:ident),)*) => {
+        impl ChangeEventBroadcaster {
+            $(
+                pub fn $name(&self) -> impl Stream<Item = crate::$event> {
+                    self.filtered_events(|event| match event {
+                        ChangeEvent::$variant(e) => Some(e.clone()),
+                        _ => None,
+                    })
+                }
+            )*
+        }
+    };
+}
+
+change_event_streams! {
+    project_switched => ProjectSwitched(ProjectSwitchedEvent),
+    track_volume_changed => TrackVolumeChanged(TrackVolumeChangedEvent),
+    track_pan_changed => TrackPanChanged(TrackPanChangedEvent),
+    track_route_volume_changed => TrackRouteVolumeChanged(TrackRouteVolumeChangedEvent),
+    track_route_pan_changed => TrackRoutePanChanged(TrackRoutePanChangedEvent),
+    track_added => TrackAdded(TrackAddedEvent),
+    track_removed => TrackRemoved(TrackRemovedEvent),
+    tracks_reordered => TracksReordered(TracksReorderedEvent),
+    receive_count_changed => ReceiveCountChanged(ReceiveCountChangedEvent),
+    hardware_output_send_count_changed => HardwareOutputSendCountChanged(HardwareOutputSendCountChangedEvent),
+    track_send_count_changed => TrackSendCountChanged(TrackSendCountChangedEvent),
+    track_name_changed => TrackNameChanged(TrackNameChangedEvent),
+    track_input_changed => TrackInputChanged(TrackInputChangedEvent),
+    track_input_monitoring_changed => TrackInputMonitoringChanged(TrackInputMonitoringChangedEvent),
+    track_arm_changed => TrackArmChanged(TrackArmChangedEvent),
+    track_mute_changed => TrackMuteChanged(TrackMuteChangedEvent),
+    track_solo_changed => TrackSoloChanged(TrackSoloChangedEvent),
+    track_selected_changed => TrackSelectedChanged(TrackSelectedChangedEvent),
+    track_automation_mode_changed => TrackAutomationModeChanged(TrackAutomationModeChangedEvent),
+    fx_added => FxAdded(FxAddedEvent),
+    fx_removed => FxRemoved(FxRemovedEvent),
+    fx_enabled_changed => FxEnabledChanged(FxEnabledChangedEvent),
+    fx_opened => FxOpened(FxOpenedEvent),
+    fx_closed => FxClosed(FxClosedEvent),
+    fx_focused => FxFocused(FxFocusedEvent),
+    fx_reordered => FxReordered(FxReorderedEvent),
+    fx_parameter_value_changed => FxParameterValueChanged(FxParameterValueChangedEvent),
+    fx_preset_changed => FxPresetChanged(FxPresetChangedEvent),
+    master_tempo_changed => MasterTempoChanged(MasterTempoChangedEvent),
+    master_playrate_changed => MasterPlayrateChanged(MasterPlayrateChangedEvent),
+    global_automation_override_changed => GlobalAutomationOverrideChanged(GlobalAutomationOverrideChangedEvent),
+    play_state_changed => PlayStateChanged(PlayStateChangedEvent),
+    repeat_state_changed => RepeatStateChanged(RepeatStateChangedEvent),
+    project_closed => ProjectClosed(ProjectClosedEvent),
+    bookmarks_changed => BookmarksChanged(BookmarksChangedEvent),
+    marker_added => MarkerAdded(MarkerAddedEvent),
+    marker_removed => MarkerRemoved(MarkerRemovedEvent),
+    marker_moved => MarkerMoved(MarkerMovedEvent),
+    marker_renamed => MarkerRenamed(MarkerRenamedEvent),
+    region_resized => RegionResized(RegionResizedEvent),
+    item_added => ItemAdded(ItemAddedEvent),
+    item_removed => ItemRemoved(ItemRemovedEvent),
+    item_moved => ItemMoved(ItemMovedEvent),
+    item_resized => ItemResized(ItemResizedEvent),
+    item_selected_changed => ItemSelectedChanged(ItemSelectedChangedEvent),
+    item_mute_changed => ItemMuteChanged(ItemMuteChangedEvent),
+    item_active_take_changed => ItemActiveTakeChanged(ItemActiveTakeChangedEvent),
+    envelope_points_changed => EnvelopePointsChanged(EnvelopePointsChangedEvent),
+    envelope_visibility_changed => EnvelopeVisibilityChanged(EnvelopeVisibilityChangedEvent),
+    envelope_arm_changed => EnvelopeArmChanged(EnvelopeArmChangedEvent),
+    automation_items_changed => AutomationItemsChanged(AutomationItemsChangedEvent),
+    undo_point_created => UndoPointCreated(UndoPointCreatedEvent),
+    undo_performed => UndoPerformed(UndoPerformedEvent),
+    redo_performed => RedoPerformed(RedoPerformedEvent),
+    project_loaded => ProjectLoaded(ProjectLoadedEvent),
+    project_saved => ProjectSaved(ProjectSavedEvent),
+    project_dirty_state_changed => ProjectDirtyStateChanged(ProjectDirtyStateChangedEvent),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BookmarksChangedEvent, Project, ProjectClosedEvent};
+    use futures::{FutureExt, StreamExt};
+    use std::ptr::NonNull;
+
+    fn project_closed() -> ChangeEvent {
+        ChangeEvent::ProjectClosed(ProjectClosedEvent {
+            project: Project::new(NonNull::dangling()),
+        })
+    }
+
+    fn bookmarks_changed() -> ChangeEvent {
+        ChangeEvent::BookmarksChanged(BookmarksChangedEvent {
+            project: Project::new(NonNull::dangling()),
+        })
+    }
+
+    #[test]
+    fn category_streams_only_receive_their_events() {
+        // Given
+        let broadcaster = ChangeEventBroadcaster::new();
+        let bookmark_changes = broadcaster.bookmarks_changed();
+        let all_events = broadcaster.events();
+        // When
+        broadcaster.broadcast(project_closed());
+        broadcaster.broadcast(bookmarks_changed());
+        broadcaster.broadcast(bookmarks_changed());
+        drop(broadcaster);
+        // Then
+        let bookmark_changes: Option<Vec<_>> = bookmark_changes.collect().now_or_never();
+        let all_events: Option<Vec<_>> = all_events.collect().now_or_never();
+        assert_eq!(bookmark_changes.map(|events| events.len()), Some(2));
+        assert_eq!(all_events.map(|events| events.len()), Some(3));
+    }
+
+    #[test]
+    fn drop_events_if_stream_is_full() {
+        // Given
+        let broadcaster = ChangeEventBroadcaster::with_stream_capacity(1);
+        let all_events = broadcaster.events();
+        // When
+        for _ in 0..10 {
+            broadcaster.broadcast(project_closed());
+        }
+        let subscriber_count = broadcaster.subscribers.borrow().len();
+        drop(broadcaster);
+        // Then
+        let all_events: Option<Vec<_>> = all_events.collect().now_or_never();
+        assert_eq!(subscriber_count, 1);
+        // The channel capacity includes one slot per sender
+        assert_eq!(all_events.map(|events| events.len()), Some(2));
+    }
+
+    #[test]
+    fn dropped_streams_are_unsubscribed() {
+        // Given
+        let broadcaster = ChangeEventBroadcaster::new();
+        let bookmark_changes = broadcaster.bookmarks_changed();
+        let _all_events = broadcaster.events();
+        // When
+        drop(bookmark_changes);
+        broadcaster.broadcast(project_closed());
+        // Then
+        assert_eq!(broadcaster.subscribers.borrow().len(), 1);
+    }
+}
@@ -0,0 +1,476 @@
+use crate::{ChangeEvent, Fx, Project, Track};
+use reaper_medium::TrackSendDirection;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Decides when coalesced change events are delivered.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum CoalescingMode {
+    /// Delivers only the latest event per target once per main loop cycle.
+    PerCycle,
+    /// Delivers at most one event per target within the given interval.
+    ///
+    /// The first change of a target is delivered immediately. Changes within the interval are
+    /// coalesced and delivered as soon as the interval has elapsed, so the latest value is never
+    /// lost.
+    MinInterval(Duration),
+}
+
+/// Coalesces high-rate change events such as volume, pan and FX parameter value changes.
+///
+/// When REAPER reads automation, the corresponding events are emitted at a very high rate. This
+/// layer sits between [`ChangeDetectionMiddleware`] and the subscribers:
+///
+/// ```no_run
+/// use reaper_high::{ChangeDetectionMiddleware, ChangeEvent, ChangeEventCoalescer};
+/// use reaper_high::CoalescingMode;
+/// # use reaper_high::ControlSurfaceEvent;
+/// # let event: ControlSurfaceEvent = todo!();
+/// let middleware = ChangeDetectionMiddleware::new();
+/// let coalescer = ChangeEventCoalescer::new(CoalescingMode::PerCycle);
+/// let handle_change = |e: ChangeEvent| println!("{:?}", e);
+/// // In ControlSurface::handle_event()
+/// middleware.process(&event, |e| coalescer.process(e, handle_change));
+/// // In ControlSurface::run()
+/// middleware.run(|e| coalescer.process(e, handle_change));
+/// coalescer.run(handle_change);
+/// ```
+///
+/// All other events are passed through immediately. A coalesced event carries the new value of the
+/// latest change. Event types which also report an old value (e.g. track volume) keep the one of
+/// the first change. It's marked as touched if any of the coalesced changes was a touch.
+///
+/// Structural events (adding, removing or reordering tracks or FX, switching or closing projects)
+/// first flush the pending events of all targets they might affect, so subscribers never see a
+/// parameter change after the removal of its FX, for example. Because delivery is deferred in
+/// general, the target might still not exist anymore when the event arrives, which can be checked
+/// with [`ChangeEvent::is_still_valid()`].
+#[derive(Debug)]
+pub struct ChangeEventCoalescer {
+    mode: CoalescingMode,
+    next_sequence_number: Cell<u64>,
+    pending_events: RefCell<HashMap<CoalescingKey, PendingEvent>>,
+    last_delivery_times: RefCell<HashMap<CoalescingKey, Instant>>,
+}
+
+/// Target of a coalescable change event.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum CoalescingKey {
+    TrackVolume(Track),
+    TrackPan(Track),
+    TrackRouteVolume(Track, TrackSendDirection, u32),
+    TrackRoutePan(Track, TrackSendDirection, u32),
+    FxParameterValue(Fx, u32),
+    MasterTempo(Project),
+    MasterPlayrate(Project),
+}
+
+impl CoalescingKey {
+    /// Returns `None` if the given event is not coalescable.
+    fn from_event(event: &ChangeEvent) -> Option<CoalescingKey> {
+        use ChangeEvent::*;
+        let key = match event {
+            TrackVolumeChanged(e) => CoalescingKey::TrackVolume(e.track.clone()),
+            TrackPanChanged(e) => CoalescingKey::TrackPan(e.track.clone()),
+            TrackRouteVolumeChanged(e) => CoalescingKey::TrackRouteVolume(
+                e.route.track().clone(),
+                e.route.direction(),
+                e.route.index(),
+            ),
+            TrackRoutePanChanged(e) => CoalescingKey::TrackRoutePan(
+                e.route.track().clone(),
+                e.route.direction(),
+                e.route.index(),
+            ),
+            FxParameterValueChanged(e) => {
+                CoalescingKey::FxParameterValue(e.parameter.fx().clone(), e.parameter.index())
+            }
+            MasterTempoChanged(e) => CoalescingKey::MasterTempo(e.project),
+            MasterPlayrateChanged(e) => CoalescingKey::MasterPlayrate(e.project),
+            _ => return None,
+        };
+        Some(key)
+    }
+
+    /// Returns whether pending events of this target must be delivered before the given event
+    /// because it restructures the context of this target (e.g. it shifts route or FX indexes).
+    fn is_affected_by(&self, event: &ChangeEvent) -> bool {
+        use ChangeEvent::*;
+        match event {
+            ProjectSwitched(e) => self.project() == Some(e.old_project),
+            ProjectClosed(e) => self.project() == Some(e.project),
+            TrackAdded(e) => self.project() == Some(e.track.project()),
+            TrackRemoved(e) => self.project() == Some(e.track.project()),
+            TracksReordered(e) => self.project() == Some(e.project),
+            FxAdded(e) => self.fx_track().is_some() && self.fx_track() == e.fx.track(),
+            FxRemoved(e) => self.fx_track().is_some() && self.fx_track() == e.fx.track(),
+            FxReordered(e) => self.fx_track() == Some(&e.track),
+            _ => false,
+        }
+    }
+
+    fn project(&self) -> Option<Project> {
+        use CoalescingKey::*;
+        match self {
+            TrackVolume(t) | TrackPan(t) | TrackRouteVolume(t, ..) | TrackRoutePan(t, ..) => {
+                Some(t.project())
+            }
+            FxParameterValue(fx, _) => fx.project(),
+            MasterTempo(p) | MasterPlayrate(p) => Some(*p),
+        }
+    }
+
+    /// Returns the track whose FX chain contains the target FX parameter.
+    fn fx_track(&self) -> Option<&Track> {
+        match self {
+            CoalescingKey::FxParameterValue(fx, _) => fx.track(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PendingEvent {
+    /// For delivering coalesced events in the order in which they occurred first.
+    sequence_number: u64,
+    event: ChangeEvent,
+}
+
+impl ChangeEventCoalescer {
+    pub fn new(mode: CoalescingMode) -> ChangeEventCoalescer {
+        ChangeEventCoalescer {
+            mode,
+            next_sequence_number: Cell::new(0),
+            pending_events: Default::default(),
+            last_delivery_times: Default::default(),
+        }
+    }
+
+    pub fn mode(&self) -> CoalescingMode {
+        self.mode
+    }
+
+    /// Discards all coalesced events which haven't been delivered yet.
+    pub fn reset(&self) {
+        self.pending_events.borrow_mut().clear();
+        self.last_delivery_times.borrow_mut().clear();
+    }
+
+    /// Coalesces the given event or passes it through immediately.
+    ///
+    /// Supposed to be called from the `handle_change` callback passed to
+    /// [`ChangeDetectionMiddleware`].
+    pub fn process(&self, event: ChangeEvent, mut handle_change: impl FnMut(ChangeEvent)) {
+        let key = match CoalescingKey::from_event(&event) {
+            None => {
+                self.flush_events_affected_by(&event, &mut handle_change);
+                handle_change(event);
+                return;
+            }
+            Some(k) => k,
+        };
+        {
+            let mut pending_events = self.pending_events.borrow_mut();
+            if let Some(pending_event) = pending_events.get_mut(&key) {
+                merge_into(&mut pending_event.event, event);
+                return;
+            }
+            let deliver_immediately = match self.mode {
+                CoalescingMode::PerCycle => false,
+                CoalescingMode::MinInterval(interval) => {
+                    let now = Instant::now();
+                    let mut last_delivery_times = self.last_delivery_times.borrow_mut();
+                    let is_due = last_delivery_times
+                        .get(&key)
+                        .map(|t| now.duration_since(*t) >= interval)
+                        .unwrap_or(true);
+                    if is_due {
+                        last_delivery_times.insert(key.clone(), now);
+                    }
+                    is_due
+                }
+            };
+            if !deliver_immediately {
+                let sequence_number = self.next_sequence_number.get();
+                self.next_sequence_number.set(sequence_number + 1);
+                pending_events.insert(
+                    key,
+                    PendingEvent {
+                        sequence_number,
+                        event,
+                    },
+                );
+                return;
+            }
+        }
+        handle_change(event);
+    }
+
+    /// Delivers all coalesced events which are due.
+    ///
+    /// Should be called in each main loop cycle (`ControlSurface::run()`), after
+    /// [`ChangeDetectionMiddleware::run()`].
+    pub fn run(&self, mut handle_change: impl FnMut(ChangeEvent)) {
+        let mut due_events = self.take_due_events();
+        due_events.sort_by_key(|e| e.sequence_number);
+        for pending_event in due_events {
+            handle_change(pending_event.event);
+        }
+    }
+
+    /// Delivers the pending events of all targets which are affected by the given structural event.
+    fn flush_events_affected_by(
+        &self,
+        event: &ChangeEvent,
+        handle_change: &mut impl FnMut(ChangeEvent),
+    ) {
+        let mut affected_events: Vec<_> = {
+            let mut pending_events = self.pending_events.borrow_mut();
+            let affected_keys: Vec<_> = pending_events
+                .keys()
+                .filter(|key| key.is_affected_by(event))
+                .cloned()
+                .collect();
+            let now = Instant::now();
+            let mut last_delivery_times = self.last_delivery_times.borrow_mut();
+            affected_keys
+                .into_iter()
+                .filter_map(|key| {
+                    let pending_event = pending_events.remove(&key)?;
+                    if let CoalescingMode::MinInterval(_) = self.mode {
+                        last_delivery_times.insert(key, now);
+                    }
+                    Some(pending_event)
+                })
+                .collect()
+        };
+        affected_events.sort_by_key(|e| e.sequence_number);
+        for pending_event in affected_events {
+            handle_change(pending_event.event);
+        }
+    }
+
+    fn take_due_events(&self) -> Vec<PendingEvent> {
+        let mut pending_events = self.pending_events.borrow_mut();
+        let interval = match self.mode {
+            CoalescingMode::PerCycle => {
+                return pending_events.drain().map(|(_, e)| e).collect();
+            }
+            CoalescingMode::MinInterval(interval) => interval,
+        };
+        let now = Instant::now();
+        let mut last_delivery_times = self.last_delivery_times.borrow_mut();
+        let due_keys: Vec<_> = pending_events
+            .keys()
+            .filter(|key| {
+                last_delivery_times
+                    .get(key)
+                    .map(|t| now.duration_since(*t) >= interval)
+                    .unwrap_or(true)
+            })
+            .cloned()
+            .collect();
+        let due_events = due_keys
+            .into_iter()
+            .filter_map(|key| {
+                let pending_event = pending_events.remove(&key)?;
+                last_delivery_times.insert(key, now);
+                Some(pending_event)
+            })
+            .collect();
+        // Forget targets which haven't changed for a while
+        last_delivery_times
+            .retain(|key, t| pending_events.contains_key(key) || now.duration_since(*t) < interval);
+        due_events
+    }
+}
+
+/// Merges the given newer event into the pending one (both must have the same coalescing key).
+fn merge_into(pending: &mut ChangeEvent, newer: ChangeEvent) {
+    use ChangeEvent::*;
+    match (pending, newer) {
+        (TrackVolumeChanged(p), TrackVolumeChanged(n)) => {
+            p.touched |= n.touched;
+            p.new_value = n.new_value;
+        }
+        (TrackPanChanged(p), TrackPanChanged(n)) => {
+            p.touched |= n.touched;
+            p.new_value = n.new_value;
+        }
+        (TrackRouteVolumeChanged(p), TrackRouteVolumeChanged(n)) => {
+            p.touched |= n.touched;
+            p.new_value = n.new_value;
+        }
+        (TrackRoutePanChanged(p), TrackRoutePanChanged(n)) => {
+            p.touched |= n.touched;
+            p.new_value = n.new_value;
+        }
+        (FxParameterValueChanged(p), FxParameterValueChanged(n)) => {
+            p.touched |= n.touched;
+            p.new_value = n.new_value;
+        }
+        (MasterTempoChanged(p), MasterTempoChanged(n)) => {
+            p.touched |= n.touched;
+            p.new_value = n.new_value;
+        }
+        (MasterPlayrateChanged(p), MasterPlayrateChanged(n)) => {
+            p.touched |= n.touched;
+            p.new_value = n.new_value;
+        }
+        (pending, newer) => *pending = newer,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Guid, MasterTempoChangedEvent, PlayStateChangedEvent, TrackAddedEvent, TrackRemovedEvent,
+        TrackVolumeChangedEvent,
+    };
+    use reaper_low::raw::GUID;
+    use reaper_medium::{Bpm, PlayState, ReaperVolumeValue};
+    use std::ptr::NonNull;
+
+    fn project() -> Project {
+        Project::new(NonNull::dangling())
+    }
+
+    fn tempo_changed(bpm: f64, touched: bool) -> ChangeEvent {
+        ChangeEvent::MasterTempoChanged(MasterTempoChangedEvent {
+            project: project(),
+            touched,
+            new_value: Bpm::new(bpm),
+        })
+    }
+
+    fn track(project: Project, n: u32) -> Track {
+        let guid = Guid::new(GUID {
+            Data1: n,
+            Data2: 0,
+            Data3: 0,
+            Data4: [0; 8],
+        });
+        Track::from_guid(project, guid)
+    }
+
+    fn volume_changed(track: &Track, volume: f64) -> ChangeEvent {
+        ChangeEvent::TrackVolumeChanged(TrackVolumeChangedEvent {
+            touched: false,
+            track: track.clone(),
+            old_value: ReaperVolumeValue::ZERO_DB,
+            new_value: ReaperVolumeValue::new(volume),
+        })
+    }
+
+    fn play_state_changed() -> ChangeEvent {
+        ChangeEvent::PlayStateChanged(PlayStateChangedEvent {
+            project: project(),
+            new_value: PlayState {
+                is_playing: true,
+                is_paused: false,
+                is_recording: false,
+            },
+        })
+    }
+
+    fn delivered_tempos(events: &[ChangeEvent]) -> Vec<(f64, bool)> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                ChangeEvent::MasterTempoChanged(e) => Some((e.new_value.get(), e.touched)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn coalesce_per_cycle() {
+        // Given
+        let coalescer = ChangeEventCoalescer::new(CoalescingMode::PerCycle);
+        let mut events = Vec::new();
+        // When
+        coalescer.process(tempo_changed(100.0, true), |e| events.push(e));
+        coalescer.process(play_state_changed(), |e| events.push(e));
+        coalescer.process(tempo_changed(110.0, false), |e| events.push(e));
+        let events_before_run = events.len();
+        coalescer.run(|e| events.push(e));
+        coalescer.run(|e| events.push(e));
+        // Then
+        assert_eq!(events_before_run, 1);
+        assert_eq!(events.len(), 2);
+        assert_eq!(delivered_tempos(&events), vec![(110.0, true)]);
+    }
+
+    #[test]
+    fn deliver_pending_events_before_structural_change() {
+        // Given
+        let coalescer = ChangeEventCoalescer::new(CoalescingMode::PerCycle);
+        let track = track(project(), 1);
+        let mut other_project_storage = 0u8;
+        let other_project = Project::new(NonNull::from(&mut other_project_storage).cast());
+        let mut events = Vec::new();
+        // When
+        coalescer.process(volume_changed(&track, 0.5), |e| events.push(e));
+        coalescer.process(tempo_changed(100.0, false), |e| events.push(e));
+        coalescer.process(
+            ChangeEvent::TrackAdded(TrackAddedEvent {
+                track: self::track(other_project, 2),
+            }),
+            |e| events.push(e),
+        );
+        let events_after_unrelated_change = events.len();
+        coalescer.process(
+            ChangeEvent::TrackRemoved(TrackRemovedEvent {
+                track: track.clone(),
+            }),
+            |e| events.push(e),
+        );
+        coalescer.run(|e| events.push(e));
+        // Then
+        assert_eq!(events_after_unrelated_change, 1);
+        let kinds: Vec<_> = events
+            .iter()
+            .map(|e| match e {
+                ChangeEvent::TrackAdded(_) => "added",
+                ChangeEvent::TrackVolumeChanged(_) => "volume",
+                ChangeEvent::MasterTempoChanged(_) => "tempo",
+                ChangeEvent::TrackRemoved(_) => "removed",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(kinds, vec!["added", "volume", "tempo", "removed"]);
+    }
+
+    #[test]
+    fn coalesce_within_min_interval() {
+        // Given
+        let coalescer =
+            ChangeEventCoalescer::new(CoalescingMode::MinInterval(Duration::from_secs(3600)));
+        let mut events = Vec::new();
+        // When
+        coalescer.process(tempo_changed(100.0, false), |e| events.push(e));
+        coalescer.process(tempo_changed(110.0, false), |e| events.push(e));
+        coalescer.process(tempo_changed(120.0, false), |e| events.push(e));
+        coalescer.run(|e| events.push(e));
+        // Then
+        assert_eq!(delivered_tempos(&events), vec![(100.0, false)]);
+    }
+
+    #[test]
+    fn deliver_immediately_without_min_interval() {
+        // Given
+        let coalescer = ChangeEventCoalescer::new(CoalescingMode::MinInterval(Duration::ZERO));
+        let mut events = Vec::new();
+        // When
+        coalescer.process(tempo_changed(100.0, false), |e| events.push(e));
+        coalescer.process(tempo_changed(110.0, false), |e| events.push(e));
+        coalescer.run(|e| events.push(e));
+        // Then
+        assert_eq!(
+            delivered_tempos(&events),
+            vec![(100.0, false), (110.0, false)]
+        );
+    }
+}
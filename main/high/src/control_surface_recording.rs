@@ -0,0 +1,887 @@
+use crate::{get_media_track_guid, ControlSurfaceEvent, ControlSurfaceMiddleware, Guid, Project};
+use crate::{Reaper, ReaperResult};
+use reaper_medium::{
+    AutomationMode, Bpm, ExtResetArgs, ExtSetBpmAndPlayRateArgs, ExtSetFocusedFxArgs,
+    ExtSetFxChangeArgs, ExtSetFxEnabledArgs, ExtSetFxOpenArgs, ExtSetFxParamArgs,
+    ExtSetInputMonitorArgs, ExtSetLastTouchedFxArgs, ExtSetPanExArgs,
+    ExtSetProjectMarkerChangeArgs, ExtSetRecvPanArgs, ExtSetRecvVolumeArgs, ExtSetSendPanArgs,
+    ExtSetSendVolumeArgs, ExtSupportsExtendedTouchArgs, ExtTrackFxPresetChangedArgs,
+    GetTouchStateArgs, InputMonitoringMode, IsKeyDownArgs, MediaTrack, OnTrackSelectionArgs, Pan,
+    PlaybackSpeedFactor, QualifiedFxLocation, ReaperNormalizedFxParamValue, ReaperPanValue,
+    ReaperStr, ReaperStringArg, ReaperVolumeValue, ReaperWidthValue, SetAutoModeArgs,
+    SetPlayStateArgs, SetRepeatStateArgs, SetSurfaceMuteArgs, SetSurfacePanArgs,
+    SetSurfaceRecArmArgs, SetSurfaceSelectedArgs, SetSurfaceSoloArgs, SetSurfaceVolumeArgs,
+    SetTrackTitleArgs, TrackFxChainType, TrackFxLocation, TrackLocation,
+    VersionDependentFxLocation, VersionDependentTrackFxLocation,
+};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt::Debug;
+use std::io::{BufRead, Write};
+use std::time::Instant;
+
+/// One line of a control surface event log.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ControlSurfaceLogEntry {
+    /// Milliseconds since the recording started.
+    pub time_millis: u64,
+    pub event: LoggedControlSurfaceEvent,
+}
+
+/// Reference to a track which stays valid across REAPER sessions (in contrast to `MediaTrack`).
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum LoggedTrack {
+    Master,
+    Normal(Guid),
+}
+
+/// A [`ControlSurfaceEvent`] in a form which can be written to a log.
+///
+/// Track pointers are replaced with track references and medium-level types with plain values.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum LoggedControlSurfaceEvent {
+    CloseNoReset,
+    SetTrackListChange,
+    SetSurfaceVolume {
+        track: LoggedTrack,
+        volume: f64,
+    },
+    SetSurfacePan {
+        track: LoggedTrack,
+        pan: f64,
+    },
+    SetSurfaceMute {
+        track: LoggedTrack,
+        is_mute: bool,
+    },
+    SetSurfaceSelected {
+        track: LoggedTrack,
+        is_selected: bool,
+    },
+    SetSurfaceSolo {
+        track: LoggedTrack,
+        is_solo: bool,
+    },
+    SetSurfaceRecArm {
+        track: LoggedTrack,
+        is_armed: bool,
+    },
+    SetPlayState {
+        is_playing: bool,
+        is_paused: bool,
+        is_recording: bool,
+    },
+    SetRepeatState {
+        is_enabled: bool,
+    },
+    SetTrackTitle {
+        track: LoggedTrack,
+        name: String,
+    },
+    SetAutoMode {
+        /// Raw automation mode.
+        mode: i32,
+    },
+    ResetCachedVolPanStates,
+    OnTrackSelection {
+        track: LoggedTrack,
+    },
+    ExtSetInputMonitor {
+        track: LoggedTrack,
+        /// Raw input monitoring mode.
+        mode: i32,
+    },
+    ExtSetFxParam {
+        track: LoggedTrack,
+        fx_index: u32,
+        param_index: u32,
+        param_value: f64,
+    },
+    ExtSetFxParamRecFx {
+        track: LoggedTrack,
+        fx_index: u32,
+        param_index: u32,
+        param_value: f64,
+    },
+    ExtSetFxEnabled {
+        track: LoggedTrack,
+        fx_location: LoggedTrackFxLocation,
+        is_enabled: bool,
+    },
+    ExtSetSendVolume {
+        track: LoggedTrack,
+        send_index: u32,
+        volume: f64,
+    },
+    ExtSetSendPan {
+        track: LoggedTrack,
+        send_index: u32,
+        pan: f64,
+    },
+    ExtSetRecvVolume {
+        track: LoggedTrack,
+        receive_index: u32,
+        volume: f64,
+    },
+    ExtSetRecvPan {
+        track: LoggedTrack,
+        receive_index: u32,
+        pan: f64,
+    },
+    ExtSetPanExt {
+        track: LoggedTrack,
+        pan: LoggedPan,
+    },
+    ExtSetFocusedFx {
+        fx_location: Option<LoggedQualifiedFxLocation>,
+    },
+    ExtSetLastTouchedFx {
+        fx_location: Option<LoggedQualifiedFxLocation>,
+    },
+    ExtSetFxOpen {
+        track: LoggedTrack,
+        fx_location: LoggedTrackFxLocation,
+        is_open: bool,
+    },
+    ExtSetFxChange {
+        track: LoggedTrack,
+        /// `None` in REAPER < 5.95.
+        is_input_fx_chain: Option<bool>,
+    },
+    ExtSetBpmAndPlayRate {
+        tempo: Option<f64>,
+        play_rate: Option<f64>,
+    },
+    ExtTrackFxPresetChanged {
+        track: LoggedTrack,
+        /// Raw track FX location.
+        fx_location: i32,
+    },
+    ExtReset,
+    ExtSetProjectMarkerChange,
+}
+
+/// Loggable form of [`VersionDependentTrackFxLocation`].
+///
+/// [`VersionDependentTrackFxLocation`]: https://docs.rs/reaper-medium
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+pub enum LoggedTrackFxLocation {
+    /// FX index in REAPER < 5.95 (doesn't distinguish between normal and input FX chain).
+    Old(u32),
+    /// Raw track FX location in REAPER >= 5.95.
+    New(i32),
+}
+
+/// Loggable form of [`QualifiedFxLocation`].
+///
+/// [`QualifiedFxLocation`]: https://docs.rs/reaper-medium
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct LoggedQualifiedFxLocation {
+    pub track: LoggedTrack,
+    pub fx_location: LoggedFxLocation,
+}
+
+/// Loggable form of [`VersionDependentFxLocation`].
+///
+/// [`VersionDependentFxLocation`]: https://docs.rs/reaper-medium
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+pub enum LoggedFxLocation {
+    TakeFx { item_index: u32, fx_index: u32 },
+    TrackFx(LoggedTrackFxLocation),
+}
+
+/// Loggable form of [`Pan`].
+///
+/// [`Pan`]: https://docs.rs/reaper-medium
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum LoggedPan {
+    BalanceV1(f64),
+    BalanceV4(f64),
+    StereoPan {
+        pan: f64,
+        width: f64,
+    },
+    DualPan {
+        left: f64,
+        right: f64,
+    },
+    /// A pan mode unknown to *reaper-rs*. Can't be replayed.
+    Unknown,
+}
+
+/// Writes control surface events to a log, one JSON object per line.
+///
+/// The writer is not flushed after each event. Pass a `LineWriter` if the log must survive a
+/// crash.
+#[derive(Debug)]
+pub struct ControlSurfaceEventRecorder<W: Write> {
+    writer: RefCell<W>,
+    start: Instant,
+}
+
+impl<W: Write> ControlSurfaceEventRecorder<W> {
+    /// Creates a recorder. Timestamps are relative to the time of creation.
+    pub fn new(writer: W) -> ControlSurfaceEventRecorder<W> {
+        ControlSurfaceEventRecorder {
+            writer: RefCell::new(writer),
+            start: Instant::now(),
+        }
+    }
+
+    /// Writes the given event to the log.
+    ///
+    /// Must be called in the main thread because track GUIDs are queried.
+    pub fn record(&self, event: &ControlSurfaceEvent) -> Result<(), Box<dyn Error>> {
+        let entry = ControlSurfaceLogEntry {
+            time_millis: self.start.elapsed().as_millis() as u64,
+            event: LoggedControlSurfaceEvent::from_event(event),
+        };
+        let mut writer = self.writer.borrow_mut();
+        serde_json::to_writer(&mut *writer, &entry)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+}
+
+/// Middleware which records each event before passing it on to the wrapped middleware.
+#[derive(Debug)]
+pub struct RecordingMiddleware<M: ControlSurfaceMiddleware + Debug, W: Write + Debug> {
+    inner: M,
+    recorder: ControlSurfaceEventRecorder<W>,
+}
+
+impl<M: ControlSurfaceMiddleware + Debug, W: Write + Debug> RecordingMiddleware<M, W> {
+    pub fn new(inner: M, writer: W) -> RecordingMiddleware<M, W> {
+        RecordingMiddleware {
+            inner,
+            recorder: ControlSurfaceEventRecorder::new(writer),
+        }
+    }
+
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut M {
+        &mut self.inner
+    }
+}
+
+impl<M: ControlSurfaceMiddleware + Debug, W: Write + Debug> ControlSurfaceMiddleware
+    for RecordingMiddleware<M, W>
+{
+    fn run(&mut self) {
+        self.inner.run();
+    }
+
+    fn handle_event(&self, event: ControlSurfaceEvent) -> bool {
+        if let Err(e) = self.recorder.record(&event) {
+            slog::warn!(Reaper::get().logger(), "Couldn't record control surface event";
+                "error" => %e,
+            );
+        }
+        self.inner.handle_event(event)
+    }
+
+    fn get_type_string(&self) -> Option<&ReaperStr> {
+        self.inner.get_type_string()
+    }
+
+    fn get_desc_string(&self) -> Option<&ReaperStr> {
+        self.inner.get_desc_string()
+    }
+
+    fn get_config_string(&self) -> Option<&ReaperStr> {
+        self.inner.get_config_string()
+    }
+
+    fn get_touch_state(&self, args: GetTouchStateArgs) -> bool {
+        self.inner.get_touch_state(args)
+    }
+
+    fn is_key_down(&self, args: IsKeyDownArgs) -> bool {
+        self.inner.is_key_down(args)
+    }
+
+    fn ext_supports_extended_touch(&self, args: ExtSupportsExtendedTouchArgs) -> i32 {
+        self.inner.ext_supports_extended_touch(args)
+    }
+}
+
+/// Feeds recorded control surface events into a consumer, e.g. a middleware.
+///
+/// Events are replayed in order and as fast as possible, timestamps are ignored. Tracks are looked
+/// up by GUID in the given project, so the project from the bug report should be loaded.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ControlSurfaceEventReplayer {
+    entries: Vec<ControlSurfaceLogEntry>,
+}
+
+impl ControlSurfaceEventReplayer {
+    pub fn new(entries: Vec<ControlSurfaceLogEntry>) -> ControlSurfaceEventReplayer {
+        ControlSurfaceEventReplayer { entries }
+    }
+
+    /// Reads a log as written by [`ControlSurfaceEventRecorder`]. Empty lines are ignored.
+    ///
+    /// [`ControlSurfaceEventRecorder`]: struct.ControlSurfaceEventRecorder.html
+    pub fn read(reader: impl BufRead) -> Result<ControlSurfaceEventReplayer, Box<dyn Error>> {
+        let mut entries = vec![];
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(serde_json::from_str(&line)?);
+        }
+        Ok(ControlSurfaceEventReplayer { entries })
+    }
+
+    pub fn entries(&self) -> &[ControlSurfaceLogEntry] {
+        &self.entries
+    }
+
+    /// Passes each recorded event to the given function.
+    ///
+    /// Stops at the first event which can't be restored, e.g. because a track doesn't exist in
+    /// the given project.
+    pub fn replay(
+        &self,
+        project: Project,
+        mut handle_event: impl FnMut(ControlSurfaceEvent<'static>),
+    ) -> ReaperResult<()> {
+        for entry in &self.entries {
+            let event = entry.event.to_event(project)?;
+            handle_event(event);
+        }
+        Ok(())
+    }
+
+    /// Passes each recorded event to the given middleware.
+    pub fn replay_into(
+        &self,
+        project: Project,
+        middleware: &impl ControlSurfaceMiddleware,
+    ) -> ReaperResult<()> {
+        self.replay(project, |event| {
+            middleware.handle_event(event);
+        })
+    }
+}
+
+impl LoggedTrack {
+    /// Must be called in the main thread.
+    pub fn from_media_track(track: MediaTrack) -> LoggedTrack {
+        let location = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .get_set_media_track_info_get_track_number(track)
+        };
+        if location == Some(TrackLocation::MasterTrack) {
+            LoggedTrack::Master
+        } else {
+            LoggedTrack::Normal(get_media_track_guid(track))
+        }
+    }
+
+    pub fn resolve(self, project: Project) -> ReaperResult<MediaTrack> {
+        let track = match self {
+            LoggedTrack::Master => project.master_track()?,
+            LoggedTrack::Normal(guid) => project.track_by_guid(&guid)?,
+        };
+        if !track.is_available() {
+            return Err("logged track doesn't exist in project".into());
+        }
+        Ok(track.raw())
+    }
+}
+
+impl LoggedControlSurfaceEvent {
+    /// Must be called in the main thread.
+    pub fn from_event(event: &ControlSurfaceEvent) -> LoggedControlSurfaceEvent {
+        use ControlSurfaceEvent as E;
+        use LoggedControlSurfaceEvent::*;
+        let t = LoggedTrack::from_media_track;
+        match event {
+            E::CloseNoReset => CloseNoReset,
+            E::SetTrackListChange => SetTrackListChange,
+            E::SetSurfaceVolume(a) => SetSurfaceVolume {
+                track: t(a.track),
+                volume: a.volume.get(),
+            },
+            E::SetSurfacePan(a) => SetSurfacePan {
+                track: t(a.track),
+                pan: a.pan.get(),
+            },
+            E::SetSurfaceMute(a) => SetSurfaceMute {
+                track: t(a.track),
+                is_mute: a.is_mute,
+            },
+            E::SetSurfaceSelected(a) => SetSurfaceSelected {
+                track: t(a.track),
+                is_selected: a.is_selected,
+            },
+            E::SetSurfaceSolo(a) => SetSurfaceSolo {
+                track: t(a.track),
+                is_solo: a.is_solo,
+            },
+            E::SetSurfaceRecArm(a) => SetSurfaceRecArm {
+                track: t(a.track),
+                is_armed: a.is_armed,
+            },
+            E::SetPlayState(a) => SetPlayState {
+                is_playing: a.is_playing,
+                is_paused: a.is_paused,
+                is_recording: a.is_recording,
+            },
+            E::SetRepeatState(a) => SetRepeatState {
+                is_enabled: a.is_enabled,
+            },
+            E::SetTrackTitle(a) => SetTrackTitle {
+                track: t(a.track),
+                name: a.name.to_str().to_string(),
+            },
+            E::SetAutoMode(a) => SetAutoMode {
+                mode: a.mode.to_raw(),
+            },
+            E::ResetCachedVolPanStates => ResetCachedVolPanStates,
+            E::OnTrackSelection(a) => OnTrackSelection { track: t(a.track) },
+            E::ExtSetInputMonitor(a) => ExtSetInputMonitor {
+                track: t(a.track),
+                mode: a.mode.to_raw(),
+            },
+            E::ExtSetFxParam(a) => ExtSetFxParam {
+                track: t(a.track),
+                fx_index: a.fx_index,
+                param_index: a.param_index,
+                param_value: a.param_value.get(),
+            },
+            E::ExtSetFxParamRecFx(a) => ExtSetFxParamRecFx {
+                track: t(a.track),
+                fx_index: a.fx_index,
+                param_index: a.param_index,
+                param_value: a.param_value.get(),
+            },
+            E::ExtSetFxEnabled(a) => ExtSetFxEnabled {
+                track: t(a.track),
+                fx_location: a.fx_location.into(),
+                is_enabled: a.is_enabled,
+            },
+            E::ExtSetSendVolume(a) => ExtSetSendVolume {
+                track: t(a.track),
+                send_index: a.send_index,
+                volume: a.volume.get(),
+            },
+            E::ExtSetSendPan(a) => ExtSetSendPan {
+                track: t(a.track),
+                send_index: a.send_index,
+                pan: a.pan.get(),
+            },
+            E::ExtSetRecvVolume(a) => ExtSetRecvVolume {
+                track: t(a.track),
+                receive_index: a.receive_index,
+                volume: a.volume.get(),
+            },
+            E::ExtSetRecvPan(a) => ExtSetRecvPan {
+                track: t(a.track),
+                receive_index: a.receive_index,
+                pan: a.pan.get(),
+            },
+            E::ExtSetPanExt(a) => ExtSetPanExt {
+                track: t(a.track),
+                pan: a.pan.into(),
+            },
+            E::ExtSetFocusedFx(a) => ExtSetFocusedFx {
+                fx_location: a.fx_location.map(LoggedQualifiedFxLocation::from_location),
+            },
+            E::ExtSetLastTouchedFx(a) => ExtSetLastTouchedFx {
+                fx_location: a.fx_location.map(LoggedQualifiedFxLocation::from_location),
+            },
+            E::ExtSetFxOpen(a) => ExtSetFxOpen {
+                track: t(a.track),
+                fx_location: a.fx_location.into(),
+                is_open: a.is_open,
+            },
+            E::ExtSetFxChange(a) => ExtSetFxChange {
+                track: t(a.track),
+                is_input_fx_chain: a.fx_chain_type.map(|c| c == TrackFxChainType::InputFxChain),
+            },
+            E::ExtSetBpmAndPlayRate(a) => ExtSetBpmAndPlayRate {
+                tempo: a.tempo.map(|t| t.get()),
+                play_rate: a.play_rate.map(|r| r.get()),
+            },
+            E::ExtTrackFxPresetChanged(a) => ExtTrackFxPresetChanged {
+                track: t(a.track),
+                fx_location: a.fx_location.to_raw(),
+            },
+            E::ExtReset(_) => ExtReset,
+            E::ExtSetProjectMarkerChange(_) => ExtSetProjectMarkerChange,
+        }
+    }
+
+    /// Restores the original event, looking up tracks in the given project.
+    pub fn to_event(&self, project: Project) -> ReaperResult<ControlSurfaceEvent<'static>> {
+        use ControlSurfaceEvent as E;
+        use LoggedControlSurfaceEvent::*;
+        let t = |track: &LoggedTrack| track.resolve(project);
+        let event = match self {
+            CloseNoReset => E::CloseNoReset,
+            SetTrackListChange => E::SetTrackListChange,
+            SetSurfaceVolume { track, volume } => E::SetSurfaceVolume(SetSurfaceVolumeArgs {
+                track: t(track)?,
+                volume: volume_value(*volume)?,
+            }),
+            SetSurfacePan { track, pan } => E::SetSurfacePan(SetSurfacePanArgs {
+                track: t(track)?,
+                pan: pan_value(*pan)?,
+            }),
+            SetSurfaceMute { track, is_mute } => E::SetSurfaceMute(SetSurfaceMuteArgs {
+                track: t(track)?,
+                is_mute: *is_mute,
+            }),
+            SetSurfaceSelected { track, is_selected } => {
+                E::SetSurfaceSelected(SetSurfaceSelectedArgs {
+                    track: t(track)?,
+                    is_selected: *is_selected,
+                })
+            }
+            SetSurfaceSolo { track, is_solo } => E::SetSurfaceSolo(SetSurfaceSoloArgs {
+                track: t(track)?,
+                is_solo: *is_solo,
+            }),
+            SetSurfaceRecArm { track, is_armed } => E::SetSurfaceRecArm(SetSurfaceRecArmArgs {
+                track: t(track)?,
+                is_armed: *is_armed,
+            }),
+            SetPlayState {
+                is_playing,
+                is_paused,
+                is_recording,
+            } => E::SetPlayState(SetPlayStateArgs {
+                is_playing: *is_playing,
+                is_paused: *is_paused,
+                is_recording: *is_recording,
+            }),
+            SetRepeatState { is_enabled } => E::SetRepeatState(SetRepeatStateArgs {
+                is_enabled: *is_enabled,
+            }),
+            SetTrackTitle { track, name } => E::SetTrackTitle(SetTrackTitleArgs {
+                track: t(track)?,
+                name: Cow::Owned(
+                    ReaperStringArg::from(name.as_str())
+                        .into_inner()
+                        .into_owned(),
+                ),
+            }),
+            SetAutoMode { mode } => E::SetAutoMode(SetAutoModeArgs {
+                mode: AutomationMode::from_raw(*mode),
+            }),
+            ResetCachedVolPanStates => E::ResetCachedVolPanStates,
+            OnTrackSelection { track } => {
+                E::OnTrackSelection(OnTrackSelectionArgs { track: t(track)? })
+            }
+            ExtSetInputMonitor { track, mode } => E::ExtSetInputMonitor(ExtSetInputMonitorArgs {
+                track: t(track)?,
+                mode: InputMonitoringMode::from_raw(*mode),
+            }),
+            ExtSetFxParam {
+                track,
+                fx_index,
+                param_index,
+                param_value,
+            } => E::ExtSetFxParam(ExtSetFxParamArgs {
+                track: t(track)?,
+                fx_index: *fx_index,
+                param_index: *param_index,
+                param_value: ReaperNormalizedFxParamValue::new(*param_value),
+            }),
+            ExtSetFxParamRecFx {
+                track,
+                fx_index,
+                param_index,
+                param_value,
+            } => E::ExtSetFxParamRecFx(ExtSetFxParamArgs {
+                track: t(track)?,
+                fx_index: *fx_index,
+                param_index: *param_index,
+                param_value: ReaperNormalizedFxParamValue::new(*param_value),
+            }),
+            ExtSetFxEnabled {
+                track,
+                fx_location,
+                is_enabled,
+            } => E::ExtSetFxEnabled(ExtSetFxEnabledArgs {
+                track: t(track)?,
+                fx_location: (*fx_location).into(),
+                is_enabled: *is_enabled,
+            }),
+            ExtSetSendVolume {
+                track,
+                send_index,
+                volume,
+            } => E::ExtSetSendVolume(ExtSetSendVolumeArgs {
+                track: t(track)?,
+                send_index: *send_index,
+                volume: volume_value(*volume)?,
+            }),
+            ExtSetSendPan {
+                track,
+                send_index,
+                pan,
+            } => E::ExtSetSendPan(ExtSetSendPanArgs {
+                track: t(track)?,
+                send_index: *send_index,
+                pan: pan_value(*pan)?,
+            }),
+            ExtSetRecvVolume {
+                track,
+                receive_index,
+                volume,
+            } => E::ExtSetRecvVolume(ExtSetRecvVolumeArgs {
+                track: t(track)?,
+                receive_index: *receive_index,
+                volume: volume_value(*volume)?,
+            }),
+            ExtSetRecvPan {
+                track,
+                receive_index,
+                pan,
+            } => E::ExtSetRecvPan(ExtSetRecvPanArgs {
+                track: t(track)?,
+                receive_index: *receive_index,
+                pan: pan_value(*pan)?,
+            }),
+            ExtSetPanExt { track, pan } => E::ExtSetPanExt(ExtSetPanExArgs {
+                track: t(track)?,
+                pan: pan.to_pan()?,
+            }),
+            ExtSetFocusedFx { fx_location } => E::ExtSetFocusedFx(ExtSetFocusedFxArgs {
+                fx_location: restore_fx_location(fx_location, project)?,
+            }),
+            ExtSetLastTouchedFx { fx_location } => {
+                E::ExtSetLastTouchedFx(ExtSetLastTouchedFxArgs {
+                    fx_location: restore_fx_location(fx_location, project)?,
+                })
+            }
+            ExtSetFxOpen {
+                track,
+                fx_location,
+                is_open,
+            } => E::ExtSetFxOpen(ExtSetFxOpenArgs {
+                track: t(track)?,
+                fx_location: (*fx_location).into(),
+                is_open: *is_open,
+            }),
+            ExtSetFxChange {
+                track,
+                is_input_fx_chain,
+            } => E::ExtSetFxChange(ExtSetFxChangeArgs {
+                track: t(track)?,
+                fx_chain_type: is_input_fx_chain.map(|is_input| {
+                    if is_input {
+                        TrackFxChainType::InputFxChain
+                    } else {
+                        TrackFxChainType::NormalFxChain
+                    }
+                }),
+            }),
+            ExtSetBpmAndPlayRate { tempo, play_rate } => {
+                E::ExtSetBpmAndPlayRate(ExtSetBpmAndPlayRateArgs {
+                    tempo: tempo
+                        .map(Bpm::try_from)
+                        .transpose()
+                        .map_err(|_| "logged tempo is invalid")?,
+                    play_rate: play_rate
+                        .map(PlaybackSpeedFactor::try_from)
+                        .transpose()
+                        .map_err(|_| "logged play rate is invalid")?,
+                })
+            }
+            ExtTrackFxPresetChanged { track, fx_location } => {
+                E::ExtTrackFxPresetChanged(ExtTrackFxPresetChangedArgs {
+                    track: t(track)?,
+                    fx_location: TrackFxLocation::from_raw(*fx_location),
+                })
+            }
+            ExtReset => E::ExtReset(ExtResetArgs),
+            ExtSetProjectMarkerChange => {
+                E::ExtSetProjectMarkerChange(ExtSetProjectMarkerChangeArgs)
+            }
+        };
+        Ok(event)
+    }
+}
+
+impl LoggedQualifiedFxLocation {
+    fn from_location(location: QualifiedFxLocation) -> LoggedQualifiedFxLocation {
+        let fx_location = match location.fx_location {
+            VersionDependentFxLocation::TakeFx {
+                item_index,
+                fx_index,
+            } => LoggedFxLocation::TakeFx {
+                item_index,
+                fx_index,
+            },
+            VersionDependentFxLocation::TrackFx(l) => LoggedFxLocation::TrackFx(l.into()),
+        };
+        LoggedQualifiedFxLocation {
+            track: LoggedTrack::from_media_track(location.track),
+            fx_location,
+        }
+    }
+
+    fn to_location(self, project: Project) -> ReaperResult<QualifiedFxLocation> {
+        let fx_location = match self.fx_location {
+            LoggedFxLocation::TakeFx {
+                item_index,
+                fx_index,
+            } => VersionDependentFxLocation::TakeFx {
+                item_index,
+                fx_index,
+            },
+            LoggedFxLocation::TrackFx(l) => VersionDependentFxLocation::TrackFx(l.into()),
+        };
+        let location = QualifiedFxLocation {
+            track: self.track.resolve(project)?,
+            fx_location,
+        };
+        Ok(location)
+    }
+}
+
+impl From<VersionDependentTrackFxLocation> for LoggedTrackFxLocation {
+    fn from(l: VersionDependentTrackFxLocation) -> Self {
+        match l {
+            VersionDependentTrackFxLocation::Old(i) => LoggedTrackFxLocation::Old(i),
+            VersionDependentTrackFxLocation::New(l) => LoggedTrackFxLocation::New(l.to_raw()),
+        }
+    }
+}
+
+impl From<LoggedTrackFxLocation> for VersionDependentTrackFxLocation {
+    fn from(l: LoggedTrackFxLocation) -> Self {
+        match l {
+            LoggedTrackFxLocation::Old(i) => VersionDependentTrackFxLocation::Old(i),
+            LoggedTrackFxLocation::New(raw) => {
+                VersionDependentTrackFxLocation::New(TrackFxLocation::from_raw(raw))
+            }
+        }
+    }
+}
+
+impl From<Pan> for LoggedPan {
+    fn from(pan: Pan) -> Self {
+        match pan {
+            Pan::BalanceV1(p) => LoggedPan::BalanceV1(p.get()),
+            Pan::BalanceV4(p) => LoggedPan::BalanceV4(p.get()),
+            Pan::StereoPan { pan, width } => LoggedPan::StereoPan {
+                pan: pan.get(),
+                width: width.get(),
+            },
+            Pan::DualPan { left, right } => LoggedPan::DualPan {
+                left: left.get(),
+                right: right.get(),
+            },
+            Pan::Unknown(_) => LoggedPan::Unknown,
+        }
+    }
+}
+
+impl LoggedPan {
+    fn to_pan(self) -> ReaperResult<Pan> {
+        let pan = match self {
+            LoggedPan::BalanceV1(p) => Pan::BalanceV1(pan_value(p)?),
+            LoggedPan::BalanceV4(p) => Pan::BalanceV4(pan_value(p)?),
+            LoggedPan::StereoPan { pan, width } => Pan::StereoPan {
+                pan: pan_value(pan)?,
+                width: ReaperWidthValue::try_from(width).map_err(|_| "logged width is invalid")?,
+            },
+            LoggedPan::DualPan { left, right } => Pan::DualPan {
+                left: pan_value(left)?,
+                right: pan_value(right)?,
+            },
+            LoggedPan::Unknown => return Err("logged pan mode is unknown".into()),
+        };
+        Ok(pan)
+    }
+}
+
+fn restore_fx_location(
+    location: &Option<LoggedQualifiedFxLocation>,
+    project: Project,
+) -> ReaperResult<Option<QualifiedFxLocation>> {
+    location.map(|l| l.to_location(project)).transpose()
+}
+
+fn volume_value(value: f64) -> ReaperResult<ReaperVolumeValue> {
+    let volume = ReaperVolumeValue::try_from(value).map_err(|_| "logged volume is invalid")?;
+    Ok(volume)
+}
+
+fn pan_value(value: f64) -> ReaperResult<ReaperPanValue> {
+    let pan = ReaperPanValue::try_from(value).map_err(|_| "logged pan is invalid")?;
+    Ok(pan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_line_round_trip() {
+        // Given
+        let entries = vec![
+            ControlSurfaceLogEntry {
+                time_millis: 0,
+                event: LoggedControlSurfaceEvent::SetTrackListChange,
+            },
+            ControlSurfaceLogEntry {
+                time_millis: 15,
+                event: LoggedControlSurfaceEvent::SetSurfaceVolume {
+                    track: LoggedTrack::Master,
+                    volume: 0.5,
+                },
+            },
+            ControlSurfaceLogEntry {
+                time_millis: 31,
+                event: LoggedControlSurfaceEvent::ExtSetFocusedFx {
+                    fx_location: Some(LoggedQualifiedFxLocation {
+                        track: LoggedTrack::Master,
+                        fx_location: LoggedFxLocation::TrackFx(LoggedTrackFxLocation::New(
+                            0x0100_0002,
+                        )),
+                    }),
+                },
+            },
+        ];
+        // When
+        let text: String = entries
+            .iter()
+            .map(|e| serde_json::to_string(e).unwrap() + "\n")
+            .collect();
+        let replayer =
+            ControlSurfaceEventReplayer::read(format!("\n{}\n", text).as_bytes()).unwrap();
+        // Then
+        assert_eq!(text.lines().count(), 3);
+        assert_eq!(
+            text.lines().nth(1).unwrap(),
+            r#"{"time_millis":15,"event":{"type":"SetSurfaceVolume","track":"Master","volume":0.5}}"#
+        );
+        assert_eq!(replayer.entries(), entries.as_slice());
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert!(volume_value(f64::NAN).is_err());
+        assert!(pan_value(1.5).is_err());
+        assert!(LoggedPan::Unknown.to_pan().is_err());
+        assert!(ControlSurfaceEventReplayer::read("{".as_bytes()).is_err());
+    }
+}
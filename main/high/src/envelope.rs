@@ -0,0 +1,190 @@
+use crate::{Chunk, Reaper};
+use reaper_medium::{ChunkCacheHint, TrackEnvelope};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Buffer size used for loading an envelope chunk whose size is not known yet.
+const INITIAL_ENVELOPE_FINGERPRINT_CHUNK_SIZE: u32 = 4096;
+
+/// Maximum envelope chunk size which is considered when detecting envelope changes.
+///
+/// Changes beyond that size (in envelopes with a huge number of points) go unnoticed.
+pub(crate) const MAX_ENVELOPE_FINGERPRINT_CHUNK_SIZE: u32 = 1_000_000;
+
+/// An automation envelope of a track.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Envelope {
+    raw: TrackEnvelope,
+}
+
+impl Envelope {
+    pub fn new(raw: TrackEnvelope) -> Envelope {
+        Envelope { raw }
+    }
+
+    pub fn raw(self) -> TrackEnvelope {
+        self.raw
+    }
+
+    pub fn name(self) -> Result<String, &'static str> {
+        let name = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .get_envelope_name(self.raw, 256)
+                .map_err(|_| "couldn't get envelope name")?
+        };
+        Ok(name.into_string())
+    }
+
+    pub fn chunk(
+        self,
+        max_chunk_size: u32,
+        cache_hint: ChunkCacheHint,
+    ) -> Result<Chunk, &'static str> {
+        let chunk_content = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .get_envelope_state_chunk(self.raw, max_chunk_size, cache_hint)
+                .map_err(|_| "Couldn't load envelope chunk")?
+        };
+        Ok(chunk_content.into())
+    }
+
+    /// Loads the envelope chunk and condenses it into a fingerprint.
+    ///
+    /// The buffer starts small (or at the size of the previous fingerprint, if given) and is only
+    /// grown if the chunk got truncated, so that envelopes with few points stay cheap to poll.
+    pub(crate) fn fingerprint(
+        self,
+        previous: Option<&EnvelopeFingerprint>,
+    ) -> Result<EnvelopeFingerprint, &'static str> {
+        let mut buffer_size = previous
+            .map(|f| f.chunk_size.saturating_add(f.chunk_size / 4))
+            .unwrap_or(0)
+            .clamp(
+                INITIAL_ENVELOPE_FINGERPRINT_CHUNK_SIZE,
+                MAX_ENVELOPE_FINGERPRINT_CHUNK_SIZE,
+            );
+        loop {
+            let chunk_content = unsafe {
+                Reaper::get()
+                    .medium_reaper()
+                    .get_envelope_state_chunk(self.raw, buffer_size, ChunkCacheHint::NormalMode)
+                    .map_err(|_| "Couldn't load envelope chunk")?
+            };
+            let chunk_content = chunk_content.to_str();
+            // The buffer needs room for the terminating nul byte
+            let is_truncated = chunk_content.len() + 1 >= buffer_size as usize;
+            if !is_truncated || buffer_size >= MAX_ENVELOPE_FINGERPRINT_CHUNK_SIZE {
+                return Ok(EnvelopeFingerprint::from_chunk_content(chunk_content));
+            }
+            buffer_size = buffer_size
+                .saturating_mul(4)
+                .min(MAX_ENVELOPE_FINGERPRINT_CHUNK_SIZE);
+        }
+    }
+}
+
+/// Condensed envelope state which allows detecting envelope changes without keeping the complete
+/// envelope chunk around.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub(crate) struct EnvelopeFingerprint {
+    pub visible: bool,
+    pub armed: bool,
+    /// Hash of all envelope points.
+    pub points_hash: u64,
+    /// Hash of all automation items.
+    pub automation_items_hash: u64,
+    /// Size of the chunk from which this fingerprint was built (used as size hint next time).
+    pub chunk_size: u32,
+}
+
+impl EnvelopeFingerprint {
+    pub fn from_chunk_content(content: &str) -> EnvelopeFingerprint {
+        let mut visible = false;
+        let mut armed = false;
+        let mut points_hasher = DefaultHasher::new();
+        let mut automation_items_hasher = DefaultHasher::new();
+        for line in content.lines() {
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("VIS") => visible = tokens.next() == Some("1"),
+                Some("ARM") => armed = tokens.next() == Some("1"),
+                Some("PT") => line.hash(&mut points_hasher),
+                Some("POOLEDENVINST") => line.hash(&mut automation_items_hasher),
+                _ => {}
+            }
+        }
+        EnvelopeFingerprint {
+            visible,
+            armed,
+            points_hash: points_hasher.finish(),
+            automation_items_hash: automation_items_hasher.finish(),
+            chunk_size: content.len() as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHUNK: &str = r#"<VOLENV2
+EGUID {0A3B5C2E-7D8F-4E61-9A2B-3C4D5E6F7A8B}
+ACT 1 -1
+VIS 1 1 1
+LANEHEIGHT 0 0
+ARM 0
+DEFSHAPE 0 -1 -1
+VOLTYPE 1
+PT 0 1 0
+PT 2 0.5 0
+POOLEDENVINST 1 4 2 0 1 1 0 0 0 0 0 0 0 0
+>
+"#;
+
+    #[test]
+    fn fingerprint_flags() {
+        // Given
+        let fingerprint = EnvelopeFingerprint::from_chunk_content(CHUNK);
+        // When
+        // Then
+        assert!(fingerprint.visible);
+        assert!(!fingerprint.armed);
+        assert_eq!(fingerprint.chunk_size, CHUNK.len() as u32);
+    }
+
+    #[test]
+    fn fingerprint_detects_point_changes_only() {
+        // Given
+        let original = EnvelopeFingerprint::from_chunk_content(CHUNK);
+        // When
+        let moved_point =
+            EnvelopeFingerprint::from_chunk_content(&CHUNK.replace("PT 2 0.5 0", "PT 3 0.5 0"));
+        let armed = EnvelopeFingerprint::from_chunk_content(&CHUNK.replace("ARM 0", "ARM 1"));
+        // Then
+        assert_ne!(moved_point.points_hash, original.points_hash);
+        assert_eq!(
+            moved_point.automation_items_hash,
+            original.automation_items_hash
+        );
+        assert!(armed.armed);
+        assert_eq!(armed.points_hash, original.points_hash);
+    }
+
+    #[test]
+    fn fingerprint_detects_automation_item_changes() {
+        // Given
+        let original = EnvelopeFingerprint::from_chunk_content(CHUNK);
+        // When
+        let resized_item = EnvelopeFingerprint::from_chunk_content(
+            &CHUNK.replace("POOLEDENVINST 1 4 2", "POOLEDENVINST 1 4 3"),
+        );
+        // Then
+        assert_ne!(
+            resized_item.automation_items_hash,
+            original.automation_items_hash
+        );
+        assert_eq!(resized_item.points_hash, original.points_hash);
+    }
+}
@@ -7,7 +7,7 @@ use crate::fx_chain::FxChain;
 use crate::fx_parameter::FxParameter;
 use crate::guid::Guid;
 use crate::option_util::OptionExt;
-use crate::{ChunkRegion, FxChainContext, Project, Reaper, Track};
+use crate::{ChunkRegion, FxChainContext, Project, Reaper, Track, UserPresetFile};
 use reaper_medium::{
     FxPresetRef, FxShowInstruction, Hwnd, ParamId, ReaperFunctionError, ReaperString,
     ReaperStringArg, TrackFxGetPresetIndexResult, TrackFxLocation,
@@ -100,10 +100,16 @@ impl Fx {
 
     pub fn chunk(&self) -> Result<ChunkRegion, &'static str> {
         self.load_if_necessary_or_complain();
-        let res = self
-            .chain()
-            .chunk()?
-            .ok_or("FX chain chunk not found")?
+        self.chunk_within(&self.chain_chunk()?)
+    }
+
+    /// Like [`chunk()`](#method.chunk) but looks up the FX in the given FX chain chunk instead of
+    /// reading the track chunk again.
+    pub(crate) fn chunk_within(
+        &self,
+        chain_chunk: &ChunkRegion,
+    ) -> Result<ChunkRegion, &'static str> {
+        let res = chain_chunk
             .find_line_starting_with(self.fx_id_line()?.as_str())
             .ok_or("FX ID line not found")?
             .move_left_cursor_left_to_start_of_line_beginning_with("BYPASS ")
@@ -122,10 +128,11 @@ impl Fx {
     }
 
     fn tag_chunk_internal(&self) -> Result<ChunkRegion, &'static str> {
-        let res = self
-            .chain()
-            .chunk()?
-            .ok_or("FX chain chunk not found")?
+        self.tag_chunk_within(&self.chain_chunk()?)
+    }
+
+    fn tag_chunk_within(&self, chain_chunk: &ChunkRegion) -> Result<ChunkRegion, &'static str> {
+        let res = chain_chunk
             .find_line_starting_with(self.fx_id_line()?.as_str())
             .ok_or("FX ID line not found")?
             .move_left_cursor_left_to_start_of_line_beginning_with("BYPASS ")
@@ -135,13 +142,27 @@ impl Fx {
     }
 
     pub fn state_chunk(&self) -> Result<ChunkRegion, &'static str> {
+        self.load_if_necessary_or_complain();
+        self.state_chunk_within(&self.chain_chunk()?)
+    }
+
+    /// Like [`state_chunk()`](#method.state_chunk) but looks up the FX in the given FX chain
+    /// chunk instead of reading the track chunk again.
+    pub(crate) fn state_chunk_within(
+        &self,
+        chain_chunk: &ChunkRegion,
+    ) -> Result<ChunkRegion, &'static str> {
         let res = self
-            .tag_chunk()?
+            .tag_chunk_within(chain_chunk)?
             .move_left_cursor_right_to_start_of_next_line()
             .move_right_cursor_left_to_end_of_previous_line();
         Ok(res)
     }
 
+    fn chain_chunk(&self) -> Result<ChunkRegion, &'static str> {
+        self.chain().chunk()?.ok_or("FX chain chunk not found")
+    }
+
     // Attention: Currently implemented by parsing chunk
     pub fn info(&self) -> Result<FxInfo, &'static str> {
         self.load_if_necessary_or_complain();
@@ -352,6 +373,11 @@ impl Fx {
         Some(self.parameter_by_index(index))
     }
 
+    /// Looks up a parameter by its stable identifier (see [`FxParameter::ident`]).
+    pub fn parameter_by_ident(&self, ident: &str) -> Option<FxParameter> {
+        self.parameter_by_id(ParamId::custom(ident))
+    }
+
     /// Will return None if monitoring FX.
     ///
     /// In some scenarios it makes sense to fall back to the master track of the current project.
@@ -649,6 +675,26 @@ impl Fx {
         }
     }
 
+    /// Activates the preset with the given name.
+    pub fn activate_preset_by_name<'a>(
+        &self,
+        name: impl Into<ReaperStringArg<'a>>,
+    ) -> Result<(), &'static str> {
+        self.load_if_necessary_or_complain();
+        match self.chain.context() {
+            FxChainContext::Take(_) => todo!(),
+            _ => {
+                let (track, location) = self.track_and_location();
+                unsafe {
+                    Reaper::get()
+                        .medium_reaper()
+                        .track_fx_set_preset(track.raw(), location, name)
+                        .map_err(|_| "couldn't activate preset")
+                }
+            }
+        }
+    }
+
     pub fn preset_is_dirty(&self) -> bool {
         self.load_if_necessary_or_complain();
         match self.chain.context() {
@@ -680,6 +726,30 @@ impl Fx {
             }
         }
     }
+
+    /// Returns the path of the file in which REAPER stores the user presets of this FX.
+    pub fn user_preset_file_path(&self) -> Option<PathBuf> {
+        self.load_if_necessary_or_complain();
+        match self.chain.context() {
+            FxChainContext::Take(_) => todo!(),
+            _ => {
+                let (track, location) = self.track_and_location();
+                unsafe {
+                    Reaper::get()
+                        .medium_reaper()
+                        .track_fx_get_user_preset_filename(track.raw(), location, 2000)
+                }
+            }
+        }
+    }
+
+    /// Reads the user presets of this FX.
+    pub fn user_preset_file(&self) -> Result<UserPresetFile, &'static str> {
+        let path = self
+            .user_preset_file_path()
+            .ok_or("couldn't determine user preset file")?;
+        UserPresetFile::load(&path)
+    }
 }
 
 fn get_track_and_location(chain: &FxChain, index: u32) -> Option<(Track, TrackFxLocation)> {
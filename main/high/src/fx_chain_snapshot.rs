@@ -0,0 +1,427 @@
+use crate::{FxChain, Guid};
+use reaper_medium::ReaperNormalizedFxParamValue;
+
+/// The complete state of an FX chain at a certain point in time.
+///
+/// Create it via [`FxChain::snapshot`], compare two snapshots via [`FxChainSnapshot::diff`] and
+/// bring a chain back into the captured state via [`FxChain::restore_snapshot`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct FxChainSnapshot {
+    chain_chunk: String,
+    fxs: Vec<FxSnapshot>,
+}
+
+/// The state of one FX within an [`FxChainSnapshot`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct FxSnapshot {
+    pub guid: Guid,
+    pub name: String,
+    /// Complete FX chunk including the `BYPASS` and `FXID` lines.
+    pub chunk: String,
+    /// Just the plug-in state (the content of the FX tag chunk).
+    pub state_chunk: String,
+    pub is_enabled: bool,
+    pub is_online: bool,
+    pub preset_name: Option<String>,
+    pub parameter_values: Vec<ReaperNormalizedFxParamValue>,
+}
+
+/// A difference between two FX chain snapshots.
+///
+/// "Old" refers to the snapshot on which [`FxChainSnapshot::diff`] was called, "new" to the one
+/// passed as argument.
+#[derive(Clone, PartialEq, Debug)]
+pub enum FxChainDifference {
+    FxAdded {
+        guid: Guid,
+        index: u32,
+    },
+    FxRemoved {
+        guid: Guid,
+        index: u32,
+    },
+    FxMoved {
+        guid: Guid,
+        old_index: u32,
+        new_index: u32,
+    },
+    EnabledChanged {
+        guid: Guid,
+        old_value: bool,
+        new_value: bool,
+    },
+    OnlineChanged {
+        guid: Guid,
+        old_value: bool,
+        new_value: bool,
+    },
+    PresetChanged {
+        guid: Guid,
+        old_name: Option<String>,
+        new_name: Option<String>,
+    },
+    ParameterChanged {
+        guid: Guid,
+        param_index: u32,
+        old_value: Option<ReaperNormalizedFxParamValue>,
+        new_value: Option<ReaperNormalizedFxParamValue>,
+    },
+    /// The plug-in state differs in a way which is not reflected by parameter values.
+    StateChanged {
+        guid: Guid,
+    },
+}
+
+impl FxChainSnapshot {
+    /// Captures the current state of the given chain.
+    ///
+    /// Works on track FX only because it relies on chunks. The track chunk is read just once.
+    pub fn capture(chain: &FxChain) -> Result<FxChainSnapshot, &'static str> {
+        let chain_region = chain.chunk()?;
+        let fxs: Result<Vec<_>, &'static str> = chain
+            .fxs()
+            .map(|fx| {
+                let chain_region = chain_region.as_ref().ok_or("FX chain chunk not found")?;
+                let snapshot = FxSnapshot {
+                    guid: fx.guid().ok_or("FX doesn't have GUID")?,
+                    name: fx.name().into_string(),
+                    chunk: fx.chunk_within(chain_region)?.content().to_string(),
+                    state_chunk: fx.state_chunk_within(chain_region)?.content().to_string(),
+                    is_enabled: fx.is_enabled(),
+                    is_online: fx.is_online(),
+                    preset_name: fx.preset_name().map(|n| n.into_string()),
+                    parameter_values: fx
+                        .parameters()
+                        .map(|p| p.reaper_normalized_value())
+                        .collect(),
+                };
+                Ok(snapshot)
+            })
+            .collect();
+        Ok(FxChainSnapshot {
+            chain_chunk: chain_region
+                .map(|r| r.content().to_string())
+                .unwrap_or_default(),
+            fxs: fxs?,
+        })
+    }
+
+    /// The complete FX chain chunk (`FXCHAIN` or `FXCHAIN_REC` tag).
+    ///
+    /// Empty if the chain didn't exist at the time of capturing.
+    pub fn chain_chunk(&self) -> &str {
+        &self.chain_chunk
+    }
+
+    pub fn fxs(&self) -> &[FxSnapshot] {
+        &self.fxs
+    }
+
+    pub fn fx_by_guid(&self, guid: &Guid) -> Option<&FxSnapshot> {
+        self.fxs.iter().find(|fx| &fx.guid == guid)
+    }
+
+    /// Returns whether both snapshots contain the same FX (by GUID) in the same order.
+    pub fn has_same_structure_as(&self, other: &FxChainSnapshot) -> bool {
+        self.fxs
+            .iter()
+            .map(|fx| fx.guid)
+            .eq(other.fxs.iter().map(|fx| fx.guid))
+    }
+
+    /// Returns all differences between this (old) snapshot and the given (new) one.
+    ///
+    /// FX are matched by GUID. Differences of removed FX come first, followed by the ones of
+    /// the new snapshot's FX in chain order.
+    pub fn diff(&self, other: &FxChainSnapshot) -> Vec<FxChainDifference> {
+        use FxChainDifference::*;
+        let mut differences = vec![];
+        for (old_index, old_fx) in self.fxs.iter().enumerate() {
+            if other.fx_by_guid(&old_fx.guid).is_none() {
+                differences.push(FxRemoved {
+                    guid: old_fx.guid,
+                    index: old_index as u32,
+                });
+            }
+        }
+        for (new_index, new_fx) in other.fxs.iter().enumerate() {
+            let new_index = new_index as u32;
+            let old_entry = self
+                .fxs
+                .iter()
+                .enumerate()
+                .find(|(_, fx)| fx.guid == new_fx.guid);
+            let (old_index, old_fx) = match old_entry {
+                None => {
+                    differences.push(FxAdded {
+                        guid: new_fx.guid,
+                        index: new_index,
+                    });
+                    continue;
+                }
+                Some((i, fx)) => (i as u32, fx),
+            };
+            if old_index != new_index {
+                differences.push(FxMoved {
+                    guid: new_fx.guid,
+                    old_index,
+                    new_index,
+                });
+            }
+            old_fx.diff(new_fx, &mut differences);
+        }
+        differences
+    }
+}
+
+impl FxSnapshot {
+    fn diff(&self, other: &FxSnapshot, differences: &mut Vec<FxChainDifference>) {
+        use FxChainDifference::*;
+        let guid = other.guid;
+        if self.is_enabled != other.is_enabled {
+            differences.push(EnabledChanged {
+                guid,
+                old_value: self.is_enabled,
+                new_value: other.is_enabled,
+            });
+        }
+        if self.is_online != other.is_online {
+            differences.push(OnlineChanged {
+                guid,
+                old_value: self.is_online,
+                new_value: other.is_online,
+            });
+        }
+        if self.preset_name != other.preset_name {
+            differences.push(PresetChanged {
+                guid,
+                old_name: self.preset_name.clone(),
+                new_name: other.preset_name.clone(),
+            });
+        }
+        let param_count = self
+            .parameter_values
+            .len()
+            .max(other.parameter_values.len());
+        let mut parameters_changed = false;
+        for i in 0..param_count {
+            let old_value = self.parameter_values.get(i).copied();
+            let new_value = other.parameter_values.get(i).copied();
+            if old_value != new_value {
+                parameters_changed = true;
+                differences.push(ParameterChanged {
+                    guid,
+                    param_index: i as u32,
+                    old_value,
+                    new_value,
+                });
+            }
+        }
+        if !parameters_changed && self.state_chunk != other.state_chunk {
+            differences.push(StateChanged { guid });
+        }
+    }
+}
+
+impl FxChain {
+    /// Captures the complete state of this FX chain.
+    ///
+    /// Works on track FX only.
+    pub fn snapshot(&self) -> Result<FxChainSnapshot, &'static str> {
+        FxChainSnapshot::capture(self)
+    }
+
+    /// Brings this FX chain back into the state captured in the given snapshot.
+    ///
+    /// Tries to write as few chunks as possible: If the chain still contains the same FX in the
+    /// same order, enabled state, online state, preset and parameter values are restored via the
+    /// API. An FX chunk is only written if this isn't possible, e.g. if the number of parameters
+    /// differs or the plug-in state differs in a way which is not reflected by parameter values.
+    /// Otherwise the complete chain chunk is written once.
+    pub fn restore_snapshot(&self, snapshot: &FxChainSnapshot) -> Result<(), &'static str> {
+        let current = self.snapshot()?;
+        if !current.has_same_structure_as(snapshot) {
+            if snapshot.chain_chunk().is_empty() {
+                // The chain didn't exist or was empty when capturing, so there's no chunk to write
+                if !snapshot.fxs.is_empty() {
+                    return Err("snapshot doesn't contain FX chain chunk");
+                }
+                let fxs: Vec<_> = self.fxs().collect();
+                for fx in fxs.iter().rev() {
+                    self.remove_fx(fx)?;
+                }
+                return Ok(());
+            }
+            return self.set_chunk(snapshot.chain_chunk());
+        }
+        for (index, (current_fx, target_fx)) in current.fxs.iter().zip(&snapshot.fxs).enumerate() {
+            if current_fx == target_fx {
+                continue;
+            }
+            let fx = self.fx_by_guid_and_index(&target_fx.guid, index as u32);
+            if current_fx.is_online != target_fx.is_online {
+                fx.set_online(target_fx.is_online);
+            }
+            if current_fx.is_enabled != target_fx.is_enabled {
+                if target_fx.is_enabled {
+                    fx.enable();
+                } else {
+                    fx.disable();
+                }
+            }
+            let mut needs_state_chunk_write =
+                current_fx.parameter_values.len() != target_fx.parameter_values.len();
+            // Activating a preset changes parameter values, so all of them need to be set again
+            let preset_changed = current_fx.preset_name != target_fx.preset_name;
+            if preset_changed {
+                if let Some(name) = &target_fx.preset_name {
+                    if fx.activate_preset_by_name(name.as_str()).is_err() {
+                        needs_state_chunk_write = true;
+                    }
+                }
+            }
+            if !needs_state_chunk_write {
+                let params = current_fx
+                    .parameter_values
+                    .iter()
+                    .zip(&target_fx.parameter_values)
+                    .enumerate();
+                for (param_index, (current_value, target_value)) in params {
+                    if !preset_changed && current_value == target_value {
+                        continue;
+                    }
+                    let result = fx
+                        .parameter_by_index(param_index as u32)
+                        .set_reaper_normalized_value(*target_value);
+                    if result.is_err() {
+                        needs_state_chunk_write = true;
+                        break;
+                    }
+                }
+            }
+            // If parameter values are equal, a state difference can't be restored via the API
+            let has_hidden_state_change = current_fx.parameter_values == target_fx.parameter_values
+                && current_fx.state_chunk != target_fx.state_chunk;
+            if needs_state_chunk_write || has_hidden_state_change {
+                fx.set_state_chunk(&target_fx.state_chunk)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reaper_low::raw::GUID;
+
+    fn guid(n: u32) -> Guid {
+        Guid::new(GUID {
+            Data1: n,
+            Data2: 0,
+            Data3: 0,
+            Data4: [0; 8],
+        })
+    }
+
+    fn fx(n: u32) -> FxSnapshot {
+        FxSnapshot {
+            guid: guid(n),
+            name: format!("FX {}", n),
+            chunk: format!("BYPASS 0 0 0\n<JS fx{}\n>\nWAK 0", n),
+            state_chunk: String::new(),
+            is_enabled: true,
+            is_online: true,
+            preset_name: None,
+            parameter_values: vec![ReaperNormalizedFxParamValue::new(0.5); 2],
+        }
+    }
+
+    fn snapshot(fxs: Vec<FxSnapshot>) -> FxChainSnapshot {
+        FxChainSnapshot {
+            chain_chunk: String::new(),
+            fxs,
+        }
+    }
+
+    // Guid's Debug implementation needs REAPER, so we can't use assert_eq! here.
+    #[test]
+    fn diff_structure() {
+        // Given
+        let old = snapshot(vec![fx(1), fx(2), fx(3)]);
+        let new = snapshot(vec![fx(3), fx(2), fx(4)]);
+        // When
+        let differences = old.diff(&new);
+        // Then
+        use FxChainDifference::*;
+        assert!(!old.has_same_structure_as(&new));
+        assert!(
+            differences
+                == vec![
+                    FxRemoved {
+                        guid: guid(1),
+                        index: 0
+                    },
+                    FxMoved {
+                        guid: guid(3),
+                        old_index: 2,
+                        new_index: 0
+                    },
+                    FxAdded {
+                        guid: guid(4),
+                        index: 2
+                    },
+                ]
+        );
+    }
+
+    #[test]
+    fn diff_fx_state() {
+        // Given
+        let old = snapshot(vec![fx(1), fx(2)]);
+        let mut changed_fx_1 = fx(1);
+        changed_fx_1.is_enabled = false;
+        changed_fx_1.preset_name = Some("Bright".to_string());
+        changed_fx_1.parameter_values[1] = ReaperNormalizedFxParamValue::new(0.7);
+        let mut changed_fx_2 = fx(2);
+        changed_fx_2.state_chunk = "changed".to_string();
+        let new = snapshot(vec![changed_fx_1, changed_fx_2]);
+        // When
+        let differences = old.diff(&new);
+        // Then
+        use FxChainDifference::*;
+        assert!(old.has_same_structure_as(&new));
+        assert!(
+            differences
+                == vec![
+                    EnabledChanged {
+                        guid: guid(1),
+                        old_value: true,
+                        new_value: false
+                    },
+                    PresetChanged {
+                        guid: guid(1),
+                        old_name: None,
+                        new_name: Some("Bright".to_string())
+                    },
+                    ParameterChanged {
+                        guid: guid(1),
+                        param_index: 1,
+                        old_value: Some(ReaperNormalizedFxParamValue::new(0.5)),
+                        new_value: Some(ReaperNormalizedFxParamValue::new(0.7))
+                    },
+                    StateChanged { guid: guid(2) },
+                ]
+        );
+    }
+
+    #[test]
+    fn diff_equal() {
+        // Given
+        let old = snapshot(vec![fx(1), fx(2)]);
+        // When
+        let differences = old.diff(&old.clone());
+        // Then
+        assert!(differences.is_empty());
+    }
+}
@@ -1,10 +1,12 @@
 use crate::fx::Fx;
 
-use crate::{FxChain, FxChainContext, Reaper};
+use crate::{FxChain, FxChainContext, Guid, Reaper};
 use reaper_medium::{
     GetParamExResult, GetParameterStepSizesResult, ReaperFunctionError,
     ReaperNormalizedFxParamValue, ReaperString,
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct FxParameter {
@@ -95,6 +97,37 @@ impl FxParameter {
         }
     }
 
+    /// Returns an identifier which, in contrast to the index, survives plug-in updates that reorder
+    /// parameters (e.g. the VST3 parameter ID or the JS slider name).
+    ///
+    /// Requires REAPER >= 6.37.
+    pub fn ident(&self) -> Result<ReaperString, ReaperFunctionError> {
+        match self.chain().context() {
+            FxChainContext::Take(_) => todo!(),
+            _ => {
+                let (track, location) = self.fx().track_and_location();
+                unsafe {
+                    Reaper::get().medium_reaper().track_fx_get_param_ident(
+                        track.raw(),
+                        location,
+                        self.index,
+                        256,
+                    )
+                }
+            }
+        }
+    }
+
+    /// Returns a descriptor which can be persisted and resolved later to this parameter, even if
+    /// the parameters of the FX have been reordered in the meantime.
+    pub fn descriptor(&self) -> Result<FxParameterDescriptor, &'static str> {
+        let descriptor = FxParameterDescriptor {
+            fx_guid: self.fx.get_or_query_guid()?,
+            param_ident: self.ident().map_err(|e| e.message())?.into_string(),
+        };
+        Ok(descriptor)
+    }
+
     pub fn character(&self) -> FxParameterCharacter {
         let result = self.step_sizes();
         use GetParameterStepSizesResult::*;
@@ -229,6 +262,26 @@ impl FxParameter {
     }
 }
 
+/// Persistent reference to an FX parameter, identified by FX GUID and parameter ident.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FxParameterDescriptor {
+    pub fx_guid: Guid,
+    /// See [`FxParameter::ident`].
+    pub param_ident: String,
+}
+
+impl FxParameterDescriptor {
+    /// Looks up the described parameter in the given FX chain.
+    pub fn resolve(&self, chain: &FxChain) -> Option<FxParameter> {
+        let fx = chain.fx_by_guid(&self.fx_guid);
+        if !fx.is_available() {
+            return None;
+        }
+        fx.parameter_by_ident(&self.param_ident)
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum FxParameterCharacter {
     Toggle,
@@ -0,0 +1,412 @@
+use crate::Reaper;
+use std::fs;
+use std::path::Path;
+
+/// Catalogue of all FX installed in REAPER.
+///
+/// Uses `EnumInstalledFX` if available (REAPER >= 6.69). Older REAPER versions don't have this
+/// function, so the catalogue is built by parsing the plug-in cache files in the resource
+/// directory instead.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct InstalledFx {
+    entries: Vec<InstalledFxEntry>,
+}
+
+/// A single installed plug-in as listed in REAPER's FX browser.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct InstalledFxEntry {
+    pub plugin_type: InstalledFxType,
+    /// e.g. "ReaEQ" or "Element FX (34ch)"
+    pub name: String,
+    /// e.g. "Cockos", not available for JS and some other plug-ins
+    pub vendor: Option<String>,
+    /// e.g. "reaeq.dll", "utility/volume" or "com.u-he.Diva"
+    pub identifier: String,
+    pub is_instrument: bool,
+    /// e.g. "VST: ReaEQ (Cockos)", that's the name to be passed to
+    /// [`FxChain::add_fx_by_original_name`](crate::FxChain::add_fx_by_original_name)
+    pub original_name: String,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum InstalledFxType {
+    Vst,
+    Vst3,
+    Clap,
+    Au,
+    Lv2,
+    Dx,
+    Js,
+    Other,
+}
+
+impl InstalledFxType {
+    /// Parses the type prefix of an FX name, e.g. "VSTi". Also returns whether it denotes an
+    /// instrument.
+    fn from_prefix(prefix: &str) -> (InstalledFxType, bool) {
+        use InstalledFxType::*;
+        match prefix {
+            "VST" => (Vst, false),
+            "VSTi" => (Vst, true),
+            "VST3" => (Vst3, false),
+            "VST3i" => (Vst3, true),
+            "CLAP" => (Clap, false),
+            "CLAPi" => (Clap, true),
+            "AU" => (Au, false),
+            "AUi" => (Au, true),
+            "LV2" => (Lv2, false),
+            "LV2i" => (Lv2, true),
+            "DX" => (Dx, false),
+            "DXi" => (Dx, true),
+            "JS" => (Js, false),
+            _ => (Other, false),
+        }
+    }
+
+    fn prefix(self, is_instrument: bool) -> &'static str {
+        use InstalledFxType::*;
+        match (self, is_instrument) {
+            (Vst, false) => "VST",
+            (Vst, true) => "VSTi",
+            (Vst3, false) => "VST3",
+            (Vst3, true) => "VST3i",
+            (Clap, false) => "CLAP",
+            (Clap, true) => "CLAPi",
+            (Au, false) => "AU",
+            (Au, true) => "AUi",
+            (Lv2, false) => "LV2",
+            (Lv2, true) => "LV2i",
+            (Dx, false) => "DX",
+            (Dx, true) => "DXi",
+            (Js, _) => "JS",
+            (Other, _) => "",
+        }
+    }
+}
+
+impl InstalledFx {
+    /// Builds the catalogue from REAPER, falling back to the plug-in cache files if necessary.
+    pub fn load() -> InstalledFx {
+        let reaper = Reaper::get();
+        if reaper
+            .medium_reaper()
+            .low()
+            .pointers()
+            .EnumInstalledFX
+            .is_none()
+        {
+            return InstalledFx::from_resource_dir(&reaper.resource_path());
+        }
+        let entries = (0..)
+            .map(|i| {
+                reaper.medium_reaper().enum_installed_fx(i, |r| {
+                    r.map(|r| InstalledFxEntry::from_enum_result(r.name.to_str(), r.ident.to_str()))
+                })
+            })
+            .take_while(|r| r.is_some())
+            .flatten()
+            .flatten()
+            .collect();
+        InstalledFx { entries }
+    }
+
+    /// Builds the catalogue by parsing `reaper-vstplugins*.ini`, `reaper-jsfx.ini` and
+    /// `reaper-clap-*.ini` in the given REAPER resource directory.
+    ///
+    /// Files which don't exist or can't be read are skipped.
+    pub fn from_resource_dir(resource_dir: &Path) -> InstalledFx {
+        let mut entries = vec![];
+        let dir_entries = match fs::read_dir(resource_dir) {
+            Ok(d) => d,
+            Err(_) => return InstalledFx { entries },
+        };
+        let mut file_names: Vec<_> = dir_entries
+            .filter_map(|e| e.ok()?.file_name().into_string().ok())
+            .filter(|n| n.ends_with(".ini"))
+            .collect();
+        file_names.sort();
+        for file_name in file_names {
+            let parse: fn(&str) -> Vec<InstalledFxEntry> =
+                if file_name.starts_with("reaper-vstplugins") {
+                    parse_vst_plugins_ini
+                } else if file_name.starts_with("reaper-clap-") {
+                    parse_clap_plugins_ini
+                } else if file_name == "reaper-jsfx.ini" {
+                    parse_jsfx_ini
+                } else {
+                    continue;
+                };
+            if let Ok(content) = fs::read_to_string(resource_dir.join(&file_name)) {
+                entries.extend(parse(&content));
+            }
+        }
+        InstalledFx { entries }
+    }
+
+    pub fn entries(&self) -> &[InstalledFxEntry] {
+        &self.entries
+    }
+
+    pub fn instruments(&self) -> impl Iterator<Item = &InstalledFxEntry> + '_ {
+        self.entries.iter().filter(|e| e.is_instrument)
+    }
+
+    /// Returns all entries which contain each of the whitespace-separated words of the given query
+    /// in either name, vendor or identifier (case-insensitive).
+    pub fn search<'a>(&'a self, query: &str) -> impl Iterator<Item = &'a InstalledFxEntry> + 'a {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        self.entries.iter().filter(move |e| {
+            let name = e.name.to_lowercase();
+            let vendor = e.vendor.as_deref().unwrap_or_default().to_lowercase();
+            let identifier = e.identifier.to_lowercase();
+            words
+                .iter()
+                .all(|w| name.contains(w) || vendor.contains(w) || identifier.contains(w))
+        })
+    }
+
+    /// Looks up the entry with exactly the given original name, e.g. "VST: ReaEQ (Cockos)".
+    pub fn find_by_original_name(&self, original_name: &str) -> Option<&InstalledFxEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.original_name == original_name)
+    }
+}
+
+impl InstalledFxEntry {
+    /// Creates an entry from what `EnumInstalledFX` returns, e.g. "VSTi: ReaSynth (Cockos)" and
+    /// "reasynth.dll".
+    fn from_enum_result(name: &str, ident: &str) -> Option<InstalledFxEntry> {
+        let (prefix, remainder) = name.split_once(": ")?;
+        let (plugin_type, is_instrument) = InstalledFxType::from_prefix(prefix);
+        let identifier = if plugin_type == InstalledFxType::Js {
+            ident.to_owned()
+        } else {
+            // VST identifiers can contain a unique ID or a full path
+            let ident = ident.split('<').next().unwrap_or_default();
+            Path::new(ident)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| ident.to_owned())
+        };
+        let entry = InstalledFxEntry {
+            // Take it as is, we might not know the type prefix
+            original_name: name.to_owned(),
+            ..InstalledFxEntry::new(plugin_type, remainder, identifier, is_instrument)
+        };
+        Some(entry)
+    }
+
+    fn new(
+        plugin_type: InstalledFxType,
+        name_and_vendor: &str,
+        identifier: String,
+        is_instrument: bool,
+    ) -> InstalledFxEntry {
+        let (name, vendor) = split_name_and_vendor(name_and_vendor);
+        InstalledFxEntry {
+            plugin_type,
+            name,
+            vendor,
+            identifier,
+            is_instrument,
+            original_name: format!("{}: {}", plugin_type.prefix(is_instrument), name_and_vendor),
+        }
+    }
+}
+
+/// Splits e.g. "Element FX (Kushview) (34ch)" into "Element FX (34ch)" and "Kushview".
+fn split_name_and_vendor(name_and_vendor: &str) -> (String, Option<String>) {
+    let regex = regex!(r#"^(.+?) \(([^()]+)\)( \([0-9]+ ?(?:ch|out)\))?$"#);
+    match regex.captures(name_and_vendor) {
+        None => (name_and_vendor.to_owned(), None),
+        Some(captures) => {
+            let name = match captures.get(3) {
+                None => captures[1].to_owned(),
+                Some(channels) => format!("{}{}", &captures[1], channels.as_str()),
+            };
+            (name, Some(captures[2].to_owned()))
+        }
+    }
+}
+
+/// Parses lines such as `reaeq.dll=00D9B6F1F0A9D701,1919247729,ReaEQ (Cockos)`.
+///
+/// Instruments are marked with a trailing `!!!VSTi`.
+fn parse_vst_plugins_ini(content: &str) -> Vec<InstalledFxEntry> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (file_name, value) = line.split_once('=')?;
+            let mut parts = value.splitn(3, ',');
+            let name_and_vendor = parts.nth(2)?.trim();
+            if name_and_vendor.is_empty() {
+                // Not a plug-in or failed to scan
+                return None;
+            }
+            let (name_and_vendor, is_instrument) = match name_and_vendor.strip_suffix("!!!VSTi") {
+                None => (name_and_vendor, false),
+                Some(n) => (n, true),
+            };
+            let plugin_type = if file_name.to_lowercase().ends_with(".vst3") {
+                InstalledFxType::Vst3
+            } else {
+                InstalledFxType::Vst
+            };
+            Some(InstalledFxEntry::new(
+                plugin_type,
+                name_and_vendor,
+                file_name.to_owned(),
+                is_instrument,
+            ))
+        })
+        .collect()
+}
+
+/// Parses lines such as `NAME utility/volume "JS: Volume Adjustment"`.
+fn parse_jsfx_ini(content: &str) -> Vec<InstalledFxEntry> {
+    let line_regex = regex!(r#"^NAME (?:"(.+?)"|([^ ]+)) "JS: (.+)"$"#);
+    content
+        .lines()
+        .filter_map(|line| {
+            let captures = line_regex.captures(line.trim())?;
+            let path = captures.get(1).or_else(|| captures.get(2))?.as_str();
+            Some(InstalledFxEntry::new(
+                InstalledFxType::Js,
+                &captures[3],
+                path.to_owned(),
+                false,
+            ))
+        })
+        .collect()
+}
+
+/// Parses lines such as `com.u-he.Diva=1|Diva (u-he)`.
+///
+/// The number before the pipe contains flags, the first bit marks instruments.
+fn parse_clap_plugins_ini(content: &str) -> Vec<InstalledFxEntry> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (id, value) = line.split_once('=')?;
+            if id == "_" || id.starts_with('[') {
+                return None;
+            }
+            let (flags, name_and_vendor) = value.split_once('|')?;
+            let flags: u32 = flags.parse().ok()?;
+            Some(InstalledFxEntry::new(
+                InstalledFxType::Clap,
+                name_and_vendor.trim(),
+                id.to_owned(),
+                flags & 1 != 0,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enum_result() {
+        // When
+        let entry =
+            InstalledFxEntry::from_enum_result("VSTi: ReaSynth (Cockos)", "reasynth.dll").unwrap();
+        // Then
+        assert_eq!(
+            entry,
+            InstalledFxEntry {
+                plugin_type: InstalledFxType::Vst,
+                name: "ReaSynth".into(),
+                vendor: Some("Cockos".into()),
+                identifier: "reasynth.dll".into(),
+                is_instrument: true,
+                original_name: "VSTi: ReaSynth (Cockos)".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn name_with_channel_suffix() {
+        // When
+        let result = split_name_and_vendor("Element FX (Kushview) (34ch)");
+        // Then
+        assert_eq!(
+            result,
+            ("Element FX (34ch)".into(), Some("Kushview".into()))
+        );
+    }
+
+    #[test]
+    fn vst_plugins_ini() {
+        // Given
+        let content = r#"[vstcache]
+reaeq.dll=00D9B6F1F0A9D701,1919247729,ReaEQ (Cockos)
+reasynth.dll=00D9B6F1F0A9D701,1919251321,ReaSynth (Cockos)!!!VSTi
+Hive(x64).vst3=00C0E26D1D6ED601,437120294{D39D5B69D6AF42FA1234567868495645},Hive!!!VSTi
+broken.dll=00C0E26D1D6ED601,,
+"#;
+        // When
+        let entries = parse_vst_plugins_ini(content);
+        // Then
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].original_name, "VST: ReaEQ (Cockos)");
+        assert!(!entries[0].is_instrument);
+        assert_eq!(entries[1].original_name, "VSTi: ReaSynth (Cockos)");
+        assert!(entries[1].is_instrument);
+        assert_eq!(entries[2].plugin_type, InstalledFxType::Vst3);
+        assert_eq!(entries[2].original_name, "VST3i: Hive");
+        assert_eq!(entries[2].vendor, None);
+    }
+
+    #[test]
+    fn jsfx_ini() {
+        // Given
+        let content = r#"NAME utility/volume "JS: Volume Adjustment"
+NAME "Liteon/pitch shifter" "JS: Pitch Shifter (Liteon)"
+REV utility/volume 1
+"#;
+        // When
+        let entries = parse_jsfx_ini(content);
+        // Then
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].identifier, "utility/volume");
+        assert_eq!(entries[0].original_name, "JS: Volume Adjustment");
+        assert_eq!(entries[1].identifier, "Liteon/pitch shifter");
+        assert_eq!(entries[1].vendor, Some("Liteon".into()));
+    }
+
+    #[test]
+    fn clap_plugins_ini() {
+        // Given
+        let content = r#"[Diva.clap]
+_=00E0A8C4A1B3D801
+com.u-he.Diva=1|Diva (u-he)
+com.u-he.Presswerk=0|Presswerk (u-he)
+"#;
+        // When
+        let entries = parse_clap_plugins_ini(content);
+        // Then
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].original_name, "CLAPi: Diva (u-he)");
+        assert!(entries[0].is_instrument);
+        assert_eq!(entries[1].identifier, "com.u-he.Presswerk");
+        assert!(!entries[1].is_instrument);
+    }
+
+    #[test]
+    fn search() {
+        // Given
+        let catalogue = InstalledFx {
+            entries: parse_vst_plugins_ini(
+                "reaeq.dll=0,1,ReaEQ (Cockos)\nreasynth.dll=0,1,ReaSynth (Cockos)!!!VSTi",
+            ),
+        };
+        // When
+        let result: Vec<_> = catalogue.search("cockos SYNTH").collect();
+        // Then
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, "ReaSynth");
+    }
+}
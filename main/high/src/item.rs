@@ -1,6 +1,8 @@
-use crate::{Project, Reaper, Take};
+use crate::{Project, Reaper, Take, Track};
+use reaper_medium::ProjectContext::Proj;
 use reaper_medium::{
-    DurationInSeconds, MediaItem, PositionInSeconds, ReaperFunctionError, UiRefreshBehavior,
+    DurationInSeconds, ItemAttributeKey, MediaItem, NativeColorResult, PositionInSeconds,
+    ReaperFunctionError, RgbColor, UiRefreshBehavior,
 };
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
@@ -17,6 +19,14 @@ impl Item {
         self.raw
     }
 
+    /// Returns whether this item still exists in one of the open projects.
+    pub fn is_available(self) -> bool {
+        let reaper = Reaper::get();
+        reaper
+            .projects()
+            .any(|p| reaper.medium_reaper.validate_ptr_2(Proj(p.raw()), self.raw))
+    }
+
     pub fn project(self) -> Option<Project> {
         let raw_project = unsafe {
             Reaper::get()
@@ -26,6 +36,12 @@ impl Item {
         Some(Project::new(raw_project))
     }
 
+    pub fn track(self) -> Option<Track> {
+        let raw_track = unsafe { Reaper::get().medium_reaper.get_media_item_track(self.raw)? };
+        let raw_project = self.project().map(|p| p.raw());
+        Some(Track::new(raw_track, raw_project))
+    }
+
     pub fn active_take(self) -> Option<Take> {
         let raw_take = unsafe { Reaper::get().medium_reaper.get_active_take(self.raw)? };
         Some(Take::new(raw_take))
@@ -40,6 +56,15 @@ impl Item {
         Ok(Take::new(raw_take))
     }
 
+    pub fn position(self) -> PositionInSeconds {
+        let value = unsafe {
+            Reaper::get()
+                .medium_reaper
+                .get_media_item_info_value(self.raw, ItemAttributeKey::Position)
+        };
+        PositionInSeconds::new(value)
+    }
+
     pub fn set_position(
         &self,
         pos: PositionInSeconds,
@@ -52,6 +77,15 @@ impl Item {
         }
     }
 
+    pub fn length(self) -> DurationInSeconds {
+        let value = unsafe {
+            Reaper::get()
+                .medium_reaper
+                .get_media_item_info_value(self.raw, ItemAttributeKey::Length)
+        };
+        DurationInSeconds::new(value)
+    }
+
     pub fn set_length(
         &self,
         length: DurationInSeconds,
@@ -64,6 +98,55 @@ impl Item {
         }
     }
 
+    pub fn custom_color(self) -> Option<RgbColor> {
+        let value = unsafe {
+            Reaper::get()
+                .medium_reaper
+                .get_media_item_info_value(self.raw, ItemAttributeKey::CustomColor)
+        };
+        custom_color_from_raw(value)
+    }
+
+    /// Sets or removes the custom color of this item.
+    pub fn set_custom_color(self, color: Option<RgbColor>) -> Result<(), ReaperFunctionError> {
+        unsafe {
+            Reaper::get().medium_reaper.set_media_item_info_value(
+                self.raw,
+                ItemAttributeKey::CustomColor,
+                custom_color_to_raw(color),
+            )
+        }
+    }
+
+    /// Returns the color which is actually used to display this item, taking the user
+    /// preferences into account (item, take or track color).
+    ///
+    /// Pass a take to get the color of that specific take.
+    pub fn displayed_color(self, take: Option<Take>) -> Option<RgbColor> {
+        let reaper = &Reaper::get().medium_reaper;
+        let native_color =
+            unsafe { reaper.get_displayed_media_item_color_2(self.raw, take.map(|t| t.raw()))? };
+        Some(reaper.color_from_native(native_color))
+    }
+
+    pub fn is_selected(self) -> bool {
+        let value = unsafe {
+            Reaper::get()
+                .medium_reaper
+                .get_media_item_info_value(self.raw, ItemAttributeKey::Selected)
+        };
+        value > 0.0
+    }
+
+    pub fn is_muted(self) -> bool {
+        let value = unsafe {
+            Reaper::get()
+                .medium_reaper
+                .get_media_item_info_value(self.raw, ItemAttributeKey::Mute)
+        };
+        value > 0.0
+    }
+
     pub fn set_selected(&self, selected: bool) {
         unsafe {
             Reaper::get()
@@ -72,3 +155,24 @@ impl Item {
         }
     }
 }
+
+pub(crate) fn custom_color_from_raw(value: f64) -> Option<RgbColor> {
+    let result = NativeColorResult::from_raw(value as i32)?;
+    if !result.is_used {
+        return None;
+    }
+    Some(Reaper::get().medium_reaper.color_from_native(result.color))
+}
+
+pub(crate) fn custom_color_to_raw(color: Option<RgbColor>) -> f64 {
+    match color {
+        None => 0.0,
+        Some(c) => {
+            let result = NativeColorResult {
+                color: Reaper::get().medium_reaper.color_to_native(c),
+                is_used: true,
+            };
+            result.to_raw() as f64
+        }
+    }
+}
@@ -46,12 +46,33 @@ pub use reaper_simple::*;
 mod project;
 pub use project::*;
 
+mod selection_snapshot;
+pub use selection_snapshot::*;
+
 mod track;
 pub use track::*;
 
+mod track_tree;
+pub use track_tree::*;
+
+mod track_group;
+pub use track_group::*;
+
+mod track_freeze;
+pub use track_freeze::*;
+
+mod track_meter;
+pub use track_meter::*;
+
+mod razor_edit;
+pub use razor_edit::*;
+
 mod take;
 pub use take::*;
 
+mod envelope;
+pub use envelope::*;
+
 mod track_route;
 pub use track_route::*;
 
@@ -61,6 +82,15 @@ pub use fx::*;
 mod fx_parameter;
 pub use fx_parameter::*;
 
+mod installed_fx;
+pub use installed_fx::*;
+
+mod user_preset;
+pub use user_preset::*;
+
+mod rea_eq;
+pub use rea_eq::*;
+
 mod section;
 pub use section::*;
 
@@ -73,6 +103,9 @@ pub use guid::*;
 mod fx_chain;
 pub use fx_chain::*;
 
+mod fx_chain_snapshot;
+pub use fx_chain_snapshot::*;
+
 mod midi_input_device;
 pub use midi_input_device::*;
 
@@ -119,6 +152,22 @@ pub use middleware_control_surface::*;
 mod change_detection_middleware;
 pub use change_detection_middleware::*;
 
+mod change_event_broadcaster;
+pub use change_event_broadcaster::*;
+
+mod change_event_coalescer;
+pub use change_event_coalescer::*;
+
+#[cfg(feature = "control-surface-recording")]
+mod control_surface_recording;
+#[cfg(feature = "control-surface-recording")]
+pub use control_surface_recording::*;
+
+#[cfg(feature = "project-data")]
+mod project_data;
+#[cfg(feature = "project-data")]
+pub use project_data::*;
+
 mod option_util;
 
 mod bookmark;
@@ -13,7 +13,7 @@ use reaper_medium::{
     GetLastMarkerAndCurRegionResult, GetLoopTimeRange2Result, MasterTrackBehavior, PanMode,
     PlayState, PositionInSeconds, ProjectContext, ProjectRef, ReaProject, ReaperString,
     ReaperStringArg, SetEditCurPosOptions, TimeMap2TimeToBeatsResult, TimeMode, TimeModeOverride,
-    TimeRangeType, TrackDefaultsBehavior, TrackLocation, UndoBehavior,
+    TimeRangeType, TrackDefaultsBehavior, TrackLocation, TrackReorderBehavior, UndoBehavior,
 };
 use std::path::{Path, PathBuf};
 
@@ -156,6 +156,26 @@ impl Project {
         Some(Item::new(raw_item))
     }
 
+    pub fn selected_item_count(self) -> u32 {
+        Reaper::get()
+            .medium_reaper()
+            .count_selected_media_items(self.context())
+    }
+
+    pub fn selected_items(self) -> impl Iterator<Item = Item> + ExactSizeIterator + 'static {
+        if self.complain_if_not_available().is_err() {
+            return Either::Left(iter::empty());
+        }
+        let iter = (0..self.selected_item_count()).map(move |i| {
+            let media_item = Reaper::get()
+                .medium_reaper()
+                .get_selected_media_item(self.context(), i)
+                .unwrap();
+            Item::new(media_item)
+        });
+        Either::Right(iter)
+    }
+
     pub fn unselect_all_tracks(self) {
         // TODO-low No project context
         unsafe {
@@ -163,6 +183,53 @@ impl Project {
         }
     }
 
+    /// Moves the given tracks in front of the track at the given index.
+    ///
+    /// The index refers to the track order before moving. Works in the current project only
+    /// because REAPER doesn't offer a project-specific way to do this. The track selection is
+    /// restored afterwards.
+    ///
+    /// The `behavior` parameter is the "move mode" of `ReorderSelectedTracks()`. It's called
+    /// [`TrackReorderBehavior`] in line with the other `*Behavior` enums of reaper-medium.
+    pub fn move_tracks(
+        self,
+        tracks: &[Track],
+        before_index: u32,
+        behavior: TrackReorderBehavior,
+    ) -> ReaperResult<()> {
+        self.complain_if_not_available()?;
+        if self != Reaper::get().current_project() {
+            return Err("tracks can only be moved within the current project".into());
+        }
+        self.with_selected_tracks(tracks, || {
+            Reaper::get()
+                .medium_reaper()
+                .reorder_selected_tracks(before_index, behavior)
+        })?;
+        Ok(())
+    }
+
+    /// Selects exactly the given tracks, executes the given operation (usually one which works on
+    /// selected tracks) and restores the previous track selection.
+    pub(crate) fn with_selected_tracks<R>(
+        self,
+        tracks: &[Track],
+        operation: impl FnOnce() -> R,
+    ) -> R {
+        // Restores the selection even if the operation panics
+        let _restorer = TrackSelectionRestorer {
+            project: self,
+            previously_selected: self
+                .selected_tracks(MasterTrackBehavior::IncludeMasterTrack)
+                .collect(),
+        };
+        self.unselect_all_tracks();
+        for track in tracks {
+            track.select();
+        }
+        operation()
+    }
+
     pub fn selected_tracks(
         self,
         want_master: MasterTrackBehavior,
@@ -278,6 +345,10 @@ impl Project {
             .is_project_dirty(Proj(self.rea_project))
     }
 
+    /// Returns the label of the action which would be undone next.
+    ///
+    /// There's no way to get the complete undo history because REAPER's API only exposes the
+    /// labels of the next undo and redo step.
     pub fn label_of_last_undoable_action(self) -> Option<ReaperString> {
         self.complain_if_not_available().ok()?;
         Reaper::get()
@@ -292,6 +363,13 @@ impl Project {
             .undo_can_redo_2(Proj(self.rea_project), |s| s.to_owned())
     }
 
+    /// Returns a number which changes whenever the state of this project changes.
+    pub fn state_change_count(self) -> u32 {
+        Reaper::get()
+            .medium_reaper()
+            .get_project_state_change_count(Proj(self.rea_project))
+    }
+
     pub fn tempo(self) -> Tempo {
         let bpm = if self == Reaper::get().current_project() {
             Reaper::get().medium_reaper().master_get_tempo()
@@ -572,6 +650,10 @@ impl Project {
         );
     }
 
+    pub fn clear_time_selection(self) {
+        self.set_time_selection(PositionInSeconds::ZERO, PositionInSeconds::ZERO);
+    }
+
     pub fn set_loop_points(
         self,
         start: PositionInSeconds,
@@ -716,3 +798,19 @@ impl OrCurrentProject for Option<Project> {
         self.unwrap_or_else(|| Reaper::get().current_project())
     }
 }
+
+// Destructor restores the previously captured track selection (RAII).
+struct TrackSelectionRestorer {
+    project: Project,
+    previously_selected: Vec<Track>,
+}
+
+impl Drop for TrackSelectionRestorer {
+    fn drop(&mut self) {
+        self.project.unselect_all_tracks();
+        // Tracks might have been removed in the meantime
+        for track in self.previously_selected.iter().filter(|t| t.is_available()) {
+            track.select();
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{Project, Reaper};
+use reaper_medium::{
+    BeginLoadProjectStateArgs, ProcessExtensionLineArgs, ProjectConfigExtension,
+    SaveExtensionConfigArgs,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::error::Error;
+
+/// Maximum length of a line which is read from the project state.
+const MAX_LINE_LENGTH: u32 = 4096;
+
+/// Length of the base64-encoded lines which are written to the project state.
+const ENCODED_LINE_LENGTH: usize = 128;
+
+/// Consumers need to implement this trait in order to persist their own per-project data in the
+/// project file.
+///
+/// See [`SerdeProjectConfigExtension`].
+pub trait ProjectDataHandler {
+    type Data: Serialize + DeserializeOwned;
+
+    /// Returns the data which should be saved for the given project or `None` if there's nothing
+    /// to save.
+    ///
+    /// `is_undo` is `true` if the data is saved as part of creating an undo point.
+    fn save_data(&mut self, project: Project, is_undo: bool) -> Option<Self::Data>;
+
+    /// Receives the data which has been saved for the given project before.
+    ///
+    /// `is_undo` is `true` if the data is restored as part of undo/redo.
+    fn load_data(&mut self, project: Project, data: Self::Data, is_undo: bool);
+
+    /// Called before the state of the given project is loaded, also on undo/redo and when
+    /// creating a new project.
+    ///
+    /// The loaded state doesn't necessarily contain data saved by this handler, so this is the
+    /// right place to reset the data of the project.
+    fn begin_load(&mut self, project: Project, is_undo: bool) {
+        let _ = (project, is_undo);
+    }
+}
+
+/// Project config extension which stores the data of a [`ProjectDataHandler`] as a block in the
+/// project file.
+///
+/// The data is serialized as JSON and written as base64-encoded lines:
+///
+/// ```text
+/// <OUR_EXTENSION
+///   eyJ2b2x1bWUiOjAuNX0=
+/// >
+/// ```
+///
+/// Register it via `ReaperSession::plugin_register_add_project_config()`. Data is also saved in
+/// undo points if they are created with a scope that includes `ProjectPart::MiscCfg` (which is
+/// the case for [`Project::undoable()`]).
+#[derive(Debug)]
+pub struct SerdeProjectConfigExtension<H> {
+    tag: String,
+    handler: H,
+}
+
+impl<H: ProjectDataHandler> SerdeProjectConfigExtension<H> {
+    /// Creates the extension.
+    ///
+    /// The tag identifies the block in the project file, so it should be unique, e.g. the name of
+    /// your extension in upper case.
+    pub fn new(tag: impl Into<String>, handler: H) -> Self {
+        Self {
+            tag: tag.into(),
+            handler,
+        }
+    }
+
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    pub fn into_handler(self) -> H {
+        self.handler
+    }
+}
+
+impl<H: ProjectDataHandler> ProjectConfigExtension for SerdeProjectConfigExtension<H> {
+    fn process_extension_line(&mut self, args: ProcessExtensionLineArgs) -> bool {
+        if !is_block_start(args.line.to_str(), &self.tag) {
+            return false;
+        }
+        // Consume all lines of the block, even if the data turns out to be invalid
+        let ctx = args.ctx;
+        let mut encoded = String::new();
+        while let Some(line) = ctx.get_line(MAX_LINE_LENGTH) {
+            let line = line.to_str().trim();
+            if line.starts_with('>') {
+                break;
+            }
+            encoded.push_str(line);
+        }
+        match decode_data(&encoded) {
+            Ok(data) => self
+                .handler
+                .load_data(load_or_save_project(), data, args.is_undo),
+            Err(e) => {
+                slog::warn!(Reaper::get().logger(), "Couldn't load project data";
+                    "tag" => &self.tag,
+                    "error" => %e,
+                );
+            }
+        }
+        true
+    }
+
+    fn save_extension_config(&mut self, args: SaveExtensionConfigArgs) {
+        let data = match self.handler.save_data(load_or_save_project(), args.is_undo) {
+            None => return,
+            Some(d) => d,
+        };
+        let encoded = match encode_data(&data) {
+            Ok(e) => e,
+            Err(e) => {
+                slog::warn!(Reaper::get().logger(), "Couldn't save project data";
+                    "tag" => &self.tag,
+                    "error" => %e,
+                );
+                return;
+            }
+        };
+        let ctx = args.ctx;
+        ctx.add_line(format!("<{}", self.tag));
+        for line in encoded_lines(&encoded) {
+            ctx.add_line(line);
+        }
+        ctx.add_line(">");
+    }
+
+    fn begin_load_project_state(&mut self, args: BeginLoadProjectStateArgs) {
+        self.handler
+            .begin_load(load_or_save_project(), args.is_undo);
+    }
+}
+
+/// Returns the project which is currently loaded or saved.
+fn load_or_save_project() -> Project {
+    let reaper = Reaper::get();
+    reaper
+        .currently_loading_or_saving_project()
+        .unwrap_or_else(|| reaper.current_project())
+}
+
+fn is_block_start(line: &str, tag: &str) -> bool {
+    line.trim_start()
+        .strip_prefix('<')
+        .and_then(|rest| rest.split_whitespace().next())
+        == Some(tag)
+}
+
+fn encoded_lines(encoded: &str) -> impl Iterator<Item = &str> {
+    encoded
+        .as_bytes()
+        .chunks(ENCODED_LINE_LENGTH)
+        .map(|chunk| std::str::from_utf8(chunk).expect("base64 is always ASCII"))
+}
+
+fn encode_data<T: Serialize>(data: &T) -> Result<String, Box<dyn Error>> {
+    let json = serde_json::to_vec(data)?;
+    Ok(base64::encode(json))
+}
+
+fn decode_data<T: DeserializeOwned>(encoded: &str) -> Result<T, Box<dyn Error>> {
+    let json = base64::decode(encoded)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    struct TestData {
+        name: String,
+        values: Vec<u32>,
+    }
+
+    #[test]
+    fn detect_block_start() {
+        // Given
+        let tag = "OUR_EXTENSION";
+        // When
+        // Then
+        assert!(is_block_start("<OUR_EXTENSION", tag));
+        assert!(is_block_start("  <OUR_EXTENSION 1", tag));
+        assert!(!is_block_start("<OUR_EXTENSION_2", tag));
+        assert!(!is_block_start("OUR_EXTENSION", tag));
+    }
+
+    #[test]
+    fn encode_and_decode_data_in_several_lines() {
+        // Given
+        let data = TestData {
+            name: "x".repeat(200),
+            values: vec![1, 2, 3],
+        };
+        // When
+        let encoded = encode_data(&data).unwrap();
+        let lines: Vec<_> = encoded_lines(&encoded).collect();
+        let decoded: TestData = decode_data(&lines.concat()).unwrap();
+        // Then
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|l| l.len() <= ENCODED_LINE_LENGTH));
+        assert_eq!(decoded, data);
+    }
+}
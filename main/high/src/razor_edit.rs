@@ -0,0 +1,379 @@
+use crate::{Guid, Project, Reaper, ReaperResult, Track};
+use reaper_medium::{PositionInSeconds, TrackAttributeKey};
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// Number of bytes we reserve for reading the razor edits of one track.
+const RAZOR_EDITS_BUFFER_SIZE: u32 = 65_536;
+
+/// A razor edit area on a track.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RazorEdit {
+    pub start: PositionInSeconds,
+    pub end: PositionInSeconds,
+    /// GUID of the envelope to which this razor edit applies, `None` if it applies to media
+    /// items.
+    pub envelope_guid: Option<Guid>,
+    /// Vertical extent within the track, `None` if the razor edit covers the complete track
+    /// height.
+    pub lane: Option<RazorEditLane>,
+}
+
+/// Vertical extent of a razor edit, expressed as fractions of the track height (0.0 = top,
+/// 1.0 = bottom).
+///
+/// Relevant if the track shows fixed item lanes.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RazorEditLane {
+    pub top: f64,
+    pub bottom: f64,
+}
+
+/// The razor edits of one track.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TrackRazorEdits {
+    pub track: Track,
+    pub edits: Vec<RazorEdit>,
+}
+
+impl RazorEdit {
+    /// Parses razor edits in the format of `P_RAZOREDITS`.
+    ///
+    /// Needs REAPER only if the text contains envelope razor edits (for parsing the GUIDs).
+    pub fn parse_basic(text: &str) -> Result<Vec<RazorEdit>, &'static str> {
+        parse_raw_basic(text)?
+            .into_iter()
+            .map(RawRazorEdit::into_razor_edit)
+            .collect()
+    }
+
+    /// Parses razor edits in the format of `P_RAZOREDITS_EXT`.
+    ///
+    /// Needs REAPER only if the text contains envelope razor edits (for parsing the GUIDs).
+    pub fn parse_ext(text: &str) -> Result<Vec<RazorEdit>, &'static str> {
+        parse_raw_ext(text)?
+            .into_iter()
+            .map(RawRazorEdit::into_razor_edit)
+            .collect()
+    }
+
+    /// Formats the given razor edits in the format of `P_RAZOREDITS`.
+    ///
+    /// This format can't express lanes, so they are ignored.
+    pub fn format_basic(edits: &[RazorEdit]) -> String {
+        let raw_edits: Vec<_> = edits.iter().map(RawRazorEdit::from_razor_edit).collect();
+        format_raw_basic(&raw_edits)
+    }
+
+    /// Formats the given razor edits in the format of `P_RAZOREDITS_EXT`.
+    pub fn format_ext(edits: &[RazorEdit]) -> String {
+        let raw_edits: Vec<_> = edits.iter().map(RawRazorEdit::from_razor_edit).collect();
+        format_raw_ext(&raw_edits)
+    }
+}
+
+impl Track {
+    /// Returns the razor edits of this track.
+    pub fn razor_edits(&self) -> ReaperResult<Vec<RazorEdit>> {
+        let reaper = Reaper::get().medium_reaper();
+        // The extended format is not available in older REAPER versions
+        let ext = unsafe {
+            reaper.get_set_media_track_info_string_get(
+                self.raw(),
+                TrackAttributeKey::RazorEditsExt,
+                RAZOR_EDITS_BUFFER_SIZE,
+            )
+        };
+        let edits = match ext {
+            Ok(text) => RazorEdit::parse_ext(text.to_str())?,
+            Err(_) => {
+                let text = unsafe {
+                    reaper.get_set_media_track_info_string_get(
+                        self.raw(),
+                        TrackAttributeKey::RazorEdits,
+                        RAZOR_EDITS_BUFFER_SIZE,
+                    )?
+                };
+                RazorEdit::parse_basic(text.to_str())?
+            }
+        };
+        Ok(edits)
+    }
+
+    /// Replaces the razor edits of this track with the given ones.
+    ///
+    /// Pass an empty slice in order to remove all razor edits of this track.
+    pub fn set_razor_edits(&self, edits: &[RazorEdit]) -> ReaperResult<()> {
+        let (key, text) = if edits.iter().any(|e| e.lane.is_some()) {
+            (
+                TrackAttributeKey::RazorEditsExt,
+                RazorEdit::format_ext(edits),
+            )
+        } else {
+            (
+                TrackAttributeKey::RazorEdits,
+                RazorEdit::format_basic(edits),
+            )
+        };
+        unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .get_set_media_track_info_string_set(self.raw(), key, text)?;
+        }
+        Ok(())
+    }
+}
+
+impl Project {
+    /// Returns the razor edits of all tracks in this project which have razor edits.
+    pub fn razor_edits(self) -> ReaperResult<Vec<TrackRazorEdits>> {
+        let mut result = vec![];
+        for track in self.tracks() {
+            let edits = track.razor_edits()?;
+            if !edits.is_empty() {
+                result.push(TrackRazorEdits { track, edits });
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Razor edit as it appears in the string format, with the envelope GUID still being text.
+#[derive(Clone, PartialEq, Debug)]
+struct RawRazorEdit {
+    start: f64,
+    end: f64,
+    /// Envelope GUID including braces, empty if the razor edit applies to media items.
+    envelope_guid: String,
+    lane: Option<RazorEditLane>,
+}
+
+impl RawRazorEdit {
+    fn into_razor_edit(self) -> Result<RazorEdit, &'static str> {
+        let envelope_guid = if self.envelope_guid.is_empty() {
+            None
+        } else {
+            Some(Guid::from_str(&self.envelope_guid)?)
+        };
+        let edit = RazorEdit {
+            start: PositionInSeconds::new(self.start),
+            end: PositionInSeconds::new(self.end),
+            envelope_guid,
+            lane: self.lane,
+        };
+        Ok(edit)
+    }
+
+    fn from_razor_edit(edit: &RazorEdit) -> RawRazorEdit {
+        RawRazorEdit {
+            start: edit.start.get(),
+            end: edit.end.get(),
+            envelope_guid: edit
+                .envelope_guid
+                .map(|g| g.to_string_with_braces())
+                .unwrap_or_default(),
+            lane: edit.lane,
+        }
+    }
+}
+
+/// Parses `start end "GUID"` triples separated by spaces.
+fn parse_raw_basic(text: &str) -> Result<Vec<RawRazorEdit>, &'static str> {
+    let tokens: Vec<_> = text.split_whitespace().collect();
+    if tokens.len() % 3 != 0 {
+        return Err("razor edits must consist of start, end and envelope GUID");
+    }
+    tokens
+        .chunks(3)
+        .map(|t| {
+            let edit = RawRazorEdit {
+                start: parse_number(t[0])?,
+                end: parse_number(t[1])?,
+                envelope_guid: parse_quoted(t[2])?.to_string(),
+                lane: None,
+            };
+            Ok(edit)
+        })
+        .collect()
+}
+
+/// Parses `start end "GUID" top bottom` entries separated by commas.
+fn parse_raw_ext(text: &str) -> Result<Vec<RawRazorEdit>, &'static str> {
+    text.split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let t: Vec<_> = entry.split_whitespace().collect();
+            if t.len() != 5 {
+                return Err("razor edit must consist of start, end, envelope GUID, top and bottom");
+            }
+            let top = parse_number(t[3])?;
+            let bottom = parse_number(t[4])?;
+            let edit = RawRazorEdit {
+                start: parse_number(t[0])?,
+                end: parse_number(t[1])?,
+                envelope_guid: parse_quoted(t[2])?.to_string(),
+                lane: if top <= 0.0 && bottom >= 1.0 {
+                    None
+                } else {
+                    Some(RazorEditLane { top, bottom })
+                },
+            };
+            Ok(edit)
+        })
+        .collect()
+}
+
+fn format_raw_basic(edits: &[RawRazorEdit]) -> String {
+    let mut result = String::new();
+    for (i, e) in edits.iter().enumerate() {
+        if i > 0 {
+            result.push(' ');
+        }
+        write!(result, "{} {} \"{}\"", e.start, e.end, e.envelope_guid).unwrap();
+    }
+    result
+}
+
+fn format_raw_ext(edits: &[RawRazorEdit]) -> String {
+    let mut result = String::new();
+    for (i, e) in edits.iter().enumerate() {
+        if i > 0 {
+            result.push(',');
+        }
+        let lane = e.lane.unwrap_or(RazorEditLane {
+            top: 0.0,
+            bottom: 1.0,
+        });
+        write!(
+            result,
+            "{} {} \"{}\" {} {}",
+            e.start, e.end, e.envelope_guid, lane.top, lane.bottom
+        )
+        .unwrap();
+    }
+    result
+}
+
+fn parse_number(token: &str) -> Result<f64, &'static str> {
+    token
+        .parse()
+        .ok()
+        .filter(|n: &f64| n.is_finite())
+        .ok_or("razor edit contains invalid number")
+}
+
+fn parse_quoted(token: &str) -> Result<&str, &'static str> {
+    token
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .ok_or("razor edit envelope GUID must be quoted")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENVELOPE_GUID: &str = "{8E3D1C2A-6B1F-4E1D-9C3A-2D7B5F0A1E44}";
+
+    #[test]
+    fn parse_basic() {
+        // Given
+        let text = format!(r#"1.5 3.00000000000000 "" 4 6 "{}""#, ENVELOPE_GUID);
+        // When
+        let edits = parse_raw_basic(&text).unwrap();
+        // Then
+        assert_eq!(
+            edits,
+            vec![
+                RawRazorEdit {
+                    start: 1.5,
+                    end: 3.0,
+                    envelope_guid: String::new(),
+                    lane: None
+                },
+                RawRazorEdit {
+                    start: 4.0,
+                    end: 6.0,
+                    envelope_guid: ENVELOPE_GUID.to_string(),
+                    lane: None
+                }
+            ]
+        );
+        assert!(parse_raw_basic("").unwrap().is_empty());
+        assert!(parse_raw_basic("1 2").is_err());
+        assert!(parse_raw_basic("1 2 {ABC}").is_err());
+        assert!(parse_raw_basic("a 2 \"\"").is_err());
+        assert!(parse_raw_basic("1 inf \"\"").is_err());
+    }
+
+    #[test]
+    fn parse_ext() {
+        // Given
+        let text = format!(r#"1 2 "" 0 0.5,3 4 "{}" 0 1"#, ENVELOPE_GUID);
+        // When
+        let edits = parse_raw_ext(&text).unwrap();
+        // Then
+        assert_eq!(edits.len(), 2);
+        assert_eq!(
+            edits[0].lane,
+            Some(RazorEditLane {
+                top: 0.0,
+                bottom: 0.5
+            })
+        );
+        assert_eq!(edits[1].envelope_guid, ENVELOPE_GUID);
+        assert_eq!(edits[1].lane, None);
+        assert!(parse_raw_ext("").unwrap().is_empty());
+        assert!(parse_raw_ext(r#"1 2 "" 0"#).is_err());
+    }
+
+    #[test]
+    fn format_round_trip() {
+        // Given
+        let edits = vec![
+            RawRazorEdit {
+                start: 0.25,
+                end: 2.0,
+                envelope_guid: String::new(),
+                lane: Some(RazorEditLane {
+                    top: 0.5,
+                    bottom: 1.0,
+                }),
+            },
+            RawRazorEdit {
+                start: 3.0,
+                end: 4.5,
+                envelope_guid: ENVELOPE_GUID.to_string(),
+                lane: None,
+            },
+        ];
+        // When
+        let basic = format_raw_basic(&edits);
+        let ext = format_raw_ext(&edits);
+        // Then
+        assert_eq!(basic, format!(r#"0.25 2 "" 3 4.5 "{}""#, ENVELOPE_GUID));
+        assert_eq!(
+            ext,
+            format!(r#"0.25 2 "" 0.5 1,3 4.5 "{}" 0 1"#, ENVELOPE_GUID)
+        );
+        assert_eq!(parse_raw_ext(&ext).unwrap(), edits);
+        assert_eq!(parse_raw_basic(&basic).unwrap()[1], edits[1]);
+    }
+
+    #[test]
+    fn item_edits_without_reaper() {
+        // Given
+        let edits = vec![RazorEdit {
+            start: PositionInSeconds::new(1.0),
+            end: PositionInSeconds::new(2.0),
+            envelope_guid: None,
+            lane: None,
+        }];
+        // When
+        let text = RazorEdit::format_basic(&edits);
+        // Then
+        assert_eq!(text, r#"1 2 """#);
+        let parsed = RazorEdit::parse_basic(&text).unwrap();
+        assert!(parsed == edits);
+    }
+}
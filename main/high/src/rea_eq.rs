@@ -0,0 +1,249 @@
+use crate::{Fx, FxParameter, Reaper};
+use reaper_medium::{
+    Db, EqBandRef, EqBandType, EqParamType, EqParamValue, Hz, ReaperFunctionError,
+};
+
+/// A ReaEQ instance with typed access to its bands.
+///
+/// Obtain it via [`Track::rea_eq`](crate::Track::rea_eq).
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ReaEq {
+    fx: Fx,
+}
+
+/// A single band of a ReaEQ instance.
+///
+/// Values are read from the parameter's formatted value because REAPER doesn't offer a way to
+/// convert normalized ReaEQ values into Hz or dB. Use [`EqBand::parameter`] if you need the
+/// normalized values.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct EqBand {
+    fx: Fx,
+    band_type: EqBandType,
+    index: u32,
+    frequency_param_index: Option<u32>,
+    gain_param_index: Option<u32>,
+    q_param_index: Option<u32>,
+}
+
+impl ReaEq {
+    pub(crate) fn new(fx: Fx) -> ReaEq {
+        ReaEq { fx }
+    }
+
+    pub fn fx(&self) -> &Fx {
+        &self.fx
+    }
+
+    /// Returns all bands in the order of their parameters.
+    pub fn bands(&self) -> Vec<EqBand> {
+        let (track, location) = self.fx.track_and_location();
+        let mut bands: Vec<EqBand> = vec![];
+        for param_index in 0..self.fx.parameter_count() {
+            let result = unsafe {
+                Reaper::get().medium_reaper().track_fx_get_eq_param(
+                    track.raw(),
+                    location,
+                    param_index,
+                )
+            };
+            let (band_type, index, param_type) = match result {
+                Ok(r) => match r.band {
+                    EqBandRef::MasterGain => continue,
+                    EqBandRef::Band { band_type, index } => (band_type, index, r.param_type),
+                },
+                Err(_) => continue,
+            };
+            let existing_position = bands
+                .iter()
+                .position(|b| b.band_type == band_type && b.index == index);
+            let position = existing_position.unwrap_or_else(|| {
+                bands.push(EqBand {
+                    fx: self.fx.clone(),
+                    band_type,
+                    index,
+                    frequency_param_index: None,
+                    gain_param_index: None,
+                    q_param_index: None,
+                });
+                bands.len() - 1
+            });
+            let band = &mut bands[position];
+            match param_type {
+                EqParamType::Frequency => band.frequency_param_index = Some(param_index),
+                EqParamType::Gain => band.gain_param_index = Some(param_index),
+                EqParamType::Q => band.q_param_index = Some(param_index),
+                EqParamType::Unknown(_) => {}
+            }
+        }
+        bands
+    }
+
+    /// Returns the `index`th band of the given type (0 = first band of that type).
+    pub fn band(&self, band_type: EqBandType, index: u32) -> Option<EqBand> {
+        self.bands()
+            .into_iter()
+            .find(|b| b.band_type == band_type && b.index == index)
+    }
+
+    pub fn set_master_gain(&self, gain: Db) -> Result<(), ReaperFunctionError> {
+        set_eq_param(
+            &self.fx,
+            EqBandRef::MasterGain,
+            EqParamType::Gain,
+            EqParamValue::Native(gain.get()),
+        )
+    }
+}
+
+impl EqBand {
+    pub fn band_type(&self) -> EqBandType {
+        self.band_type
+    }
+
+    /// 0 = first band of this type, 1 = second band of this type etc.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn band_ref(&self) -> EqBandRef {
+        EqBandRef::Band {
+            band_type: self.band_type,
+            index: self.index,
+        }
+    }
+
+    /// Returns the underlying FX parameter, e.g. for working with normalized values.
+    pub fn parameter(&self, param_type: EqParamType) -> Option<FxParameter> {
+        let index = match param_type {
+            EqParamType::Frequency => self.frequency_param_index,
+            EqParamType::Gain => self.gain_param_index,
+            EqParamType::Q => self.q_param_index,
+            EqParamType::Unknown(_) => None,
+        }?;
+        Some(self.fx.parameter_by_index(index))
+    }
+
+    pub fn frequency(&self) -> Option<Hz> {
+        let hz = self.native_value(EqParamType::Frequency)?;
+        if hz <= 0.0 {
+            return None;
+        }
+        Some(Hz::new(hz))
+    }
+
+    pub fn set_frequency(&self, frequency: Hz) -> Result<(), ReaperFunctionError> {
+        self.set_native_value(EqParamType::Frequency, frequency.get())
+    }
+
+    pub fn gain(&self) -> Option<Db> {
+        let db = self.native_value(EqParamType::Gain)?;
+        Some(Db::new(db.max(Db::MINUS_INF.get())))
+    }
+
+    pub fn set_gain(&self, gain: Db) -> Result<(), ReaperFunctionError> {
+        self.set_native_value(EqParamType::Gain, gain.get())
+    }
+
+    /// Returns the bandwidth in octaves or the Q value, depending on the ReaEQ settings.
+    pub fn q(&self) -> Option<f64> {
+        self.native_value(EqParamType::Q)
+    }
+
+    pub fn set_q(&self, q: f64) -> Result<(), ReaperFunctionError> {
+        self.set_native_value(EqParamType::Q, q)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        let (track, location) = self.fx.track_and_location();
+        unsafe {
+            Reaper::get().medium_reaper().track_fx_get_eq_band_enabled(
+                track.raw(),
+                location,
+                self.band_ref(),
+            )
+        }
+    }
+
+    pub fn set_enabled(&self, enabled: bool) -> Result<(), ReaperFunctionError> {
+        let (track, location) = self.fx.track_and_location();
+        unsafe {
+            Reaper::get().medium_reaper().track_fx_set_eq_band_enabled(
+                track.raw(),
+                location,
+                self.band_ref(),
+                enabled,
+            )
+        }
+    }
+
+    fn native_value(&self, param_type: EqParamType) -> Option<f64> {
+        let formatted_value = self.parameter(param_type)?.formatted_value().ok()?;
+        parse_formatted_eq_value(formatted_value.to_str())
+    }
+
+    fn set_native_value(
+        &self,
+        param_type: EqParamType,
+        value: f64,
+    ) -> Result<(), ReaperFunctionError> {
+        set_eq_param(
+            &self.fx,
+            self.band_ref(),
+            param_type,
+            EqParamValue::Native(value),
+        )
+    }
+}
+
+fn set_eq_param(
+    fx: &Fx,
+    band: EqBandRef,
+    param_type: EqParamType,
+    value: EqParamValue,
+) -> Result<(), ReaperFunctionError> {
+    let (track, location) = fx.track_and_location();
+    unsafe {
+        Reaper::get().medium_reaper().track_fx_set_eq_param(
+            track.raw(),
+            location,
+            band,
+            param_type,
+            value,
+        )
+    }
+}
+
+/// Parses ReaEQ display values such as "1000.0", "2.50k", "+3.5", "-inf" or "2.00 oct".
+fn parse_formatted_eq_value(text: &str) -> Option<f64> {
+    let text = text.trim();
+    if text.starts_with("-inf") {
+        return Some(f64::NEG_INFINITY);
+    }
+    let number_end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+        .unwrap_or(text.len());
+    let number: f64 = text[..number_end].parse().ok()?;
+    let factor = match text[number_end..].trim_start().chars().next() {
+        Some('k') | Some('K') => 1000.0,
+        _ => 1.0,
+    };
+    Some(number * factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_formatted_values() {
+        assert_eq!(parse_formatted_eq_value("1000.0"), Some(1000.0));
+        assert_eq!(parse_formatted_eq_value("2.50k"), Some(2500.0));
+        assert_eq!(parse_formatted_eq_value("12.0 kHz"), Some(12000.0));
+        assert_eq!(parse_formatted_eq_value("+3.5"), Some(3.5));
+        assert_eq!(parse_formatted_eq_value("-6.0 dB"), Some(-6.0));
+        assert_eq!(parse_formatted_eq_value("-inf"), Some(f64::NEG_INFINITY));
+        assert_eq!(parse_formatted_eq_value("2.00 oct"), Some(2.0));
+        assert_eq!(parse_formatted_eq_value("off"), None);
+    }
+}
@@ -11,6 +11,7 @@ use crate::undo_block::UndoBlock;
 use crate::ActionKind::Toggleable;
 use crate::{
     create_default_console_msg_formatter, create_reaper_panic_hook, create_std_logger, Project,
+    TrackMeterPollerRegistry,
 };
 use once_cell::sync::Lazy;
 use reaper_low::{raw, register_plugin_destroy_hook};
@@ -123,6 +124,7 @@ impl ReaperBuilder {
                         }),
                     }))),
                     helper_task_sender,
+                    track_meter_pollers: Default::default(),
                 };
                 INSTANCE = Some(reaper);
                 register_plugin_destroy_hook(|| INSTANCE = None);
@@ -204,6 +206,7 @@ pub struct Reaper {
     audio_thread_task_sender: Sender<AudioThreadTaskOp>,
     session_status: RefCell<SessionStatus>,
     helper_task_sender: crossbeam_channel::Sender<HelperTask>,
+    pub(crate) track_meter_pollers: RefCell<TrackMeterPollerRegistry>,
 }
 
 #[derive(Debug)]
@@ -322,6 +325,12 @@ impl Reaper {
         }
     }
 
+    /// Like [`get()`](#method.get) but returns `None` if REAPER has not been set up yet or has
+    /// already been torn down.
+    pub(crate) fn try_get() -> Option<&'static Reaper> {
+        unsafe { INSTANCE.as_ref() }
+    }
+
     pub fn logger(&self) -> &slog::Logger {
         &self.logger
     }
@@ -407,6 +416,13 @@ impl Reaper {
         self.medium_session.borrow_mut()
     }
 
+    /// Like [`medium_session()`](#method.medium_session) but returns `None` instead of panicking
+    /// if the session is currently borrowed.
+    pub(crate) fn try_medium_session(&self) -> Option<RefMut<reaper_medium::ReaperSession>> {
+        self.require_main_thread();
+        self.medium_session.try_borrow_mut().ok()
+    }
+
     pub(crate) fn show_console_msg_thread_safe<'a>(&self, msg: impl Into<ReaperStringArg<'a>>) {
         if self.is_in_main_thread() {
             self.show_console_msg(msg);
@@ -2,15 +2,16 @@
 //! just access to `reaper_medium::Reaper` - without all the advanced stuff like subjects,
 //! channels etc. Although they end up in the same struct, this gives a little bit of structure.
 use crate::{
-    Action, Fx, FxChain, FxParameter, Guid, MidiInputDevice, MidiOutputDevice, Project, Reaper,
-    Section,
+    Action, Fx, FxChain, FxParameter, Guid, InstalledFx, MidiInputDevice, MidiOutputDevice,
+    Project, Reaper, Section,
 };
 use helgoboss_midi::ShortMessage;
 use reaper_medium::{
     AudioDeviceAttributeKey, CommandId, EnumPitchShiftModesResult, GetLastTouchedFxResult,
     GlobalAutomationModeOverride, Hwnd, Hz, MidiInputDeviceId, MidiOutputDeviceId, PitchShiftMode,
-    PitchShiftSubMode, ProjectRef, ReaperStr, ReaperString, ReaperStringArg, ReaperVersion,
-    ResampleMode, SectionId, StuffMidiMessageTarget, TrackLocation,
+    PitchShiftSubMode, ProjectRef, ReaperFunctionError, ReaperStr, ReaperString, ReaperStringArg,
+    ReaperVersion, ResampleMode, RgbColor, SectionId, StuffMidiMessageTarget,
+    ThemeColorTransformBehavior, TrackLocation,
 };
 use std::fmt::Debug;
 use std::path::PathBuf;
@@ -98,6 +99,11 @@ impl Reaper {
         self.medium_reaper.get_resource_path(|p| p.to_owned())
     }
 
+    /// Returns a catalogue of all installed FX, e.g. for offering a searchable FX browser.
+    pub fn installed_fx(&self) -> InstalledFx {
+        InstalledFx::load()
+    }
+
     // Attention: Returns normal fx only, not input fx!
     // This is not reliable! After REAPER start no focused Fx can be found!
     pub fn focused_fx(&self) -> Option<Fx> {
@@ -213,6 +219,34 @@ impl Reaper {
             .set_global_automation_override(mode_override);
     }
 
+    /// Returns the given theme color (e.g. "col_main_bg2"), including transformations.
+    pub fn theme_color<'a>(
+        &self,
+        ini_key: impl Into<ReaperStringArg<'a>>,
+    ) -> Result<RgbColor, ReaperFunctionError> {
+        let native_color = self
+            .medium_reaper()
+            .get_theme_color(ini_key, ThemeColorTransformBehavior::ApplyTransforms)?;
+        Ok(self.medium_reaper().color_from_native(native_color))
+    }
+
+    /// Temporarily overrides the given theme color or restores the theme's own color if `None`.
+    ///
+    /// The UI is not refreshed automatically, use the medium-level `update_arrange()` for that.
+    pub fn set_theme_color<'a>(
+        &self,
+        ini_key: impl Into<ReaperStringArg<'a>>,
+        color: Option<RgbColor>,
+    ) -> Result<(), ReaperFunctionError> {
+        let native_color = color.map(|c| self.medium_reaper().color_to_native(c));
+        self.medium_reaper().set_theme_color(
+            ini_key,
+            native_color,
+            ThemeColorTransformBehavior::ApplyTransforms,
+        )?;
+        Ok(())
+    }
+
     pub fn generate_guid(&self) -> Guid {
         Guid::new(Reaper::get().medium_reaper().gen_guid())
     }
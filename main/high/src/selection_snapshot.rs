@@ -0,0 +1,146 @@
+use crate::{Item, Project, Reaper, Track};
+use reaper_medium::{
+    CursorContext, GetLoopTimeRange2Result, MasterTrackBehavior, PositionInSeconds,
+    SetEditCurPosOptions, TrackEnvelope,
+};
+use std::panic::AssertUnwindSafe;
+
+/// The selection state of a project at a certain point in time.
+///
+/// Create it via [`Project::selection_snapshot`] and bring the project back into that state via
+/// [`SelectionSnapshot::restore`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct SelectionSnapshot {
+    project: Project,
+    tracks: Vec<Track>,
+    items: Vec<Item>,
+    envelope: Option<TrackEnvelope>,
+    cursor_context: Option<CursorContext>,
+    time_selection: Option<GetLoopTimeRange2Result>,
+    edit_cursor_position: PositionInSeconds,
+}
+
+impl SelectionSnapshot {
+    pub fn project(&self) -> Project {
+        self.project
+    }
+
+    pub fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    pub fn envelope(&self) -> Option<TrackEnvelope> {
+        self.envelope
+    }
+
+    /// Returns the focused part of the arrange view (`None` if unknown).
+    pub fn cursor_context(&self) -> Option<CursorContext> {
+        self.cursor_context
+    }
+
+    pub fn time_selection(&self) -> Option<GetLoopTimeRange2Result> {
+        self.time_selection
+    }
+
+    pub fn edit_cursor_position(&self) -> PositionInSeconds {
+        self.edit_cursor_position
+    }
+
+    /// Restores the captured selection.
+    ///
+    /// Tracks, items and envelopes which don't exist anymore are skipped. If no envelope was
+    /// selected, the currently selected envelope gets unselected. Because (un)selecting an
+    /// envelope focuses the envelope context, the captured cursor context is restored afterwards.
+    pub fn restore(&self) {
+        let project = self.project;
+        if !project.is_available() {
+            return;
+        }
+        let reaper = Reaper::get().medium_reaper();
+        project.unselect_all_tracks();
+        for track in self.tracks.iter().filter(|t| t.is_available()) {
+            track.select();
+        }
+        for item in project.selected_items().collect::<Vec<_>>() {
+            item.set_selected(false);
+        }
+        for item in &self.items {
+            if reaper.validate_ptr_2(project.context(), item.raw()) {
+                item.set_selected(true);
+            }
+        }
+        let envelope = self
+            .envelope
+            .filter(|e| reaper.validate_ptr_2(project.context(), *e));
+        if envelope.is_some() || reaper.get_selected_envelope(project.context()).is_some() {
+            // Passing no envelope unselects the currently selected one
+            unsafe {
+                reaper.set_cursor_context(CursorContext::Envelopes, envelope);
+            }
+        }
+        if let Some(context) = self.cursor_context {
+            if context != CursorContext::Envelopes || envelope.is_none() {
+                // The envelope passed here only matters for the envelope context
+                unsafe {
+                    reaper.set_cursor_context(context, None);
+                }
+            }
+        }
+        match self.time_selection {
+            None => project.clear_time_selection(),
+            Some(r) => project.set_time_selection(r.start, r.end),
+        }
+        project.set_edit_cursor_position(
+            self.edit_cursor_position,
+            SetEditCurPosOptions {
+                move_view: false,
+                seek_play: false,
+            },
+        );
+    }
+}
+
+impl Project {
+    /// Captures selected tracks, items and envelope, the cursor context, the time selection and
+    /// the edit cursor position.
+    pub fn selection_snapshot(self) -> SelectionSnapshot {
+        SelectionSnapshot {
+            project: self,
+            tracks: self
+                .selected_tracks(MasterTrackBehavior::IncludeMasterTrack)
+                .collect(),
+            items: self.selected_items().collect(),
+            envelope: Reaper::get()
+                .medium_reaper()
+                .get_selected_envelope(self.context()),
+            cursor_context: Reaper::get().medium_reaper().get_cursor_context_2(false),
+            time_selection: self.time_selection(),
+            edit_cursor_position: self.edit_cursor_position(),
+        }
+    }
+
+    /// Executes the given operation and restores the selection afterwards, even if the operation
+    /// panics.
+    pub fn with_preserved_selection<R>(self, f: impl FnOnce() -> R) -> R {
+        let _guard = SelectionGuard(self.selection_snapshot());
+        f()
+    }
+}
+
+// Restores the selection when dropped (RAII).
+struct SelectionGuard(SelectionSnapshot);
+
+impl Drop for SelectionGuard {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            // Panicking again while unwinding would abort the process
+            let _ = std::panic::catch_unwind(AssertUnwindSafe(|| self.0.restore()));
+        } else {
+            self.0.restore();
+        }
+    }
+}
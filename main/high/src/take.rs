@@ -1,5 +1,8 @@
+use crate::item::{custom_color_from_raw, custom_color_to_raw};
 use crate::{FxChain, OwnedSource, Reaper, ReaperSource, Track};
-use reaper_medium::{MediaItemTake, PositionInSeconds, ReaperFunctionError, TakeAttributeKey};
+use reaper_medium::{
+    MediaItemTake, PositionInSeconds, ReaperFunctionError, RgbColor, TakeAttributeKey,
+};
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct Take {
@@ -51,6 +54,26 @@ impl Take {
         previous_source.map(OwnedSource::new)
     }
 
+    pub fn custom_color(&self) -> Option<RgbColor> {
+        let value = unsafe {
+            Reaper::get()
+                .medium_reaper
+                .get_media_item_take_info_value(self.raw, TakeAttributeKey::CustomColor)
+        };
+        custom_color_from_raw(value)
+    }
+
+    /// Sets or removes the custom color of this take.
+    pub fn set_custom_color(&self, color: Option<RgbColor>) -> Result<(), ReaperFunctionError> {
+        unsafe {
+            Reaper::get().medium_reaper.set_media_item_take_info_value(
+                self.raw,
+                TakeAttributeKey::CustomColor,
+                custom_color_to_raw(color),
+            )
+        }
+    }
+
     pub fn set_start_offset(&self, length: PositionInSeconds) -> Result<(), ReaperFunctionError> {
         unsafe {
             Reaper::get().medium_reaper.set_media_item_take_info_value(
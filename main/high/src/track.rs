@@ -6,10 +6,11 @@ use crate::guid::Guid;
 use crate::track_route::TrackRoute;
 
 use crate::{
-    Chunk, ChunkRegion, Item, Pan, Project, Reaper, SendPartnerType, TrackRoutePartner, Volume,
-    Width,
+    Chunk, ChunkRegion, Envelope, Item, Pan, Project, ReaEq, Reaper, ReaperResult, SendPartnerType,
+    TrackFolderCompactState, TrackRoutePartner, Volume, Width,
 };
 
+use enumflags2::BitFlags;
 use reaper_medium::NotificationBehavior::NotifyAll;
 use reaper_medium::ProjectContext::Proj;
 use reaper_medium::SendTarget::OtherTrack;
@@ -17,9 +18,10 @@ use reaper_medium::TrackAttributeKey::{RecArm, RecInput, RecMon, Selected, Solo}
 use reaper_medium::ValueChange::Absolute;
 use reaper_medium::{
     AutomationMode, ChunkCacheHint, GangBehavior, GlobalAutomationModeOverride,
-    InputMonitoringMode, MediaTrack, ReaProject, ReaperFunctionError, ReaperString,
-    ReaperStringArg, RecordArmMode, RecordingInput, RgbColor, SoloMode, TrackArea,
-    TrackAttributeKey, TrackLocation, TrackSendCategory, TrackSendDirection,
+    InputMonitoringMode, MediaTrack, MidiHardwareOutput, ReaProject, ReaperFunctionError,
+    ReaperString, ReaperStringArg, RecordArmMode, RecordingInput, RecordingMode, RgbColor,
+    SendChannelMapping, SendTarget, SoloMode, TrackArea, TrackAttributeKey, TrackLocation,
+    TrackPerformanceFlag, TrackSendCategory, TrackSendDirection, VuMode,
 };
 use std::convert::TryInto;
 use std::hash::{Hash, Hasher};
@@ -118,6 +120,29 @@ impl Track {
         Ok(Item::new(raw_item))
     }
 
+    pub fn envelope_count(&self) -> u32 {
+        self.load_and_check_if_necessary_or_complain();
+        unsafe {
+            Reaper::get()
+                .medium_reaper
+                .count_track_envelopes(self.raw())
+        }
+    }
+
+    pub fn envelopes(&self) -> impl Iterator<Item = Envelope> + ExactSizeIterator + 'static {
+        self.load_and_check_if_necessary_or_complain();
+        let raw = self.raw();
+        (0..self.envelope_count()).map(move |i| {
+            let envelope = unsafe {
+                Reaper::get()
+                    .medium_reaper()
+                    .get_track_envelope(raw, i)
+                    .unwrap()
+            };
+            Envelope::new(envelope)
+        })
+    }
+
     // TODO-low It's really annoying to always have to unwrap an option even if we know this is not
     //  a master track. Maybe we should have different types: Track, MasterTrack, NormalTrack
     pub fn name(&self) -> Option<ReaperString> {
@@ -141,6 +166,24 @@ impl Track {
         }
     }
 
+    /// Sets or removes the custom color of this track.
+    pub fn set_custom_color(&self, color: Option<RgbColor>) {
+        self.load_and_check_if_necessary_or_complain();
+        let reaper = Reaper::get().medium_reaper();
+        unsafe {
+            match color {
+                None => {
+                    let _ = reaper.set_media_track_info_value(
+                        self.raw(),
+                        TrackAttributeKey::CustomColor,
+                        0.0,
+                    );
+                }
+                Some(c) => reaper.set_track_color(self.raw(), reaper.color_to_native(c)),
+            }
+        }
+    }
+
     pub fn input_monitoring_mode(&self) -> InputMonitoringMode {
         self.load_and_check_if_necessary_or_complain();
         unsafe {
@@ -310,6 +353,39 @@ impl Track {
         result as _
     }
 
+    pub(crate) fn set_folder_depth_change(&self, change: i32) -> Result<(), ReaperFunctionError> {
+        unsafe {
+            Reaper::get().medium_reaper().set_media_track_info_value(
+                self.raw(),
+                TrackAttributeKey::FolderDepth,
+                change as _,
+            )
+        }
+    }
+
+    pub fn folder_compact_state(&self) -> TrackFolderCompactState {
+        let result = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .get_media_track_info_value(self.raw(), TrackAttributeKey::FolderCompact)
+        };
+        TrackFolderCompactState::from_raw(result as _)
+    }
+
+    /// Only has an effect on folders.
+    pub fn set_folder_compact_state(
+        &self,
+        state: TrackFolderCompactState,
+    ) -> Result<(), ReaperFunctionError> {
+        unsafe {
+            Reaper::get().medium_reaper().set_media_track_info_value(
+                self.raw(),
+                TrackAttributeKey::FolderCompact,
+                state.to_raw() as _,
+            )
+        }
+    }
+
     pub fn channel_count(&self) -> u32 {
         let result = unsafe {
             Reaper::get()
@@ -610,6 +686,191 @@ impl Track {
         }
     }
 
+    /// Returns the current height of the track control panel.
+    pub fn height(&self) -> TrackHeight {
+        TrackHeight {
+            tcp: self.int_attribute(TrackAttributeKey::TcpH) as u32,
+            tcp_with_envelopes: self.int_attribute(TrackAttributeKey::WndH) as u32,
+        }
+    }
+
+    /// Returns the custom height of the track control panel (`None` if the default height is
+    /// used).
+    pub fn height_override(&self) -> Option<u32> {
+        let height = self.int_attribute(TrackAttributeKey::HeightOverride);
+        if height <= 0 {
+            return None;
+        }
+        Some(height as u32)
+    }
+
+    pub fn set_height_override(&self, height: Option<u32>) -> Result<(), ReaperFunctionError> {
+        let raw_height = height.unwrap_or(0) as i32;
+        self.set_int_attribute(TrackAttributeKey::HeightOverride, raw_height)?;
+        Reaper::get()
+            .medium_reaper()
+            .track_list_adjust_windows_minor();
+        Ok(())
+    }
+
+    pub fn height_is_locked(&self) -> bool {
+        self.int_attribute(TrackAttributeKey::HeightLock) > 0
+    }
+
+    /// Locks or unlocks the track height.
+    ///
+    /// Locking only has an effect if a height override is set.
+    pub fn set_height_locked(&self, locked: bool) -> Result<(), ReaperFunctionError> {
+        self.set_int_attribute(TrackAttributeKey::HeightLock, locked as i32)?;
+        Reaper::get()
+            .medium_reaper()
+            .track_list_adjust_windows_minor();
+        Ok(())
+    }
+
+    /// Returns the current position and size of the mixer control panel in pixels (position
+    /// relative to the mixer container).
+    pub fn mcp_bounds(&self) -> TrackPanelBounds {
+        TrackPanelBounds {
+            x: self.int_attribute(TrackAttributeKey::McpX),
+            y: self.int_attribute(TrackAttributeKey::McpY),
+            width: self.int_attribute(TrackAttributeKey::McpW) as u32,
+            height: self.int_attribute(TrackAttributeKey::McpH) as u32,
+        }
+    }
+
+    pub fn performance_flags(&self) -> BitFlags<TrackPerformanceFlag> {
+        let raw = self.int_attribute(TrackAttributeKey::PerfFlags);
+        BitFlags::from_bits_truncate(raw as u32)
+    }
+
+    pub fn set_performance_flags(
+        &self,
+        flags: BitFlags<TrackPerformanceFlag>,
+    ) -> Result<(), ReaperFunctionError> {
+        self.set_int_attribute(TrackAttributeKey::PerfFlags, flags.bits() as i32)?;
+        Reaper::get()
+            .medium_reaper()
+            .track_list_adjust_windows_minor();
+        Ok(())
+    }
+
+    pub fn recording_mode(&self) -> RecordingMode {
+        RecordingMode::from_raw(self.int_attribute(TrackAttributeKey::RecMode))
+    }
+
+    pub fn set_recording_mode(&self, mode: RecordingMode) -> Result<(), ReaperFunctionError> {
+        self.set_int_attribute(TrackAttributeKey::RecMode, mode.to_raw())?;
+        Reaper::get()
+            .medium_reaper()
+            .track_list_adjust_windows_minor();
+        Ok(())
+    }
+
+    pub fn vu_mode(&self) -> VuMode {
+        VuMode::from_raw(self.int_attribute(TrackAttributeKey::VuMode))
+    }
+
+    pub fn set_vu_mode(&self, mode: VuMode) -> Result<(), ReaperFunctionError> {
+        self.set_int_attribute(TrackAttributeKey::VuMode, mode.to_raw())?;
+        Reaper::get()
+            .medium_reaper()
+            .track_list_adjust_windows_minor();
+        Ok(())
+    }
+
+    /// Returns the track icon (`None` if the track doesn't have an icon).
+    pub fn icon(&self) -> Option<ReaperString> {
+        self.load_and_check_if_necessary_or_complain();
+        let icon = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .get_set_media_track_info_get_icon(self.raw(), |icon| icon.to_owned())
+        };
+        icon.filter(|i| !i.to_str().is_empty())
+    }
+
+    /// Sets the track icon, either a full file name or relative to the track icons directory.
+    ///
+    /// Pass an empty string to remove the icon.
+    pub fn set_icon<'a>(&self, icon: impl Into<ReaperStringArg<'a>>) {
+        self.load_and_check_if_necessary_or_complain();
+        let reaper = Reaper::get().medium_reaper();
+        unsafe {
+            reaper.get_set_media_track_info_set_icon(self.raw(), icon);
+        }
+        reaper.track_list_adjust_windows_minor();
+    }
+
+    /// Returns the name of the layout which this track uses in the given area (`None` if it uses
+    /// the default layout).
+    pub fn layout(&self, area: TrackArea) -> Option<ReaperString> {
+        self.load_and_check_if_necessary_or_complain();
+        let layout = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .get_set_media_track_info_get_layout(self.raw(), area, |layout| layout.to_owned())
+        };
+        layout.filter(|l| !l.to_str().is_empty())
+    }
+
+    /// Makes this track use the given layout in the given area.
+    ///
+    /// Pass an empty string to use the default layout.
+    pub fn set_layout<'a>(&self, area: TrackArea, layout: impl Into<ReaperStringArg<'a>>) {
+        self.load_and_check_if_necessary_or_complain();
+        let reaper = Reaper::get().medium_reaper();
+        unsafe {
+            reaper.get_set_media_track_info_set_layout(self.raw(), area, layout);
+        }
+        match area {
+            TrackArea::Tcp => reaper.track_list_adjust_windows_minor(),
+            TrackArea::Mcp => reaper.track_list_adjust_windows_major(),
+        };
+    }
+
+    /// Returns the MIDI hardware output to which this track sends MIDI.
+    pub fn midi_hardware_output(&self) -> Option<MidiHardwareOutput> {
+        MidiHardwareOutput::from_raw(self.int_attribute(TrackAttributeKey::MidiHwOut))
+    }
+
+    pub fn set_midi_hardware_output(
+        &self,
+        output: Option<MidiHardwareOutput>,
+    ) -> Result<(), ReaperFunctionError> {
+        self.set_int_attribute(
+            TrackAttributeKey::MidiHwOut,
+            MidiHardwareOutput::to_raw(output),
+        )?;
+        Reaper::get()
+            .medium_reaper()
+            .track_list_adjust_windows_minor();
+        Ok(())
+    }
+
+    fn int_attribute(&self, key: TrackAttributeKey) -> i32 {
+        self.load_and_check_if_necessary_or_complain();
+        let value = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .get_media_track_info_value(self.raw(), key)
+        };
+        value as i32
+    }
+
+    fn set_int_attribute(
+        &self,
+        key: TrackAttributeKey,
+        value: i32,
+    ) -> Result<(), ReaperFunctionError> {
+        self.load_and_check_if_necessary_or_complain();
+        unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .set_media_track_info_value(self.raw(), key, value as f64)
+        }
+    }
+
     #[allow(clippy::float_cmp)]
     pub fn is_muted(&self) -> bool {
         self.load_and_check_if_necessary_or_complain();
@@ -761,6 +1022,29 @@ impl Track {
         Ok(())
     }
 
+    /// Creates a copy of this track, including FX, envelopes and items.
+    ///
+    /// The copy gets fresh GUIDs and is inserted directly above this track, at the same folder
+    /// level. If this track is a folder, its children are not copied. Pooled MIDI items of the copy
+    /// stay pooled with the ones of this track, just like when duplicating in REAPER.
+    pub fn duplicate(&self) -> ReaperResult<Track> {
+        self.load_and_check_if_necessary_or_complain();
+        let index = self.index().ok_or("master track can't be duplicated")?;
+        let chunk: String = self
+            .chunk(MAX_TRACK_CHUNK_SIZE, ChunkCacheHint::UndoMode)?
+            .try_into()
+            .map_err(|_| "couldn't read track chunk")?;
+        let chunk = with_fresh_guids(&chunk, || {
+            Reaper::get().generate_guid().to_string_with_braces()
+        });
+        let project = self.project();
+        let new_track = project.insert_track_at(index)?;
+        new_track.set_chunk(Chunk::new(chunk))?;
+        new_track.set_folder_depth_change(0)?;
+        // The chunk changed the GUID, so we need to load it again.
+        Ok(Track::new(new_track.raw(), Some(project.raw())))
+    }
+
     #[allow(clippy::float_cmp)]
     pub fn is_selected(&self) -> bool {
         self.load_and_check_if_necessary_or_complain();
@@ -837,6 +1121,26 @@ impl Track {
         )
     }
 
+    /// Adds a send to the hardware output channel with the given index (first channel of the
+    /// stereo pair).
+    pub fn add_hardware_output_send(
+        &self,
+        channel: u32,
+    ) -> Result<TrackRoute, ReaperFunctionError> {
+        self.load_and_check_if_necessary_or_complain();
+        let hw_send_index = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .create_track_send(self.raw(), SendTarget::HardwareOutput)?
+        };
+        let route = TrackRoute::new(self.clone(), TrackSendDirection::Send, hw_send_index);
+        route.set_channel_mapping(SendChannelMapping {
+            destination_offset: channel,
+            ..route.channel_mapping()
+        });
+        Ok(route)
+    }
+
     pub fn receives(&self) -> impl Iterator<Item = TrackRoute> + ExactSizeIterator + '_ {
         self.load_and_check_if_necessary_or_complain();
         (0..self.receive_count())
@@ -1081,6 +1385,27 @@ impl Track {
         FxChain::from_track(self.clone(), true)
     }
 
+    /// Returns the first ReaEQ instance in the normal FX chain.
+    pub fn rea_eq(&self) -> Option<ReaEq> {
+        self.rea_eq_internal(false)
+    }
+
+    /// Like [`Self::rea_eq`] but adds a ReaEQ instance to the normal FX chain if there's none yet.
+    pub fn rea_eq_or_add(&self) -> Option<ReaEq> {
+        self.rea_eq_internal(true)
+    }
+
+    fn rea_eq_internal(&self, instantiate: bool) -> Option<ReaEq> {
+        self.load_and_check_if_necessary_or_complain();
+        let fx_index = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .track_fx_get_eq(self.raw(), instantiate)?
+        };
+        let fx = self.normal_fx_chain().fx_by_index(fx_index)?;
+        Some(ReaEq::new(fx))
+    }
+
     pub fn is_master_track(&self) -> bool {
         self.load_and_check_if_necessary_or_complain();
         let t = unsafe {
@@ -1120,6 +1445,37 @@ impl Hash for Track {
     }
 }
 
+/// Current height of a track control panel in pixels.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct TrackHeight {
+    /// Height without envelope lanes.
+    pub tcp: u32,
+    /// Height including envelope lanes.
+    pub tcp_with_envelopes: u32,
+}
+
+/// Current position and size of a track panel in pixels.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct TrackPanelBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Replaces the GUIDs of the track, its FX, envelopes, items and takes in the given track chunk.
+///
+/// Pooled MIDI event GUIDs (`POOLEDEVTS`) are kept on purpose.
+fn with_fresh_guids(chunk: &str, mut generate_guid: impl FnMut() -> String) -> String {
+    let guid_line_regex =
+        regex!(r"(?m)^(\s*<?(?:TRACK|TRACKID|FXID|EGUID|IGUID|GUID) )\{[0-9A-Fa-f-]{36}\}");
+    guid_line_regex
+        .replace_all(chunk, |captures: &regex::Captures| {
+            format!("{}{}", &captures[1], generate_guid())
+        })
+        .into_owned()
+}
+
 pub fn get_media_track_guid(media_track: MediaTrack) -> Guid {
     let internal = unsafe {
         Reaper::get()
@@ -1150,3 +1506,43 @@ fn get_show_attribute_key(track_area: TrackArea) -> TrackAttributeKey<'static> {
         Mcp => TrackAttributeKey::ShowInMixer,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_guids() {
+        // Given
+        let chunk = r#"<TRACK {11111111-1111-1111-1111-111111111111}
+  NAME Drums
+  TRACKID {22222222-2222-2222-2222-222222222222}
+  <FXCHAIN
+    FXID {33333333-3333-3333-3333-333333333333}
+  >
+  <ITEM
+    IGUID {44444444-4444-4444-4444-444444444444}
+    GUID {55555555-5555-5555-5555-555555555555}
+    POOLEDEVTS {66666666-6666-6666-6666-666666666666}
+  >
+>"#;
+        let mut count = 0;
+        // When
+        let result = with_fresh_guids(chunk, || {
+            count += 1;
+            format!(
+                "{{{0}{0}{0}{0}{0}{0}{0}{0}-0000-0000-0000-000000000000}}",
+                count
+            )
+        });
+        // Then
+        assert_eq!(count, 5);
+        assert!(result.starts_with("<TRACK {11111111-0000-0000-0000-000000000000}"));
+        assert!(result.contains("  TRACKID {22222222-0000-0000-0000-000000000000}"));
+        assert!(result.contains("    FXID {33333333-0000-0000-0000-000000000000}"));
+        assert!(result.contains("    IGUID {44444444-0000-0000-0000-000000000000}"));
+        assert!(result.contains("    GUID {55555555-0000-0000-0000-000000000000}"));
+        assert!(result.contains("POOLEDEVTS {66666666-6666-6666-6666-666666666666}"));
+        assert!(result.contains("  NAME Drums"));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::{Reaper, ReaperResult, Track, MAX_TRACK_CHUNK_SIZE};
+use reaper_medium::{ChunkCacheHint, CommandId};
+
+/// Defines the channel count of the rendered audio when freezing a track.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FreezeMode {
+    Mono,
+    Stereo,
+    /// Uses the channel count of the track.
+    Multichannel,
+}
+
+impl FreezeMode {
+    /// Returns the ID of the built-in action "Track: Freeze to ... (render pre-fader, save/remove
+    /// items and online FX)".
+    fn command_id(self) -> CommandId {
+        use FreezeMode::*;
+        let id = match self {
+            Mono => 40901,
+            Stereo => 41223,
+            Multichannel => 40877,
+        };
+        CommandId::new(id)
+    }
+}
+
+/// Command ID of the built-in action "Track: Unfreeze tracks (restore previously saved items
+/// and FX)".
+const UNFREEZE_COMMAND_ID: u32 = 41644;
+
+/// Freeze information of a track as stored in its chunk.
+///
+/// A track can be frozen multiple times. Each freeze adds one level, unfreezing removes the most
+/// recent one.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct TrackFreezeState {
+    levels: Vec<FrozenTrackData>,
+}
+
+/// The data which REAPER saved when freezing a track once (content of one `FREEZE` tag).
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct FrozenTrackData {
+    /// The original items (complete `ITEM` tags).
+    pub item_chunks: Vec<String>,
+    /// The original FX chain (complete `FXCHAIN` tag) if FX have been frozen.
+    pub fx_chain_chunk: Option<String>,
+    /// Number of FX which have been frozen.
+    pub fx_count: u32,
+}
+
+impl TrackFreezeState {
+    /// Parses the freeze information from the given track chunk.
+    pub fn from_track_chunk(track_chunk: &str) -> TrackFreezeState {
+        let levels = child_tags(track_chunk, "FREEZE")
+            .into_iter()
+            .map(|freeze_tag| {
+                let fx_chain_chunk = child_tags(freeze_tag, "FXCHAIN").into_iter().next();
+                FrozenTrackData {
+                    item_chunks: child_tags(freeze_tag, "ITEM")
+                        .into_iter()
+                        .map(|tag| tag.to_string())
+                        .collect(),
+                    fx_count: fx_chain_chunk.map(count_fx).unwrap_or(0),
+                    fx_chain_chunk: fx_chain_chunk.map(|tag| tag.to_string()),
+                }
+            })
+            .collect();
+        TrackFreezeState { levels }
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        !self.levels.is_empty()
+    }
+
+    /// Returns how often the track has been frozen (without being unfrozen in-between).
+    pub fn freeze_count(&self) -> u32 {
+        self.levels.len() as u32
+    }
+
+    /// Returns the total number of frozen FX over all freeze levels.
+    pub fn frozen_fx_count(&self) -> u32 {
+        self.levels.iter().map(|l| l.fx_count).sum()
+    }
+
+    /// Returns the saved data of each freeze, oldest first.
+    pub fn levels(&self) -> &[FrozenTrackData] {
+        &self.levels
+    }
+}
+
+impl Track {
+    /// Reads the freeze state from the track chunk.
+    pub fn freeze_state(&self) -> ReaperResult<TrackFreezeState> {
+        let chunk = self.chunk(MAX_TRACK_CHUNK_SIZE, ChunkCacheHint::NormalMode)?;
+        let content = chunk.content();
+        let state = TrackFreezeState::from_track_chunk(&content.borrow());
+        Ok(state)
+    }
+
+    /// Freezes this track and returns the new freeze state.
+    ///
+    /// REAPER renders synchronously while executing the freeze action, so the freeze data is
+    /// available as soon as this function returns. This also means that the main thread is
+    /// blocked while rendering.
+    ///
+    /// Works in the current project only.
+    pub fn freeze(&self, mode: FreezeMode) -> ReaperResult<TrackFreezeState> {
+        let count_before = self.freeze_state()?.freeze_count();
+        self.invoke_freeze_action(mode.command_id())?;
+        let state = self.freeze_state()?;
+        if state.freeze_count() <= count_before {
+            return Err("REAPER didn't freeze the track".into());
+        }
+        Ok(state)
+    }
+
+    /// Unfreezes this track (restores the items and FX saved by the most recent freeze) and
+    /// returns the new freeze state.
+    ///
+    /// Works in the current project only.
+    pub fn unfreeze(&self) -> ReaperResult<TrackFreezeState> {
+        let count_before = self.freeze_state()?.freeze_count();
+        if count_before == 0 {
+            return Err("track is not frozen".into());
+        }
+        self.invoke_freeze_action(CommandId::new(UNFREEZE_COMMAND_ID))?;
+        let state = self.freeze_state()?;
+        if state.freeze_count() >= count_before {
+            return Err("REAPER didn't unfreeze the track".into());
+        }
+        Ok(state)
+    }
+
+    fn invoke_freeze_action(&self, command_id: CommandId) -> ReaperResult<()> {
+        if self.is_master_track() {
+            return Err("master track can't be frozen".into());
+        }
+        let project = self.project();
+        if project != Reaper::get().current_project() {
+            return Err("only tracks in the current project can be frozen".into());
+        }
+        project.with_selected_tracks(&[self.clone()], || {
+            Reaper::get()
+                .medium_reaper()
+                .main_on_command_ex(command_id, 0, project.context());
+        });
+        Ok(())
+    }
+}
+
+/// Returns the direct child tags with the given name (complete, from `<` to `>`) of the given
+/// tag.
+fn child_tags<'a>(tag: &'a str, child_tag_name: &str) -> Vec<&'a str> {
+    let mut result = vec![];
+    let mut depth = 0;
+    let mut child_start = None;
+    let mut pos = 0;
+    for line in tag.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if let Some(opener) = trimmed.strip_prefix('<') {
+            depth += 1;
+            let name = opener.split(' ').next().unwrap_or_default();
+            if depth == 2 && name == child_tag_name {
+                child_start = Some(pos + (line.len() - line.trim_start().len()));
+            }
+        } else if trimmed == ">" {
+            if depth == 2 {
+                if let Some(start) = child_start.take() {
+                    let end = pos + line.find('>').unwrap() + 1;
+                    result.push(&tag[start..end]);
+                }
+            }
+            depth -= 1;
+        }
+        pos += line.len();
+    }
+    result
+}
+
+/// Counts the FX in the given `FXCHAIN` tag (each FX has exactly one `FXID` line).
+fn count_fx(fx_chain_tag: &str) -> u32 {
+    let mut depth = 0;
+    let mut count = 0;
+    for line in fx_chain_tag.lines().map(|l| l.trim()) {
+        if line.starts_with('<') {
+            depth += 1;
+        } else if line == ">" {
+            depth -= 1;
+        } else if depth == 1 && line.starts_with("FXID ") {
+            count += 1;
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FROZEN_TRACK_CHUNK: &str = r#"<TRACK {11111111-1111-1111-1111-111111111111}
+  NAME Synth
+  <FXCHAIN
+    SHOW 0
+    <JS "frozen/volume"
+    >
+    FXID {22222222-2222-2222-2222-222222222222}
+  >
+  <FREEZE 1
+    <ITEM
+      POSITION 0
+      <SOURCE MIDI
+        HASDATA 1 960 QN
+      >
+    >
+    <ITEM
+      POSITION 4
+    >
+    <FXCHAIN
+      SHOW 0
+      <VST "VSTi: Synth"
+        ZXZhbA==
+      >
+      FXID {33333333-3333-3333-3333-333333333333}
+      <JS utility/volume
+      >
+      FXID {44444444-4444-4444-4444-444444444444}
+    >
+  >
+  <ITEM
+    POSITION 0
+  >
+>"#;
+
+    #[test]
+    fn parse_frozen_track() {
+        // When
+        let state = TrackFreezeState::from_track_chunk(FROZEN_TRACK_CHUNK);
+        // Then
+        assert!(state.is_frozen());
+        assert_eq!(state.freeze_count(), 1);
+        assert_eq!(state.frozen_fx_count(), 2);
+        let level = &state.levels()[0];
+        assert_eq!(level.item_chunks.len(), 2);
+        assert!(level.item_chunks[0].starts_with("<ITEM\n      POSITION 0"));
+        assert!(level.item_chunks[0].ends_with("\n    >"));
+        let fx_chain_chunk = level.fx_chain_chunk.as_ref().unwrap();
+        assert!(fx_chain_chunk.starts_with("<FXCHAIN"));
+        assert!(fx_chain_chunk.contains("VSTi: Synth"));
+    }
+
+    #[test]
+    fn parse_unfrozen_track() {
+        // Given
+        let chunk = "<TRACK\n  NAME Bass\n  <ITEM\n  >\n>";
+        // When
+        let state = TrackFreezeState::from_track_chunk(chunk);
+        // Then
+        assert!(!state.is_frozen());
+        assert_eq!(state.frozen_fx_count(), 0);
+    }
+}
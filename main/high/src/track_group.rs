@@ -0,0 +1,174 @@
+use crate::{Project, Reaper, ReaperResult, Track};
+use reaper_medium::TrackGroupParam;
+use std::collections::BTreeSet;
+
+/// Number of track groups available in each project.
+pub const TRACK_GROUP_COUNT: u32 = 64;
+
+/// Membership of a track in one track group for one particular parameter.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Ord, PartialOrd)]
+pub struct TrackGroupMembership {
+    /// Group number from 1 to 64.
+    pub group: u32,
+    pub param: TrackGroupParam,
+}
+
+impl TrackGroupMembership {
+    pub fn new(group: u32, param: TrackGroupParam) -> TrackGroupMembership {
+        TrackGroupMembership { group, param }
+    }
+}
+
+impl Track {
+    /// Returns all group memberships of this track.
+    pub fn group_membership(&self) -> BTreeSet<TrackGroupMembership> {
+        TrackGroupParam::ALL
+            .iter()
+            .flat_map(|param| {
+                self.groups(*param)
+                    .into_iter()
+                    .map(move |group| TrackGroupMembership::new(group, *param))
+            })
+            .collect()
+    }
+
+    /// Returns the numbers of the groups in which this track is a member for the given parameter.
+    pub fn groups(&self, param: TrackGroupParam) -> Vec<u32> {
+        let (low, high) = self.group_masks(param);
+        groups_from_masks(low, high)
+    }
+
+    pub fn is_in_group(&self, membership: TrackGroupMembership) -> bool {
+        self.groups(membership.param).contains(&membership.group)
+    }
+
+    /// Adds this track to or removes it from the given group for the given parameter.
+    pub fn set_group_membership(
+        &self,
+        membership: TrackGroupMembership,
+        is_member: bool,
+    ) -> ReaperResult<()> {
+        let (low, high) = group_to_masks(membership.group)?;
+        let (low_value, high_value) = if is_member { (low, high) } else { (0, 0) };
+        self.group_masks_with_values(membership.param, (low, low_value), (high, high_value));
+        Ok(())
+    }
+
+    /// Replaces all group memberships of this track with the given ones.
+    pub fn set_group_memberships(
+        &self,
+        memberships: impl IntoIterator<Item = TrackGroupMembership>,
+    ) -> ReaperResult<()> {
+        let memberships: Vec<_> = memberships.into_iter().collect();
+        for param in TrackGroupParam::ALL.iter() {
+            let mut low_value = 0;
+            let mut high_value = 0;
+            for m in memberships.iter().filter(|m| m.param == *param) {
+                let (low, high) = group_to_masks(m.group)?;
+                low_value |= low;
+                high_value |= high;
+            }
+            self.group_masks_with_values(*param, (u32::MAX, low_value), (u32::MAX, high_value));
+        }
+        Ok(())
+    }
+
+    /// Queries the membership masks (groups 1-32, groups 33-64) for the given parameter.
+    fn group_masks(&self, param: TrackGroupParam) -> (u32, u32) {
+        self.group_masks_with_values(param, (0, 0), (0, 0))
+    }
+
+    /// Applies the given (mask, value) pairs and returns the masks as they were before.
+    fn group_masks_with_values(
+        &self,
+        param: TrackGroupParam,
+        (low_mask, low_value): (u32, u32),
+        (high_mask, high_value): (u32, u32),
+    ) -> (u32, u32) {
+        let reaper = Reaper::get().medium_reaper();
+        unsafe {
+            let low = reaper.get_set_track_group_membership(self.raw(), param, low_mask, low_value);
+            let high = reaper.get_set_track_group_membership_high(
+                self.raw(),
+                param,
+                high_mask,
+                high_value,
+            );
+            (low, high)
+        }
+    }
+}
+
+impl Project {
+    /// Returns the name of the given track group (1 to 64).
+    ///
+    /// Returns `None` if the group has no name.
+    pub fn track_group_name(self, group: u32) -> ReaperResult<Option<String>> {
+        group_to_masks(group)?;
+        let name = Reaper::get()
+            .medium_reaper()
+            .get_set_project_info_string_get(
+                self.context(),
+                format!("TRACK_GROUP_NAME:{}", group),
+                256,
+            )?;
+        let name = name.into_string();
+        Ok(if name.is_empty() { None } else { Some(name) })
+    }
+
+    /// Sets the name of the given track group (1 to 64).
+    pub fn set_track_group_name(self, group: u32, name: &str) -> ReaperResult<()> {
+        group_to_masks(group)?;
+        Reaper::get()
+            .medium_reaper()
+            .get_set_project_info_string_set(
+                self.context(),
+                format!("TRACK_GROUP_NAME:{}", group),
+                name,
+            )?;
+        Ok(())
+    }
+}
+
+/// Converts a group number (1 to 64) to the bit masks for groups 1-32 and 33-64.
+fn group_to_masks(group: u32) -> ReaperResult<(u32, u32)> {
+    match group {
+        1..=32 => Ok((1 << (group - 1), 0)),
+        33..=TRACK_GROUP_COUNT => Ok((0, 1 << (group - 33))),
+        _ => Err("track group must be between 1 and 64".into()),
+    }
+}
+
+fn groups_from_masks(low: u32, high: u32) -> Vec<u32> {
+    (1..=TRACK_GROUP_COUNT)
+        .filter(|group| {
+            let (low_bit, high_bit) = group_to_masks(*group).unwrap();
+            low & low_bit != 0 || high & high_bit != 0
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks() {
+        // Then
+        assert_eq!(group_to_masks(1).unwrap(), (1, 0));
+        assert_eq!(group_to_masks(32).unwrap(), (1 << 31, 0));
+        assert_eq!(group_to_masks(33).unwrap(), (0, 1));
+        assert_eq!(group_to_masks(64).unwrap(), (0, 1 << 31));
+        assert!(group_to_masks(0).is_err());
+        assert!(group_to_masks(65).is_err());
+    }
+
+    #[test]
+    fn groups() {
+        // When
+        let groups = groups_from_masks(0b101, 1 << 31);
+        // Then
+        assert_eq!(groups, vec![1, 3, 64]);
+        assert!(groups_from_masks(0, 0).is_empty());
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{Reaper, ReaperResult, Track, Volume};
+use futures::channel::mpsc;
+use futures::{Stream, StreamExt};
+use reaper_low::firewall;
+use reaper_medium::Db;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Current meter state of one track channel.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TrackChannelPeak {
+    /// Current peak.
+    pub peak: Volume,
+    /// Highest peak since the hold value has been reset.
+    pub hold: Db,
+}
+
+/// Meter state of one track at a certain point in time.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TrackMeter {
+    pub track: Track,
+    /// One entry per track channel.
+    pub channels: Vec<TrackChannelPeak>,
+}
+
+/// Meter state of several tracks, captured in one main loop cycle.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MeterFrame {
+    pub time: Instant,
+    pub tracks: Vec<TrackMeter>,
+}
+
+impl Track {
+    /// Returns the current peak and hold values of all channels of this track.
+    pub fn peaks(&self) -> Vec<TrackChannelPeak> {
+        let reaper = Reaper::get().medium_reaper();
+        (0..self.channel_count())
+            .map(|ch| unsafe {
+                TrackChannelPeak {
+                    peak: Volume::from_reaper_value(reaper.track_get_peak_info(self.raw(), ch)),
+                    hold: reaper.track_get_peak_hold_db(self.raw(), ch, false),
+                }
+            })
+            .collect()
+    }
+
+    /// Resets the peak hold values of all channels of this track.
+    pub fn reset_peak_hold(&self) {
+        let reaper = Reaper::get().medium_reaper();
+        for ch in 0..self.channel_count() {
+            unsafe {
+                reaper.track_get_peak_hold_db(self.raw(), ch, true);
+            }
+        }
+    }
+}
+
+/// Stream of meter frames of some tracks, captured on REAPER's main-thread timer.
+///
+/// The timer fires roughly 30 times per second, so intervals shorter than that don't have any
+/// effect. If the stream isn't consumed fast enough, frames are dropped. Dropping the stream removes
+/// its tracks from polling immediately. The timer itself is unregistered on its next tick after
+/// the last stream has been dropped.
+///
+/// Must be used in the main thread only, e.g. by consuming it in a future spawned via
+/// [`FutureSupport::spawn_in_main_thread_from_main_thread`].
+///
+/// [`FutureSupport::spawn_in_main_thread_from_main_thread`]: struct.FutureSupport.html#method.spawn_in_main_thread_from_main_thread
+#[derive(Debug)]
+pub struct TrackMeterPoller {
+    id: u64,
+    receiver: mpsc::Receiver<MeterFrame>,
+    // The registry of active pollers lives in the main thread.
+    _not_send: PhantomData<Rc<()>>,
+}
+
+/// All started [`TrackMeterPoller`]s plus the registration state of the main-thread timer.
+#[derive(Debug, Default)]
+pub(crate) struct TrackMeterPollerRegistry {
+    pollers: Vec<ActiveTrackMeterPoller>,
+    timer_is_registered: bool,
+}
+
+/// State of a started [`TrackMeterPoller`], driven by the main-thread timer.
+#[derive(Debug)]
+struct ActiveTrackMeterPoller {
+    id: u64,
+    tracks: Vec<Track>,
+    interval: Duration,
+    last_frame_time: Option<Instant>,
+    sender: mpsc::Sender<MeterFrame>,
+}
+
+impl TrackMeterPoller {
+    /// Starts polling the meters of the given tracks at most once per interval.
+    ///
+    /// The capacity determines how many frames are buffered if the stream isn't consumed fast
+    /// enough.
+    pub fn start(
+        tracks: Vec<Track>,
+        interval: Duration,
+        capacity: usize,
+    ) -> ReaperResult<TrackMeterPoller> {
+        let reaper = Reaper::get();
+        reaper.require_main_thread();
+        let id = NEXT_POLLER_ID.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::channel(capacity);
+        let mut registry = reaper.track_meter_pollers.borrow_mut();
+        if !registry.timer_is_registered {
+            reaper
+                .medium_session()
+                .plugin_register_add_timer(poll_track_meters)?;
+            registry.timer_is_registered = true;
+        }
+        registry.pollers.push(ActiveTrackMeterPoller {
+            id,
+            tracks,
+            interval,
+            last_frame_time: None,
+            sender,
+        });
+        let poller = TrackMeterPoller {
+            id,
+            receiver,
+            _not_send: PhantomData,
+        };
+        Ok(poller)
+    }
+
+    /// Changes the set of metered tracks.
+    pub fn set_tracks(&self, tracks: Vec<Track>) {
+        let reaper = Reaper::get();
+        reaper.require_main_thread();
+        let mut registry = reaper.track_meter_pollers.borrow_mut();
+        if let Some(p) = registry.pollers.iter_mut().find(|p| p.id == self.id) {
+            p.tracks = tracks;
+        }
+    }
+}
+
+impl Stream for TrackMeterPoller {
+    type Item = MeterFrame;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<MeterFrame>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+impl Drop for TrackMeterPoller {
+    fn drop(&mut self) {
+        // Doesn't touch the medium session because the caller might currently hold it. The timer
+        // unregisters itself on its next tick.
+        let reaper = match Reaper::try_get() {
+            Some(r) => r,
+            // Already torn down, nothing left to clean up
+            None => return,
+        };
+        if let Ok(mut registry) = reaper.track_meter_pollers.try_borrow_mut() {
+            registry.pollers.retain(|p| p.id != self.id);
+        }
+    }
+}
+
+impl ActiveTrackMeterPoller {
+    fn poll(&mut self, now: Instant) {
+        if !is_due(self.last_frame_time, self.interval, now) {
+            return;
+        }
+        self.last_frame_time = Some(now);
+        let frame = MeterFrame {
+            time: now,
+            tracks: self
+                .tracks
+                .iter()
+                .filter(|t| t.is_available())
+                .map(|t| TrackMeter {
+                    track: t.clone(),
+                    channels: t.peaks(),
+                })
+                .collect(),
+        };
+        // Dropping frames is okay if the receiver is too slow
+        let _ = self.sender.try_send(frame);
+    }
+}
+
+static NEXT_POLLER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Called by REAPER's main-thread timer.
+extern "C" fn poll_track_meters() {
+    firewall(|| {
+        let reaper = Reaper::get();
+        let mut registry = reaper.track_meter_pollers.borrow_mut();
+        // Also catches pollers whose entry couldn't be removed on drop
+        registry.pollers.retain(|p| !p.sender.is_closed());
+        if registry.pollers.is_empty() {
+            // If the session is currently borrowed, we just try again on the next tick
+            if let Some(mut session) = reaper.try_medium_session() {
+                session.plugin_register_remove_timer(poll_track_meters);
+                registry.timer_is_registered = false;
+            }
+            return;
+        }
+        let now = Instant::now();
+        for poller in registry.pollers.iter_mut() {
+            poller.poll(now);
+        }
+    });
+}
+
+fn is_due(last_frame_time: Option<Instant>, interval: Duration, now: Instant) -> bool {
+    match last_frame_time {
+        None => true,
+        Some(t) => now.duration_since(t) >= interval,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn due() {
+        // Given
+        let interval = Duration::from_millis(50);
+        let start = Instant::now();
+        // Then
+        assert!(is_due(None, interval, start));
+        assert!(!is_due(
+            Some(start),
+            interval,
+            start + Duration::from_millis(20)
+        ));
+        assert!(is_due(Some(start), interval, start + interval));
+    }
+}
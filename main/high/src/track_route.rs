@@ -1,8 +1,9 @@
 use crate::{Pan, Reaper, Track, Volume};
 
 use reaper_medium::{
-    AutomationMode, EditMode, MediaTrack, ReaperFunctionError, ReaperString, TrackSendAttributeKey,
-    TrackSendCategory, TrackSendDirection, TrackSendRef, VolumeAndPan,
+    AutomationMode, EditMode, MediaTrack, MidiSendMapping, ReaperFunctionError, ReaperString,
+    SendChannelMapping, SendMode, TrackSendAttributeKey, TrackSendCategory, TrackSendDirection,
+    TrackSendRef, VolumeAndPan,
 };
 use std::fmt;
 use TrackSendDirection::*;
@@ -227,6 +228,46 @@ impl TrackRoute {
         AutomationMode::from_raw(raw_mode)
     }
 
+    pub fn send_mode(&self) -> SendMode {
+        let raw_mode = self.prop_numeric_value(TrackSendAttributeKey::SendMode) as i32;
+        SendMode::from_raw(raw_mode)
+    }
+
+    pub fn set_send_mode(&self, mode: SendMode) {
+        self.set_prop_numeric_value(TrackSendAttributeKey::SendMode, mode.to_raw() as _);
+    }
+
+    /// Returns which audio channels are sent where.
+    ///
+    /// For hardware output sends, the destination offset is the hardware output channel.
+    pub fn channel_mapping(&self) -> SendChannelMapping {
+        let source_channel = self.prop_numeric_value(TrackSendAttributeKey::SrcChan) as i32;
+        let destination_channel = self.prop_numeric_value(TrackSendAttributeKey::DstChan) as i32;
+        SendChannelMapping::from_raw(source_channel, destination_channel)
+    }
+
+    pub fn set_channel_mapping(&self, mapping: SendChannelMapping) {
+        let (source_channel, destination_channel) = mapping.to_raw();
+        self.set_prop_numeric_value(TrackSendAttributeKey::SrcChan, source_channel as _);
+        self.set_prop_numeric_value(TrackSendAttributeKey::DstChan, destination_channel as _);
+    }
+
+    /// Returns `None` if this route doesn't send MIDI.
+    ///
+    /// Not applicable to hardware output sends.
+    pub fn midi_mapping(&self) -> Option<MidiSendMapping> {
+        let flags = self.prop_numeric_value(TrackSendAttributeKey::MidiFlags) as i32;
+        MidiSendMapping::from_raw(flags)
+    }
+
+    /// Pass `None` to stop sending MIDI.
+    pub fn set_midi_mapping(&self, mapping: Option<MidiSendMapping>) {
+        let flags = mapping
+            .map(|m| m.to_raw())
+            .unwrap_or(MidiSendMapping::DISABLED_RAW);
+        self.set_prop_numeric_value(TrackSendAttributeKey::MidiFlags, flags as _);
+    }
+
     fn set_prop_enabled(&self, key: TrackSendAttributeKey, enabled: bool) {
         self.set_prop_numeric_value(key, if enabled { 1.0 } else { 0.0 });
     }
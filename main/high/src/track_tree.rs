@@ -0,0 +1,363 @@
+use crate::{Project, Reaper, ReaperResult, Track};
+use reaper_medium::TrackReorderBehavior;
+use std::ops::Range;
+
+/// A track together with its child tracks, as returned by [`Project::track_tree`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TrackTreeNode {
+    track: Track,
+    children: Vec<TrackTreeNode>,
+}
+
+impl TrackTreeNode {
+    pub fn track(&self) -> &Track {
+        &self.track
+    }
+
+    pub fn children(&self) -> &[TrackTreeNode] {
+        &self.children
+    }
+
+    pub fn is_folder(&self) -> bool {
+        !self.children.is_empty()
+    }
+
+    /// Returns all tracks of this subtree in project order, starting with the track itself.
+    pub fn tracks(&self) -> Vec<&Track> {
+        let mut tracks = vec![&self.track];
+        for child in &self.children {
+            tracks.extend(child.tracks());
+        }
+        tracks
+    }
+}
+
+/// Defines how the children of a folder are displayed.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum TrackFolderCompactState {
+    Normal,
+    Small,
+    TinyChildren,
+}
+
+impl TrackFolderCompactState {
+    pub(crate) fn from_raw(value: i32) -> TrackFolderCompactState {
+        use TrackFolderCompactState::*;
+        match value {
+            0 => Normal,
+            1 => Small,
+            _ => TinyChildren,
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> i32 {
+        use TrackFolderCompactState::*;
+        match self {
+            Normal => 0,
+            Small => 1,
+            TinyChildren => 2,
+        }
+    }
+}
+
+impl Project {
+    /// Returns the top-level tracks of this project together with their descendants.
+    pub fn track_tree(self) -> Vec<TrackTreeNode> {
+        let tracks: Vec<_> = self.tracks().collect();
+        let depths = absolute_depths(tracks.iter().map(|t| t.folder_depth_change()));
+        build_index_tree(&depths, 0..depths.len())
+            .into_iter()
+            .map(|n| n.into_track_tree_node(&tracks))
+            .collect()
+    }
+
+    /// Moves the given track and all of its descendants in front of the track at index `before`,
+    /// making it a descendant at the given depth (0 = top level).
+    ///
+    /// Rewrites the folder depth changes of all affected tracks so the folder structure stays
+    /// consistent.
+    pub(crate) fn move_track_subtree(
+        self,
+        track: &Track,
+        before: usize,
+        root_depth: u32,
+    ) -> ReaperResult<()> {
+        let tracks: Vec<_> = self.tracks().collect();
+        let depths = absolute_depths(tracks.iter().map(|t| t.folder_depth_change()));
+        let index = track.index().ok_or("master track can't be moved")? as usize;
+        let subtree = index..subtree_end(&depths, index);
+        if subtree.contains(&before) && before != index {
+            return Err("track can't be moved into itself".into());
+        }
+        let (new_order, new_depths) = move_subtree(&depths, subtree.clone(), before, root_depth);
+        self.undoable("Move track", || {
+            self.move_tracks(
+                &tracks[subtree],
+                before as u32,
+                TrackReorderBehavior::Normal,
+            )?;
+            for (new_index, depth_change) in depth_changes(&new_depths).into_iter().enumerate() {
+                let track = &tracks[new_order[new_index]];
+                if track.folder_depth_change() != depth_change {
+                    track.set_folder_depth_change(depth_change)?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+impl Track {
+    /// Returns the folder which contains this track.
+    pub fn parent(&self) -> Option<Track> {
+        let media_track = unsafe {
+            Reaper::get()
+                .medium_reaper()
+                .get_set_media_track_info_get_par_track(self.raw())?
+        };
+        Some(Track::new(media_track, Some(self.project().raw())))
+    }
+
+    pub fn is_folder(&self) -> bool {
+        self.folder_depth_change() > 0
+    }
+
+    /// Returns the direct children of this track (empty if it's not a folder).
+    pub fn children(&self) -> Vec<Track> {
+        self.subtree_tracks(|depth, own_depth| depth == own_depth + 1)
+    }
+
+    /// Returns all tracks within this folder, including the ones in nested folders.
+    pub fn descendants(&self) -> Vec<Track> {
+        self.subtree_tracks(|_, _| true)
+    }
+
+    /// Moves this track (including its descendants) into the given folder, making it the last
+    /// child.
+    ///
+    /// If the given track is not a folder yet, it becomes one. Works in the current project only.
+    pub fn move_into_folder(&self, folder: &Track) -> ReaperResult<()> {
+        let project = self.project();
+        let depths = absolute_depths(project.tracks().map(|t| t.folder_depth_change()));
+        let folder_index = folder.index().ok_or("master track can't be a folder")? as usize;
+        let index = self.index().ok_or("master track can't be moved")? as usize;
+        if (index..subtree_end(&depths, index)).contains(&folder_index) {
+            return Err("track can't be moved into itself".into());
+        }
+        project.move_track_subtree(
+            self,
+            subtree_end(&depths, folder_index),
+            depths[folder_index] + 1,
+        )
+    }
+
+    /// Moves this track (including its descendants) out of its folder, placing it right after
+    /// the folder.
+    ///
+    /// Works in the current project only.
+    pub fn move_out_of_folder(&self) -> ReaperResult<()> {
+        let parent = self.parent().ok_or("track is not in a folder")?;
+        let project = self.project();
+        let depths = absolute_depths(project.tracks().map(|t| t.folder_depth_change()));
+        let parent_index = parent.index().ok_or("master track can't be a folder")? as usize;
+        project.move_track_subtree(
+            self,
+            subtree_end(&depths, parent_index),
+            depths[parent_index],
+        )
+    }
+
+    fn subtree_tracks(&self, include: impl Fn(u32, u32) -> bool) -> Vec<Track> {
+        let index = match self.index() {
+            None => return vec![],
+            Some(i) => i as usize,
+        };
+        let tracks: Vec<_> = self.project().tracks().collect();
+        let depths = absolute_depths(tracks.iter().map(|t| t.folder_depth_change()));
+        (index + 1..subtree_end(&depths, index))
+            .filter(|i| include(depths[*i], depths[index]))
+            .map(|i| tracks[i].clone())
+            .collect()
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct IndexTreeNode {
+    index: usize,
+    children: Vec<IndexTreeNode>,
+}
+
+impl IndexTreeNode {
+    fn into_track_tree_node(self, tracks: &[Track]) -> TrackTreeNode {
+        TrackTreeNode {
+            track: tracks[self.index].clone(),
+            children: self
+                .children
+                .into_iter()
+                .map(|c| c.into_track_tree_node(tracks))
+                .collect(),
+        }
+    }
+}
+
+/// Converts the folder depth changes (`I_FOLDERDEPTH`) of consecutive tracks into absolute
+/// depths (0 = top level).
+fn absolute_depths(depth_changes: impl IntoIterator<Item = i32>) -> Vec<u32> {
+    let mut depth = 0;
+    depth_changes
+        .into_iter()
+        .map(|change| {
+            let current = depth;
+            depth = (depth as i32 + change).max(0) as u32;
+            current
+        })
+        .collect()
+}
+
+/// Converts absolute depths back into folder depth changes, closing all folders at the end.
+fn depth_changes(depths: &[u32]) -> Vec<i32> {
+    depths
+        .iter()
+        .enumerate()
+        .map(|(i, depth)| *depths.get(i + 1).unwrap_or(&0) as i32 - *depth as i32)
+        .collect()
+}
+
+/// Returns the (exclusive) end index of the subtree which starts at the given index.
+fn subtree_end(depths: &[u32], index: usize) -> usize {
+    (index + 1..depths.len())
+        .find(|i| depths[*i] <= depths[index])
+        .unwrap_or(depths.len())
+}
+
+fn build_index_tree(depths: &[u32], range: Range<usize>) -> Vec<IndexTreeNode> {
+    let mut nodes = vec![];
+    let mut index = range.start;
+    while index < range.end {
+        let end = subtree_end(depths, index).min(range.end);
+        nodes.push(IndexTreeNode {
+            index,
+            children: build_index_tree(depths, index + 1..end),
+        });
+        index = end;
+    }
+    nodes
+}
+
+/// Moves the given subtree in front of index `before` (an index in the original order) and
+/// shifts its depths so the subtree root ends up at `root_depth`.
+///
+/// Returns the new order (as original indexes) and the new depths.
+fn move_subtree(
+    depths: &[u32],
+    subtree: Range<usize>,
+    before: usize,
+    root_depth: u32,
+) -> (Vec<usize>, Vec<u32>) {
+    let remaining = (0..depths.len()).filter(|i| !subtree.contains(i));
+    let (head, tail): (Vec<_>, Vec<_>) = remaining.partition(|i| *i < before);
+    let new_order: Vec<_> = head
+        .into_iter()
+        .chain(subtree.clone())
+        .chain(tail)
+        .collect();
+    let old_root_depth = depths[subtree.start] as i32;
+    let new_depths = new_order
+        .iter()
+        .map(|i| {
+            if subtree.contains(i) {
+                (depths[*i] as i32 - old_root_depth + root_depth as i32) as u32
+            } else {
+                depths[*i]
+            }
+        })
+        .collect();
+    (new_order, new_depths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 Folder A
+    // 1   Child A1 (folder)
+    // 2     Grandchild
+    // 3   Child A2
+    // 4 Track B
+    const DEPTH_CHANGES: [i32; 5] = [1, 1, -1, -1, 0];
+
+    #[test]
+    fn depths() {
+        // When
+        let depths = absolute_depths(DEPTH_CHANGES);
+        // Then
+        assert_eq!(depths, vec![0, 1, 2, 1, 0]);
+        assert_eq!(depth_changes(&depths), DEPTH_CHANGES.to_vec());
+        assert_eq!(subtree_end(&depths, 0), 4);
+        assert_eq!(subtree_end(&depths, 1), 3);
+        assert_eq!(subtree_end(&depths, 4), 5);
+    }
+
+    #[test]
+    fn depths_close_unbalanced_folders() {
+        // When
+        let depths = absolute_depths([1, 1, -5, 0]);
+        // Then
+        assert_eq!(depths, vec![0, 1, 2, 0]);
+        assert_eq!(depth_changes(&[0, 1, 2]), vec![1, 1, -2]);
+    }
+
+    #[test]
+    fn tree() {
+        // Given
+        let depths = absolute_depths(DEPTH_CHANGES);
+        // When
+        let tree = build_index_tree(&depths, 0..depths.len());
+        // Then
+        let leaf = |index| IndexTreeNode {
+            index,
+            children: vec![],
+        };
+        assert_eq!(
+            tree,
+            vec![
+                IndexTreeNode {
+                    index: 0,
+                    children: vec![
+                        IndexTreeNode {
+                            index: 1,
+                            children: vec![leaf(2)]
+                        },
+                        leaf(3)
+                    ]
+                },
+                leaf(4)
+            ]
+        );
+    }
+
+    #[test]
+    fn move_into_folder() {
+        // Given
+        let depths = absolute_depths(DEPTH_CHANGES);
+        // When
+        // Move track B into folder A as last child
+        let (order, new_depths) = move_subtree(&depths, 4..5, 4, 1);
+        // Then
+        assert_eq!(order, vec![0, 1, 2, 3, 4]);
+        assert_eq!(depth_changes(&new_depths), vec![1, 1, -1, 0, -1]);
+    }
+
+    #[test]
+    fn move_out_of_folder() {
+        // Given
+        let depths = absolute_depths(DEPTH_CHANGES);
+        // When
+        // Move child A1 (including grandchild) out of folder A
+        let (order, new_depths) = move_subtree(&depths, 1..3, 4, 0);
+        // Then
+        assert_eq!(order, vec![0, 3, 1, 2, 4]);
+        assert_eq!(new_depths, vec![0, 1, 0, 1, 0]);
+        assert_eq!(depth_changes(&new_depths), vec![1, -1, 1, -1, 0]);
+    }
+}
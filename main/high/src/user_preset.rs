@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Maximum number of base64 characters written into one `Data` entry.
+const MAX_DATA_ENTRY_LENGTH: usize = 16384;
+
+/// A user preset of a plug-in, that is, a named state blob.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct UserPreset {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// The content of a user preset file in which REAPER stores all user presets of one plug-in
+/// (e.g. "vst-reaeq.ini" in the "presets" folder of the resource directory).
+///
+/// Use [`Fx::user_preset_file_path`](crate::Fx::user_preset_file_path) to find out the location of
+/// the file. Keep in mind that REAPER caches the presets of open plug-ins, so changes written to
+/// the file might not show up in the preset list of an FX instance that's already open.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct UserPresetFile {
+    presets: Vec<UserPreset>,
+    /// Entries of the `[General]` section other than `NbPresets`, preserved when writing.
+    general_entries: Vec<(String, String)>,
+}
+
+impl UserPresetFile {
+    pub fn from_presets(presets: Vec<UserPreset>) -> UserPresetFile {
+        UserPresetFile {
+            presets,
+            general_entries: Vec::new(),
+        }
+    }
+
+    /// Reads the given user preset file.
+    ///
+    /// Returns an empty preset file if it doesn't exist yet.
+    pub fn load(path: &Path) -> Result<UserPresetFile, &'static str> {
+        match fs::read_to_string(path) {
+            Ok(content) => UserPresetFile::parse(&content),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(UserPresetFile::default()),
+            Err(_) => Err("couldn't read user preset file"),
+        }
+    }
+
+    /// Writes this user preset file to the given path, replacing any existing file.
+    pub fn save(&self, path: &Path) -> Result<(), &'static str> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|_| "couldn't create preset directory")?;
+        }
+        fs::write(path, self.to_ini_string()).map_err(|_| "couldn't write user preset file")
+    }
+
+    pub fn parse(content: &str) -> Result<UserPresetFile, &'static str> {
+        let mut sections: BTreeMap<u32, BTreeMap<&str, &str>> = BTreeMap::new();
+        let mut current_section: Option<&mut BTreeMap<&str, &str>> = None;
+        let mut general_entries = Vec::new();
+        let mut in_general_section = false;
+        for line in content.lines() {
+            let line = line.trim();
+            if let Some(section_name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                in_general_section = section_name == "General";
+                current_section = section_name
+                    .strip_prefix("Preset")
+                    .and_then(|i| i.parse().ok())
+                    .map(|i| sections.entry(i).or_default());
+                continue;
+            }
+            let (key, value) = match line.split_once('=') {
+                None => continue,
+                Some(entry) => entry,
+            };
+            if in_general_section {
+                if key != "NbPresets" {
+                    general_entries.push((key.to_string(), value.to_string()));
+                }
+            } else if let Some(section) = current_section.as_mut() {
+                section.insert(key, value);
+            }
+        }
+        let presets: Result<Vec<_>, _> = sections
+            .values()
+            .map(|entries| {
+                let name = entries.get("Name").ok_or("preset without name")?;
+                let base64_data: String = entries
+                    .get("Data")
+                    .into_iter()
+                    .chain((1..).map_while(|i| entries.get(format!("Data_{}", i).as_str())))
+                    .copied()
+                    .collect();
+                let data = base64::decode(&base64_data).map_err(|_| "invalid preset data")?;
+                if let Some(len) = entries.get("Len") {
+                    if len.parse::<usize>().ok() != Some(data.len()) {
+                        return Err("preset data length mismatch");
+                    }
+                }
+                Ok(UserPreset {
+                    name: name.to_string(),
+                    data,
+                })
+            })
+            .collect();
+        Ok(UserPresetFile {
+            presets: presets?,
+            general_entries,
+        })
+    }
+
+    pub fn to_ini_string(&self) -> String {
+        let mut ini = format!("[General]\nNbPresets={}\n", self.presets.len());
+        for (key, value) in &self.general_entries {
+            ini.push_str(&format!("{}={}\n", key, value));
+        }
+        for (i, preset) in self.presets.iter().enumerate() {
+            ini.push_str(&format!("\n[Preset{}]\n", i));
+            let base64_data = base64::encode(&preset.data);
+            let chunks = base64_data.as_bytes().chunks(MAX_DATA_ENTRY_LENGTH);
+            for (j, chunk) in chunks.enumerate() {
+                // Base64 is pure ASCII, so chunking bytes is safe
+                let chunk = std::str::from_utf8(chunk).unwrap();
+                if j == 0 {
+                    ini.push_str(&format!("Data={}\n", chunk));
+                } else {
+                    ini.push_str(&format!("Data_{}={}\n", j, chunk));
+                }
+            }
+            ini.push_str(&format!("Len={}\n", preset.data.len()));
+            ini.push_str(&format!("Name={}\n", preset.name));
+        }
+        ini
+    }
+
+    pub fn presets(&self) -> &[UserPreset] {
+        &self.presets
+    }
+
+    pub fn preset_names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.presets.iter().map(|p| p.name.as_str())
+    }
+
+    pub fn preset_by_name(&self, name: &str) -> Option<&UserPreset> {
+        self.presets.iter().find(|p| p.name == name)
+    }
+
+    /// Adds the given preset. Replaces an existing preset with the same name.
+    pub fn import_preset(&mut self, preset: UserPreset) {
+        if let Some(existing) = self.presets.iter_mut().find(|p| p.name == preset.name) {
+            *existing = preset;
+        } else {
+            self.presets.push(preset);
+        }
+    }
+
+    /// Creates a new preset file which contains just the presets with the given names.
+    pub fn export_presets<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a str>,
+    ) -> Result<UserPresetFile, &'static str> {
+        let presets: Option<Vec<_>> = names
+            .into_iter()
+            .map(|n| self.preset_by_name(n).cloned())
+            .collect();
+        Ok(UserPresetFile::from_presets(
+            presets.ok_or("preset not found")?,
+        ))
+    }
+
+    pub fn rename_preset(&mut self, old_name: &str, new_name: &str) -> Result<(), &'static str> {
+        if old_name != new_name && self.preset_by_name(new_name).is_some() {
+            return Err("preset with new name exists already");
+        }
+        let preset = self
+            .presets
+            .iter_mut()
+            .find(|p| p.name == old_name)
+            .ok_or("preset not found")?;
+        preset.name = new_name.to_string();
+        Ok(())
+    }
+
+    pub fn remove_preset(&mut self, name: &str) -> Result<UserPreset, &'static str> {
+        let index = self
+            .presets
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or("preset not found")?;
+        Ok(self.presets.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_FILE: &str = r#"[General]
+NbPresets=2
+
+[Preset0]
+Data=AQIDBA==
+Len=4
+Name=Bright
+
+[Preset1]
+Data=aGVsbG8g
+Data_1=d29ybGQ=
+Len=11
+Name=Split data
+"#;
+
+    #[test]
+    fn parse() {
+        // When
+        let file = UserPresetFile::parse(SAMPLE_FILE).unwrap();
+        // Then
+        assert_eq!(
+            file.presets(),
+            &[
+                UserPreset {
+                    name: "Bright".into(),
+                    data: vec![1, 2, 3, 4]
+                },
+                UserPreset {
+                    name: "Split data".into(),
+                    data: b"hello world".to_vec()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_length_mismatch() {
+        // Given
+        let content = "[Preset0]\nData=AQIDBA==\nLen=5\nName=Broken\n";
+        // When
+        let result = UserPresetFile::parse(content);
+        // Then
+        assert_eq!(result, Err("preset data length mismatch"));
+    }
+
+    #[test]
+    fn round_trip() {
+        // Given
+        let file = UserPresetFile::from_presets(vec![
+            UserPreset {
+                name: "Small".into(),
+                data: vec![1, 2, 3],
+            },
+            UserPreset {
+                name: "Large".into(),
+                data: vec![7; MAX_DATA_ENTRY_LENGTH],
+            },
+        ]);
+        // When
+        let ini = file.to_ini_string();
+        // Then
+        assert!(ini.contains("Data_1="));
+        assert_eq!(UserPresetFile::parse(&ini), Ok(file));
+    }
+
+    #[test]
+    fn preserve_unknown_general_entries() {
+        // Given
+        let content = SAMPLE_FILE.replace("NbPresets=2\n", "NbPresets=2\nLastDir=/tmp\n");
+        let mut file = UserPresetFile::parse(&content).unwrap();
+        // When
+        file.remove_preset("Bright").unwrap();
+        let ini = file.to_ini_string();
+        // Then
+        assert!(ini.starts_with("[General]\nNbPresets=1\nLastDir=/tmp\n"));
+        assert_eq!(UserPresetFile::parse(&ini), Ok(file));
+    }
+
+    #[test]
+    fn manage_presets() {
+        // Given
+        let mut file = UserPresetFile::parse(SAMPLE_FILE).unwrap();
+        // When
+        file.rename_preset("Bright", "Brighter").unwrap();
+        let removed = file.remove_preset("Split data").unwrap();
+        file.import_preset(UserPreset {
+            name: "Imported".into(),
+            data: vec![5],
+        });
+        // Then
+        assert_eq!(removed.data, b"hello world".to_vec());
+        assert_eq!(
+            file.preset_names().collect::<Vec<_>>(),
+            vec!["Brighter", "Imported"]
+        );
+        assert_eq!(
+            file.rename_preset("Brighter", "Imported"),
+            Err("preset with new name exists already")
+        );
+        let exported = file.export_presets(["Imported"]).unwrap();
+        assert_eq!(
+            exported.preset_names().collect::<Vec<_>>(),
+            vec!["Imported"]
+        );
+        assert!(file.export_presets(["Unknown"]).is_err());
+    }
+}
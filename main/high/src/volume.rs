@@ -4,7 +4,7 @@ use std::convert::TryFrom;
 use std::fmt;
 
 /// TODO-medium This struct needs an overhaul, not ready for prime time at all.
-#[derive(Debug)]
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Volume {
     soft_normalized_value: f64,
 }
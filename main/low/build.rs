@@ -173,6 +173,7 @@ mod codegen {
                 .whitelist_type("gaccel_register_t")
                 .whitelist_type("accelerator_register_t")
                 .whitelist_type("audio_hook_register_t")
+                .whitelist_type("project_config_extension_t")
                 .whitelist_type("midi_realtime_write_struct_t")
                 .whitelist_type("midi_quantize_mode_t")
                 .whitelist_type("KbdSectionInfo")
@@ -1172,6 +1172,34 @@ pub mod root {
     }
     #[repr(C)]
     #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+    pub struct project_config_extension_t {
+        pub ProcessExtensionLine: ::std::option::Option<
+            unsafe extern "C" fn(
+                line: *const ::std::os::raw::c_char,
+                ctx: *mut root::ProjectStateContext,
+                isUndo: bool,
+                reg: *mut root::project_config_extension_t,
+            ) -> bool,
+        >,
+        pub SaveExtensionConfig: ::std::option::Option<
+            unsafe extern "C" fn(
+                ctx: *mut root::ProjectStateContext,
+                isUndo: bool,
+                reg: *mut root::project_config_extension_t,
+            ),
+        >,
+        pub BeginLoadProjectState: ::std::option::Option<
+            unsafe extern "C" fn(isUndo: bool, reg: *mut root::project_config_extension_t),
+        >,
+        pub userData: *mut ::std::os::raw::c_void,
+    }
+    impl Default for project_config_extension_t {
+        fn default() -> Self {
+            unsafe { ::std::mem::zeroed() }
+        }
+    }
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
     pub struct audio_hook_register_t {
         pub OnAudioBuffer: ::std::option::Option<
             unsafe extern "C" fn(
@@ -2431,6 +2459,16 @@ pub mod root {
                 ) -> *const ::std::os::raw::c_char,
             >;
         }
+        extern "C" {
+            #[link_name = "\u{1}_ZN16reaper_functions15EnumInstalledFXE"]
+            pub static mut EnumInstalledFX: ::std::option::Option<
+                unsafe extern "C" fn(
+                    index: ::std::os::raw::c_int,
+                    nameOut: *mut *const ::std::os::raw::c_char,
+                    identOut: *mut *const ::std::os::raw::c_char,
+                ) -> bool,
+            >;
+        }
         extern "C" {
             #[link_name = "\u{1}_ZN16reaper_functions19EnumPitchShiftModesE"]
             pub static mut EnumPitchShiftModes: ::std::option::Option<
@@ -3,8 +3,9 @@
 namespace reaper_project_state_context {
   // Rust -> C++
   void rust_to_cpp_ProjectStateContext_AddLine(ProjectStateContext* self, const char* line) {
-    // TODO-high This can't work. Wait for variadics support in stable Rust.
-//    self->AddLine(line);
+    // Passing the line as argument for a fixed format string makes sure that REAPER doesn't
+    // interpret any format specifiers contained in the line.
+    self->AddLine("%s", line);
   }
   int rust_to_cpp_ProjectStateContext_GetLine(ProjectStateContext* self, char* buf, int buflen) {
     return self->GetLine(buf, buflen);
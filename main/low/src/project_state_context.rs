@@ -8,7 +8,10 @@ use std::os::raw::c_void;
 use std::ptr::NonNull;
 
 impl raw::ProjectStateContext {
-    /// Attention: Not really usable yet due to the lack of the variadic parameter in AddLine.
+    /// Adds the given line.
+    ///
+    /// The C++ method takes a format string and variadic arguments. This wrapper passes the given
+    /// line as single argument for a `%s` format, so it's added literally.
     ///
     /// # Safety
     ///
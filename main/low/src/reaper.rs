@@ -581,6 +581,10 @@ impl Reaper {
                     plugin_context
                         .GetFunc(c_str_macro::c_str!(stringify!(EnumerateSubdirectories)).as_ptr()),
                 ),
+                EnumInstalledFX: std::mem::transmute(
+                    plugin_context
+                        .GetFunc(c_str_macro::c_str!(stringify!(EnumInstalledFX)).as_ptr()),
+                ),
                 EnumPitchShiftModes: std::mem::transmute(
                     plugin_context
                         .GetFunc(c_str_macro::c_str!(stringify!(EnumPitchShiftModes)).as_ptr()),
@@ -3691,6 +3695,9 @@ impl Reaper {
         if pointers.EnumerateSubdirectories.is_some() {
             loaded_count += 1;
         }
+        if pointers.EnumInstalledFX.is_some() {
+            loaded_count += 1;
+        }
         if pointers.EnumPitchShiftModes.is_some() {
             loaded_count += 1;
         }
@@ -7783,6 +7790,23 @@ impl Reaper {
     #[doc = r" # Safety"]
     #[doc = r""]
     #[doc = r" REAPER can crash if you pass an invalid pointer."]
+    pub unsafe fn EnumInstalledFX(
+        &self,
+        index: ::std::os::raw::c_int,
+        nameOut: *mut *const ::std::os::raw::c_char,
+        identOut: *mut *const ::std::os::raw::c_char,
+    ) -> bool {
+        match self.pointers.EnumInstalledFX {
+            None => panic!(
+                "Attempt to use a function that has not been loaded: {}",
+                stringify!(EnumInstalledFX)
+            ),
+            Some(f) => f(index, nameOut, identOut),
+        }
+    }
+    #[doc = r" # Safety"]
+    #[doc = r""]
+    #[doc = r" REAPER can crash if you pass an invalid pointer."]
     pub unsafe fn EnumPitchShiftModes(
         &self,
         mode: ::std::os::raw::c_int,
@@ -19791,6 +19815,13 @@ pub struct ReaperFunctionPointers {
             subdirindex: ::std::os::raw::c_int,
         ) -> *const ::std::os::raw::c_char,
     >,
+    pub EnumInstalledFX: Option<
+        unsafe extern "C" fn(
+            index: ::std::os::raw::c_int,
+            nameOut: *mut *const ::std::os::raw::c_char,
+            identOut: *mut *const ::std::os::raw::c_char,
+        ) -> bool,
+    >,
     pub EnumPitchShiftModes: Option<
         unsafe extern "C" fn(
             mode: ::std::os::raw::c_int,
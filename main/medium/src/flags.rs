@@ -83,3 +83,14 @@ pub enum AcceleratorBehavior {
     /// If this flag is not specified, key is assumed to specify a character code.
     VirtKey = 0x01,
 }
+
+/// Performance option of a track.
+#[enumflags2::bitflags]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[repr(u32)]
+pub enum TrackPerformanceFlag {
+    /// Disables media buffering.
+    NoMediaBuffering = 1,
+    /// Disables anticipative FX processing.
+    NoAnticipativeFx = 2,
+}
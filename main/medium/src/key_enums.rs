@@ -33,6 +33,16 @@ pub enum TrackAttributeKey<'a> {
     ///
     /// `*const char`
     TcpLayout,
+    /// Razor edits of the track, only accessible via a `_string()` function.
+    ///
+    /// A space-separated list of `start end "envelope GUID"` triples. The GUID is empty for
+    /// media item razor edits.
+    RazorEdits,
+    /// Like [`RazorEdits`] but each edit additionally contains its vertical extent as
+    /// `top bottom` fractions of the track height. Edits are separated by commas.
+    ///
+    /// [`RazorEdits`]: #variant.RazorEdits
+    RazorEditsExt,
     /// Extension-specific persistent data.
     ///
     /// `*mut char`
@@ -456,6 +466,8 @@ impl<'a> TrackAttributeKey<'a> {
             Name => reaper_str!("P_NAME").into(),
             ParTrack => reaper_str!("P_PARTRACK").into(),
             Project => reaper_str!("P_PROJECT").into(),
+            RazorEdits => reaper_str!("P_RAZOREDITS").into(),
+            RazorEditsExt => reaper_str!("P_RAZOREDITS_EXT").into(),
             TcpLayout => reaper_str!("P_TCP_LAYOUT").into(),
             Custom(key) => key,
         }
@@ -476,6 +488,13 @@ pub enum TakeAttributeKey<'a> {
     ///
     /// `*mut PCM_source`
     Source,
+    /// Custom take color.
+    ///
+    /// `*mut i32`
+    ///
+    /// OS-dependent color | 0x1000000. If 0x1000000 is not set, the color is stored but not
+    /// used.
+    CustomColor,
     /// If a variant is missing in this enum, you can use this custom one as a resort.
     ///
     /// Use [`custom()`] to create this variant.
@@ -497,6 +516,56 @@ impl<'a> TakeAttributeKey<'a> {
         match self {
             Source => reaper_str!("P_SOURCE").into(),
             StartOffs => reaper_str!("D_STARTOFFS").into(),
+            CustomColor => reaper_str!("I_CUSTOMCOLOR").into(),
+            Custom(key) => key,
+        }
+    }
+}
+
+/// Item attribute key which you can pass to [`get_media_item_info_value()`].
+///
+/// [`get_media_item_info_value()`]: struct.Reaper.html#method.get_media_item_info_value
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ItemAttributeKey<'a> {
+    /// Item position in seconds.
+    Position,
+    /// Item length in seconds.
+    Length,
+    /// Whether the item is muted.
+    Mute,
+    /// Whether the item is selected.
+    Selected,
+    /// Custom item color.
+    ///
+    /// `*mut i32`
+    ///
+    /// OS-dependent color | 0x1000000. If 0x1000000 is not set, the color is stored but not
+    /// used.
+    CustomColor,
+    /// If a variant is missing in this enum, you can use this custom one as a resort.
+    ///
+    /// Use [`custom()`] to create this variant.
+    ///
+    /// [`custom()`]: #method.custom
+    Custom(Cow<'a, ReaperStr>),
+}
+
+impl<'a> ItemAttributeKey<'a> {
+    /// Convenience function for creating a [`Custom`] key.
+    ///
+    /// [`Custom`]: #variant.Custom
+    pub fn custom(key: impl Into<ReaperStringArg<'a>>) -> ItemAttributeKey<'a> {
+        ItemAttributeKey::Custom(key.into().into_inner())
+    }
+
+    pub(crate) fn into_raw(self) -> Cow<'a, ReaperStr> {
+        use ItemAttributeKey::*;
+        match self {
+            Position => reaper_str!("D_POSITION").into(),
+            Length => reaper_str!("D_LENGTH").into(),
+            Mute => reaper_str!("B_MUTE").into(),
+            Selected => reaper_str!("B_UISEL").into(),
+            CustomColor => reaper_str!("I_CUSTOMCOLOR").into(),
             Custom(key) => key,
         }
     }
@@ -573,6 +642,10 @@ pub enum TrackSendAttributeKey<'a> {
     /// - 1 → pre-fx
     /// - 2 → post-fx (deprecated)
     /// - 3 → post-fx
+    ///
+    /// See [`SendMode`] for a typed representation.
+    ///
+    /// [`SendMode`]: enum.SendMode.html
     SendMode,
     /// Automation mode.
     ///
@@ -589,20 +662,61 @@ pub enum TrackSendAttributeKey<'a> {
     ///
     /// `*mut i32`
     ///
-    /// Index, &1024 → mono, -1 → none
+    /// Low 10 bits → channel offset, higher bits → channel count (0 → stereo, 1 → mono,
+    /// 2 → 4 channels, 3 → 6 channels etc.), -1 → none
+    ///
+    /// See [`SendChannelMapping`] for a typed representation.
+    ///
+    /// [`SendChannelMapping`]: struct.SendChannelMapping.html
     SrcChan,
     /// Destination channel.
     ///
     /// `*mut i32`
-    /// Index, &1024 → mono, otherwise stereo pair, hwout: &512 → rearoute
+    ///
+    /// Index, &1024 → mix to mono, hwout: &512 → rearoute
     DstChan,
     /// MIDI flags.
     ///
     /// `*mut i32`
     ///
-    /// - Low 5 bits → source channel (0 → all, 1..=16)
+    /// - Low 5 bits → source channel (0 → all, 1..=16, 31 → MIDI send disabled)
     /// - Next 5 bits → destination channel (0 → original, 1..=16)
+    /// - &1024 → faders send MIDI volume/pan
+    /// - (>> 14) & 255 → source bus (0 → all, 1 → normal, 2+)
+    /// - (>> 22) & 255 → destination bus (0 → original, 1 → normal, 2+)
+    ///
+    /// See [`MidiSendMapping`] for a typed representation.
+    ///
+    /// [`MidiSendMapping`]: struct.MidiSendMapping.html
     MidiFlags,
+    /// MIDI source channel.
+    ///
+    /// `*mut i32`
+    ///
+    /// 0 → all, 1..=16, -1 → MIDI send disabled
+    MidiSrcChan,
+    /// MIDI source bus.
+    ///
+    /// `*mut i32`
+    ///
+    /// 0 → all, 1 → normal, 2+
+    MidiSrcBus,
+    /// MIDI destination channel.
+    ///
+    /// `*mut i32`
+    ///
+    /// 0 → original, 1..=16
+    MidiDstChan,
+    /// MIDI destination bus.
+    ///
+    /// `*mut i32`
+    ///
+    /// 0 → original, 1 → normal, 2+
+    MidiDstBus,
+    /// Whether the send's volume and pan faders send MIDI volume and pan messages.
+    ///
+    /// `*mut bool`
+    MidiLinkVolPan,
     /// If a variant is missing in this enum, you can use this custom one as a resort.
     ///
     /// Use [`custom()`] to create this variant.
@@ -638,6 +752,11 @@ impl<'a> TrackSendAttributeKey<'a> {
             AutoMode => reaper_str!("I_AUTOMODE").into(),
             DstChan => reaper_str!("I_DSTCHAN").into(),
             MidiFlags => reaper_str!("I_MIDIFLAGS").into(),
+            MidiSrcChan => reaper_str!("I_MIDI_SRCCHAN").into(),
+            MidiSrcBus => reaper_str!("I_MIDI_SRCBUS").into(),
+            MidiDstChan => reaper_str!("I_MIDI_DSTCHAN").into(),
+            MidiDstBus => reaper_str!("I_MIDI_DSTBUS").into(),
+            MidiLinkVolPan => reaper_str!("I_MIDI_LINK_VOLPAN").into(),
             SendMode => reaper_str!("I_SENDMODE").into(),
             SrcChan => reaper_str!("I_SRCCHAN").into(),
             DestTrack => reaper_str!("P_DESTTRACK").into(),
@@ -705,6 +824,102 @@ impl<'a> EnvChunkName<'a> {
     }
 }
 
+/// Track group parameter which you can pass to [`get_set_track_group_membership()`].
+///
+/// Each parameter has its own set of group memberships. A track can for example be volume lead
+/// in group 1 but only volume follower in group 2.
+///
+/// [`get_set_track_group_membership()`]: struct.Reaper.html#method.get_set_track_group_membership
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Ord, PartialOrd)]
+pub enum TrackGroupParam {
+    VolumeLead,
+    VolumeFollow,
+    VolumeVcaLead,
+    VolumeVcaFollow,
+    PanLead,
+    PanFollow,
+    WidthLead,
+    WidthFollow,
+    MuteLead,
+    MuteFollow,
+    SoloLead,
+    SoloFollow,
+    RecArmLead,
+    RecArmFollow,
+    PolarityLead,
+    PolarityFollow,
+    AutomationModeLead,
+    AutomationModeFollow,
+    /// Reverses the direction of volume changes coming from the lead.
+    VolumeReverse,
+    /// Reverses the direction of pan changes coming from the lead.
+    PanReverse,
+    /// Reverses the direction of width changes coming from the lead.
+    WidthReverse,
+    /// Prevents the track from acting as lead while it follows.
+    NoLeadWhenFollow,
+    /// Applies VCA volume following pre-FX.
+    VolumeVcaFollowIsPreFx,
+}
+
+impl TrackGroupParam {
+    /// All parameters in the order in which REAPER displays them.
+    pub const ALL: [TrackGroupParam; 23] = [
+        TrackGroupParam::VolumeLead,
+        TrackGroupParam::VolumeFollow,
+        TrackGroupParam::VolumeVcaLead,
+        TrackGroupParam::VolumeVcaFollow,
+        TrackGroupParam::PanLead,
+        TrackGroupParam::PanFollow,
+        TrackGroupParam::WidthLead,
+        TrackGroupParam::WidthFollow,
+        TrackGroupParam::MuteLead,
+        TrackGroupParam::MuteFollow,
+        TrackGroupParam::SoloLead,
+        TrackGroupParam::SoloFollow,
+        TrackGroupParam::RecArmLead,
+        TrackGroupParam::RecArmFollow,
+        TrackGroupParam::PolarityLead,
+        TrackGroupParam::PolarityFollow,
+        TrackGroupParam::AutomationModeLead,
+        TrackGroupParam::AutomationModeFollow,
+        TrackGroupParam::VolumeReverse,
+        TrackGroupParam::PanReverse,
+        TrackGroupParam::WidthReverse,
+        TrackGroupParam::NoLeadWhenFollow,
+        TrackGroupParam::VolumeVcaFollowIsPreFx,
+    ];
+
+    pub(crate) fn into_raw(self) -> &'static ReaperStr {
+        use TrackGroupParam::*;
+        match self {
+            VolumeLead => reaper_str!("VOLUME_LEAD"),
+            VolumeFollow => reaper_str!("VOLUME_FOLLOW"),
+            VolumeVcaLead => reaper_str!("VOLUME_VCA_LEAD"),
+            VolumeVcaFollow => reaper_str!("VOLUME_VCA_FOLLOW"),
+            PanLead => reaper_str!("PAN_LEAD"),
+            PanFollow => reaper_str!("PAN_FOLLOW"),
+            WidthLead => reaper_str!("WIDTH_LEAD"),
+            WidthFollow => reaper_str!("WIDTH_FOLLOW"),
+            MuteLead => reaper_str!("MUTE_LEAD"),
+            MuteFollow => reaper_str!("MUTE_FOLLOW"),
+            SoloLead => reaper_str!("SOLO_LEAD"),
+            SoloFollow => reaper_str!("SOLO_FOLLOW"),
+            RecArmLead => reaper_str!("RECARM_LEAD"),
+            RecArmFollow => reaper_str!("RECARM_FOLLOW"),
+            PolarityLead => reaper_str!("POLARITY_LEAD"),
+            PolarityFollow => reaper_str!("POLARITY_FOLLOW"),
+            AutomationModeLead => reaper_str!("AUTOMODE_LEAD"),
+            AutomationModeFollow => reaper_str!("AUTOMODE_FOLLOW"),
+            VolumeReverse => reaper_str!("VOLUME_REVERSE"),
+            PanReverse => reaper_str!("PAN_REVERSE"),
+            WidthReverse => reaper_str!("WIDTH_REVERSE"),
+            NoLeadWhenFollow => reaper_str!("NO_LEAD_WHEN_FOLLOW"),
+            VolumeVcaFollowIsPreFx => reaper_str!("VOLUME_VCA_FOLLOW_ISPREFX"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -357,6 +357,9 @@ pub use gaccel_register::*;
 mod accelerator_register;
 pub use accelerator_register::*;
 
+mod project_config_extension;
+pub use project_config_extension::*;
+
 mod preview_register;
 pub use preview_register::*;
 
@@ -398,6 +401,9 @@ pub use string_types::*;
 mod recording_input;
 pub use recording_input::*;
 
+mod send_mapping;
+pub use send_mapping::*;
+
 mod automation_mode;
 pub use automation_mode::*;
 
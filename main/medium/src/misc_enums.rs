@@ -1,6 +1,7 @@
 use crate::{
     BookmarkId, CommandId, Hidden, Hwnd, KbdSectionInfo, MediaTrack, MidiFrameOffset,
-    MidiOutputDeviceId, ReaProject, ReaperPanValue, ReaperStr, ReaperStringArg, ReaperWidthValue,
+    MidiOutputDeviceId, ReaProject, ReaperNormalizedFxParamValue, ReaperPanValue, ReaperStr,
+    ReaperStringArg, ReaperWidthValue,
 };
 
 use crate::util::concat_reaper_strs;
@@ -63,6 +64,40 @@ pub enum AutoSeekBehavior {
     AllowAutoSeek,
 }
 
+/// Part of the arrange view which has the keyboard focus.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum CursorContext {
+    Tracks,
+    Items,
+    Envelopes,
+}
+
+impl CursorContext {
+    /// Converts an integer as returned by the low-level API to a cursor context.
+    ///
+    /// Returns `None` if the value doesn't denote a known context (e.g. -1 for "unknown").
+    pub fn from_raw(v: i32) -> Option<CursorContext> {
+        use CursorContext::*;
+        let context = match v {
+            0 => Tracks,
+            1 => Items,
+            2 => Envelopes,
+            _ => return None,
+        };
+        Some(context)
+    }
+
+    /// Converts this value to an integer as expected by the low-level API.
+    pub fn to_raw(self) -> i32 {
+        use CursorContext::*;
+        match self {
+            Tracks => 0,
+            Items => 1,
+            Envelopes => 2,
+        }
+    }
+}
+
 /// Determines how to deal with the master track.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum MasterTrackBehavior {
@@ -190,6 +225,39 @@ pub enum TrackDefaultsBehavior {
     AddDefaultEnvAndFx,
 }
 
+/// Determines how moved tracks are integrated into the folder structure.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum TrackReorderBehavior {
+    /// Tracks keep their folder depth changes (REAPER might still adjust them).
+    Normal,
+    /// Tracks become children of the track preceding the insertion point.
+    MakePrevFolder,
+    /// If the track preceding the insertion point is the last track in a folder, tracks become
+    /// the last children of that folder.
+    AppendToPrevFolder,
+}
+
+impl TrackReorderBehavior {
+    /// Converts this value to an integer as expected by the low-level API.
+    pub fn to_raw(self) -> i32 {
+        use TrackReorderBehavior::*;
+        match self {
+            Normal => 0,
+            MakePrevFolder => 1,
+            AppendToPrevFolder => 2,
+        }
+    }
+}
+
+/// Determines whether theme color transformations should be taken into account.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ThemeColorTransformBehavior {
+    /// Uses the current color including transformations (e.g. brightness/contrast tweaks).
+    ApplyTransforms,
+    /// Uses the color as originally specified by the theme.
+    IgnoreTransforms,
+}
+
 /// Determines the gang behavior.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum GangBehavior {
@@ -683,6 +751,16 @@ pub enum RegistrationObject<'a> {
     /// (IReaperControlSurface*)instance
     /// ```
     CsurfInst(NonNull<raw::IReaperControlSurface>),
+    /// A project config extension (useful for being notified about project load/save and for
+    /// storing custom data in the project file).
+    ///
+    /// Extract from `reaper_plugin.h`:
+    ///
+    /// ```text
+    /// plug-ins may or may not want to save their undo states (look at isUndo)
+    /// undo states will be saved if UNDO_STATE_MISCCFG is set (for adding your own undo points)
+    /// ```
+    ProjectConfig(NonNull<raw::project_config_extension_t>),
     /// If a variant is missing in this enum, you can use this custom one as a resort.
     ///
     /// Use [`custom()`] to create this variant.
@@ -792,6 +870,10 @@ impl<'a> RegistrationObject<'a> {
                 key: reaper_str!("csurf_inst").into(),
                 value: inst.as_ptr() as _,
             },
+            ProjectConfig(reg) => PluginRegistration {
+                key: reaper_str!("projectconfig").into(),
+                value: reg.as_ptr() as _,
+            },
             Custom(key, value) => PluginRegistration {
                 key: key.into_owned().into(),
                 value,
@@ -860,6 +942,110 @@ impl InputMonitoringMode {
     }
 }
 
+/// Defines what a record-armed track records.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum RecordingMode {
+    Input,
+    StereoOut,
+    /// Records nothing (input monitoring only).
+    Disabled,
+    StereoOutWithLatencyCompensation,
+    MidiOutput,
+    MonoOut,
+    MonoOutWithLatencyCompensation,
+    MidiOverdub,
+    MidiReplace,
+    /// Represents a variant unknown to *reaper-rs*. Please contribute if you encounter a variant
+    /// that is supported by REAPER but not yet by *reaper-rs*. Thanks!
+    Unknown(Hidden<i32>),
+}
+
+impl RecordingMode {
+    /// Converts an integer as returned by the low-level API to a recording mode.
+    pub fn from_raw(v: i32) -> RecordingMode {
+        use RecordingMode::*;
+        match v {
+            0 => Input,
+            1 => StereoOut,
+            2 => Disabled,
+            3 => StereoOutWithLatencyCompensation,
+            4 => MidiOutput,
+            5 => MonoOut,
+            6 => MonoOutWithLatencyCompensation,
+            7 => MidiOverdub,
+            8 => MidiReplace,
+            x => Unknown(Hidden(x)),
+        }
+    }
+
+    /// Converts this value to an integer as expected by the low-level API.
+    pub fn to_raw(self) -> i32 {
+        use RecordingMode::*;
+        match self {
+            Input => 0,
+            StereoOut => 1,
+            Disabled => 2,
+            StereoOutWithLatencyCompensation => 3,
+            MidiOutput => 4,
+            MonoOut => 5,
+            MonoOutWithLatencyCompensation => 6,
+            MidiOverdub => 7,
+            MidiReplace => 8,
+            Unknown(Hidden(x)) => x,
+        }
+    }
+}
+
+/// Defines what the VU meter of a track displays.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum VuMode {
+    StereoPeaks,
+    MultichannelPeaks,
+    StereoRms,
+    CombinedRms,
+    /// Momentary loudness (channels 1 and 2 only).
+    LufsM,
+    /// Short-term loudness with maximum readout (channels 1 and 2 only).
+    LufsSMax,
+    /// Short-term loudness with current readout (channels 1 and 2 only).
+    LufsSCurrent,
+    /// Represents a variant unknown to *reaper-rs*. Please contribute if you encounter a variant
+    /// that is supported by REAPER but not yet by *reaper-rs*. Thanks!
+    Unknown(Hidden<i32>),
+}
+
+impl VuMode {
+    /// Converts an integer as returned by the low-level API to a VU mode.
+    pub fn from_raw(v: i32) -> VuMode {
+        use VuMode::*;
+        match v {
+            0 => StereoPeaks,
+            2 => MultichannelPeaks,
+            4 => StereoRms,
+            8 => CombinedRms,
+            12 => LufsM,
+            16 => LufsSMax,
+            20 => LufsSCurrent,
+            x => Unknown(Hidden(x)),
+        }
+    }
+
+    /// Converts this value to an integer as expected by the low-level API.
+    pub fn to_raw(self) -> i32 {
+        use VuMode::*;
+        match self {
+            StereoPeaks => 0,
+            MultichannelPeaks => 2,
+            StereoRms => 4,
+            CombinedRms => 8,
+            LufsM => 12,
+            LufsSMax => 16,
+            LufsSCurrent => 20,
+            Unknown(Hidden(x)) => x,
+        }
+    }
+}
+
 /// Track solo mode.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum SoloMode {
@@ -1408,3 +1594,132 @@ impl<'a> ParamId<'a> {
         }
     }
 }
+
+/// Type of a ReaEQ band.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum EqBandType {
+    HighPass,
+    LowShelf,
+    Band,
+    Notch,
+    HighShelf,
+    LowPass,
+    BandPass,
+    ParallelBandPass,
+    /// Represents a variant unknown to *reaper-rs*. Please contribute if you encounter a variant
+    /// that is supported by REAPER but not yet by *reaper-rs*. Thanks!
+    Unknown(Hidden<i32>),
+}
+
+impl EqBandType {
+    /// Converts an integer as returned by the low-level API to an EQ band type.
+    pub fn from_raw(v: i32) -> EqBandType {
+        use EqBandType::*;
+        match v {
+            0 => HighPass,
+            1 => LowShelf,
+            2 => Band,
+            3 => Notch,
+            4 => HighShelf,
+            5 => LowPass,
+            6 => BandPass,
+            7 => ParallelBandPass,
+            x => Unknown(Hidden(x)),
+        }
+    }
+
+    /// Converts this value to an integer as expected by the low-level API.
+    pub fn to_raw(self) -> i32 {
+        use EqBandType::*;
+        match self {
+            HighPass => 0,
+            LowShelf => 1,
+            Band => 2,
+            Notch => 3,
+            HighShelf => 4,
+            LowPass => 5,
+            BandPass => 6,
+            ParallelBandPass => 7,
+            Unknown(Hidden(x)) => x,
+        }
+    }
+}
+
+/// Refers to a ReaEQ band or to the master gain.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum EqBandRef {
+    /// The master gain of the EQ.
+    MasterGain,
+    /// The band with the given type.
+    ///
+    /// `index` 0 targets the first band of this type, 1 the second band of this type and so on.
+    Band { band_type: EqBandType, index: u32 },
+}
+
+impl EqBandRef {
+    /// Converts an integer pair as returned by the low-level API to an EQ band reference.
+    pub fn from_raw(band_type: i32, band_index: i32) -> EqBandRef {
+        if band_type == -1 {
+            EqBandRef::MasterGain
+        } else {
+            EqBandRef::Band {
+                band_type: EqBandType::from_raw(band_type),
+                index: band_index.max(0) as u32,
+            }
+        }
+    }
+
+    /// Converts this value to an integer pair as expected by the low-level API.
+    pub fn to_raw(self) -> (i32, i32) {
+        match self {
+            EqBandRef::MasterGain => (-1, 0),
+            EqBandRef::Band { band_type, index } => (band_type.to_raw(), index as i32),
+        }
+    }
+}
+
+/// Type of a ReaEQ band parameter.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum EqParamType {
+    Frequency,
+    Gain,
+    /// Q or bandwidth, depending on the ReaEQ settings.
+    Q,
+    /// Represents a variant unknown to *reaper-rs*. Please contribute if you encounter a variant
+    /// that is supported by REAPER but not yet by *reaper-rs*. Thanks!
+    Unknown(Hidden<i32>),
+}
+
+impl EqParamType {
+    /// Converts an integer as returned by the low-level API to an EQ parameter type.
+    pub fn from_raw(v: i32) -> EqParamType {
+        use EqParamType::*;
+        match v {
+            0 => Frequency,
+            1 => Gain,
+            2 => Q,
+            x => Unknown(Hidden(x)),
+        }
+    }
+
+    /// Converts this value to an integer as expected by the low-level API.
+    pub fn to_raw(self) -> i32 {
+        use EqParamType::*;
+        match self {
+            Frequency => 0,
+            Gain => 1,
+            Q => 2,
+            Unknown(Hidden(x)) => x,
+        }
+    }
+}
+
+/// A value for a ReaEQ parameter.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum EqParamValue {
+    /// Normalized value.
+    Normalized(ReaperNormalizedFxParamValue),
+    /// Value in the parameter's native unit: Hz for frequencies, dB for gain and octaves (or Q)
+    /// for the Q parameter.
+    Native(f64),
+}
@@ -7,7 +7,7 @@ use crate::util::{create_passing_c_str, with_string_buffer};
 use crate::{
     BorrowedMidiEventList, Bpm, DurationInBeats, DurationInSeconds, ExtendedArgs, Hwnd, Hz,
     MediaItemTake, PcmSource, PositionInSeconds, ReaperFunctionError, ReaperFunctionResult,
-    ReaperStr, ReaperString,
+    ReaperStr, ReaperString, ReaperStringArg,
 };
 use reaper_low::raw::{PCM_source, PCM_source_peaktransfer_t, PCM_source_transfer_t, HWND__};
 use std::borrow::Borrow;
@@ -197,10 +197,48 @@ impl PcmSourcePeakTransfer {
 pub struct BorrowedProjectStateContext(raw::ProjectStateContext);
 
 impl BorrowedProjectStateContext {
+    /// Creates a medium-level representation from the given low-level reference.
+    pub fn from_raw(raw: &raw::ProjectStateContext) -> &Self {
+        Self::ref_cast(raw)
+    }
+
+    /// Creates a mutable medium-level representation from the given low-level reference.
+    pub fn from_raw_mut(raw: &mut raw::ProjectStateContext) -> &mut Self {
+        Self::ref_cast_mut(raw)
+    }
+
     /// Returns the pointer to this context.
     pub fn as_ptr(&self) -> NonNull<raw::ProjectStateContext> {
         NonNull::from(&self.0)
     }
+
+    /// Adds the given line to the project state.
+    ///
+    /// The line is added literally, it's not interpreted as format string.
+    pub fn add_line<'a>(&mut self, line: impl Into<ReaperStringArg<'a>>) {
+        unsafe {
+            self.0.AddLine(line.into().as_ptr());
+        }
+    }
+
+    /// Reads the next line from the project state.
+    ///
+    /// With `buffer_size` you can tell REAPER how many bytes of the line you want. Returns `None`
+    /// if there are no more lines.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given buffer size is 0.
+    pub fn get_line(&mut self, buffer_size: u32) -> Option<ReaperString> {
+        assert!(buffer_size > 0);
+        let (line, result) = with_string_buffer(buffer_size, |buffer, max_size| unsafe {
+            self.0.GetLine(buffer, max_size)
+        });
+        if result != 0 {
+            return None;
+        }
+        Some(line)
+    }
 }
 
 // Case 3: Internals exposed: no | vtable: yes
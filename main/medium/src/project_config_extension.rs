@@ -0,0 +1,147 @@
+use crate::{decode_user_data, encode_user_data, BorrowedProjectStateContext, ReaperStr};
+use reaper_low::{firewall, raw};
+use ref_cast::RefCast;
+use std::fmt::{Debug, Formatter};
+use std::os::raw::c_char;
+
+/// Consumers need to implement this trait in order to be called back when REAPER loads or saves
+/// project state.
+///
+/// All methods have default implementations, so you only need to implement the ones you are
+/// interested in.
+///
+/// See [`plugin_register_add_project_config()`].
+///
+/// [`plugin_register_add_project_config()`]: struct.ReaperSession.html#method.plugin_register_add_project_config
+pub trait ProjectConfigExtension {
+    /// Called for each line in the project state which REAPER doesn't know itself.
+    ///
+    /// Should return `true` if the line (and optionally subsequent lines read via
+    /// [`get_line()`]) has been processed, `false` if it's not a line of this extension.
+    ///
+    /// [`get_line()`]: struct.BorrowedProjectStateContext.html#method.get_line
+    fn process_extension_line(&mut self, args: ProcessExtensionLineArgs) -> bool {
+        let _ = args;
+        false
+    }
+
+    /// Called when REAPER saves the project state, either to a file or as undo point.
+    ///
+    /// Lines written via [`add_line()`] end up in the project state. Undo points only contain
+    /// them if the undo point has been created with a scope that includes [`MiscCfg`].
+    ///
+    /// [`add_line()`]: struct.BorrowedProjectStateContext.html#method.add_line
+    /// [`MiscCfg`]: enum.ProjectPart.html#variant.MiscCfg
+    fn save_extension_config(&mut self, args: SaveExtensionConfigArgs) {
+        let _ = args;
+    }
+
+    /// Called on project load, undo/redo and "new project" before any (possible)
+    /// [`process_extension_line()`] calls.
+    ///
+    /// [`process_extension_line()`]: #method.process_extension_line
+    fn begin_load_project_state(&mut self, args: BeginLoadProjectStateArgs) {
+        let _ = args;
+    }
+}
+
+#[derive(Eq, PartialEq, Hash, Debug)]
+pub struct ProcessExtensionLineArgs<'a> {
+    pub line: &'a ReaperStr,
+    pub ctx: &'a mut BorrowedProjectStateContext,
+    /// Whether the state is loaded as part of undo/redo (as opposed to loading a project).
+    pub is_undo: bool,
+}
+
+#[derive(Eq, PartialEq, Hash, Debug)]
+pub struct SaveExtensionConfigArgs<'a> {
+    pub ctx: &'a mut BorrowedProjectStateContext,
+    /// Whether the state is saved as part of creating an undo point (as opposed to saving a
+    /// project).
+    pub is_undo: bool,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct BeginLoadProjectStateArgs {
+    /// Whether the state is loaded as part of undo/redo (as opposed to loading a project).
+    pub is_undo: bool,
+}
+
+extern "C" fn delegating_process_extension_line<T: ProjectConfigExtension>(
+    line: *const c_char,
+    ctx: *mut raw::ProjectStateContext,
+    is_undo: bool,
+    reg: *mut raw::project_config_extension_t,
+) -> bool {
+    firewall(|| {
+        let callback_struct: &mut T = decode_user_data(unsafe { (*reg).userData });
+        let line = unsafe { ReaperStr::from_ptr(line) };
+        let ctx = BorrowedProjectStateContext::ref_cast_mut(unsafe { &mut *ctx });
+        callback_struct.process_extension_line(ProcessExtensionLineArgs { line, ctx, is_undo })
+    })
+    .unwrap_or(false)
+}
+
+extern "C" fn delegating_save_extension_config<T: ProjectConfigExtension>(
+    ctx: *mut raw::ProjectStateContext,
+    is_undo: bool,
+    reg: *mut raw::project_config_extension_t,
+) {
+    firewall(|| {
+        let callback_struct: &mut T = decode_user_data(unsafe { (*reg).userData });
+        let ctx = BorrowedProjectStateContext::ref_cast_mut(unsafe { &mut *ctx });
+        callback_struct.save_extension_config(SaveExtensionConfigArgs { ctx, is_undo });
+    });
+}
+
+extern "C" fn delegating_begin_load_project_state<T: ProjectConfigExtension>(
+    is_undo: bool,
+    reg: *mut raw::project_config_extension_t,
+) {
+    firewall(|| {
+        let callback_struct: &mut T = decode_user_data(unsafe { (*reg).userData });
+        callback_struct.begin_load_project_state(BeginLoadProjectStateArgs { is_undo });
+    });
+}
+
+pub(crate) struct OwnedProjectConfigExtension {
+    inner: raw::project_config_extension_t,
+    callback: Box<dyn ProjectConfigExtension>,
+}
+
+impl Debug for OwnedProjectConfigExtension {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // ProjectConfigExtension doesn't generally implement Debug.
+        f.debug_struct("OwnedProjectConfigExtension")
+            .field("inner", &self.inner)
+            .field("callback", &"<omitted>")
+            .finish()
+    }
+}
+
+impl OwnedProjectConfigExtension {
+    pub fn new<T>(callback: Box<T>) -> Self
+    where
+        T: ProjectConfigExtension + 'static,
+    {
+        Self {
+            inner: raw::project_config_extension_t {
+                ProcessExtensionLine: Some(delegating_process_extension_line::<T>),
+                SaveExtensionConfig: Some(delegating_save_extension_config::<T>),
+                BeginLoadProjectState: Some(delegating_begin_load_project_state::<T>),
+                userData: encode_user_data(&callback),
+            },
+            callback,
+        }
+    }
+
+    pub fn into_callback(self) -> Box<dyn ProjectConfigExtension> {
+        self.callback
+    }
+}
+
+impl AsRef<raw::project_config_extension_t> for OwnedProjectConfigExtension {
+    fn as_ref(&self) -> &raw::project_config_extension_t {
+        &self.inner
+    }
+}
@@ -7,10 +7,11 @@ use reaper_low::{raw, register_plugin_destroy_hook};
 use crate::ProjectContext::CurrentProject;
 use crate::{
     require_non_null_panic, Accel, ActionValueChange, AddFxBehavior, AudioDeviceAttributeKey,
-    AutoSeekBehavior, AutomationMode, BookmarkId, BookmarkRef, Bpm, ChunkCacheHint, CommandId, Db,
-    DurationInSeconds, EditMode, EnvChunkName, FxAddByNameBehavior, FxChainVisibility, FxPresetRef,
-    FxShowInstruction, GangBehavior, GlobalAutomationModeOverride, HelpMode, Hidden, Hwnd,
-    InitialAction, InputMonitoringMode, KbdSectionInfo, MasterTrackBehavior, MeasureMode,
+    AutoSeekBehavior, AutomationMode, BookmarkId, BookmarkRef, Bpm, ChunkCacheHint, CommandId,
+    CursorContext, Db, DurationInSeconds, EditMode, EnvChunkName, EqBandRef, EqParamType,
+    EqParamValue, FxAddByNameBehavior, FxChainVisibility, FxPresetRef, FxShowInstruction,
+    GangBehavior, GlobalAutomationModeOverride, HelpMode, Hidden, Hwnd, InitialAction,
+    InputMonitoringMode, ItemAttributeKey, KbdSectionInfo, MasterTrackBehavior, MeasureMode,
     MediaItem, MediaItemTake, MediaTrack, MessageBoxResult, MessageBoxType, MidiImportBehavior,
     MidiInput, MidiInputDeviceId, MidiOutput, MidiOutputDeviceId, NativeColor, NormalizedPlayRate,
     NotificationBehavior, OwnedPcmSource, OwnedReaperPitchShift, OwnedReaperResample, PanMode,
@@ -20,11 +21,12 @@ use crate::{
     ReaperNormalizedFxParamValue, ReaperPanLikeValue, ReaperPanValue, ReaperPointer, ReaperStr,
     ReaperString, ReaperStringArg, ReaperVersion, ReaperVolumeValue, ReaperWidthValue,
     RecordArmMode, RecordingInput, RequiredViewMode, ResampleMode, SectionContext, SectionId,
-    SendTarget, SoloMode, StuffMidiMessageTarget, TakeAttributeKey, TimeModeOverride,
-    TimeRangeType, TrackArea, TrackAttributeKey, TrackDefaultsBehavior, TrackEnvelope,
-    TrackFxChainType, TrackFxLocation, TrackLocation, TrackSendAttributeKey, TrackSendCategory,
-    TrackSendDirection, TrackSendRef, TransferBehavior, UiRefreshBehavior, UndoBehavior, UndoScope,
-    ValueChange, VolumeSliderValue, WindowContext,
+    SendTarget, SoloMode, StuffMidiMessageTarget, TakeAttributeKey, ThemeColorTransformBehavior,
+    TimeModeOverride, TimeRangeType, TrackArea, TrackAttributeKey, TrackDefaultsBehavior,
+    TrackEnvelope, TrackFxChainType, TrackFxLocation, TrackGroupParam, TrackLocation,
+    TrackReorderBehavior, TrackSendAttributeKey, TrackSendCategory, TrackSendDirection,
+    TrackSendRef, TransferBehavior, UiRefreshBehavior, UndoBehavior, UndoScope, ValueChange,
+    VolumeSliderValue, WindowContext,
 };
 
 use helgoboss_midi::ShortMessage;
@@ -536,6 +538,74 @@ impl<UsageScope> Reaper<UsageScope> {
         Ok(())
     }
 
+    /// Gets a take attribute as numerical value.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid take.
+    pub unsafe fn get_media_item_take_info_value(
+        &self,
+        take: MediaItemTake,
+        attribute_key: TakeAttributeKey,
+    ) -> f64
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        self.low
+            .GetMediaItemTakeInfo_Value(take.as_ptr(), attribute_key.into_raw().as_ptr())
+    }
+
+    /// Gets an item attribute as numerical value.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid item.
+    pub unsafe fn get_media_item_info_value(
+        &self,
+        item: MediaItem,
+        attribute_key: ItemAttributeKey,
+    ) -> f64
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        self.low
+            .GetMediaItemInfo_Value(item.as_ptr(), attribute_key.into_raw().as_ptr())
+    }
+
+    /// Sets an item attribute as numerical value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an invalid (e.g. non-numerical) item attribute key is passed.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid item.
+    pub unsafe fn set_media_item_info_value(
+        &self,
+        item: MediaItem,
+        attribute_key: ItemAttributeKey,
+        new_value: f64,
+    ) -> ReaperFunctionResult<()>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let successful = self.low.SetMediaItemInfo_Value(
+            item.as_ptr(),
+            attribute_key.into_raw().as_ptr(),
+            new_value,
+        );
+        if !successful {
+            return Err(ReaperFunctionError::new(
+                "couldn't set item attribute (maybe attribute key is invalid)",
+            ));
+        }
+        Ok(())
+    }
+
     /// Convenience function which sets the take's source (`P_SOURCE`).
     ///
     /// Returns the previous source in case the take had a source assigned.
@@ -668,6 +738,172 @@ impl<UsageScope> Reaper<UsageScope> {
         self.get_set_media_track_info(track, TrackAttributeKey::Name, message.into().as_ptr() as _);
     }
 
+    /// Convenience function which grants temporary access to the given track's icon (`P_ICON`).
+    ///
+    /// The icon is either a full file name or relative to the track icons directory within the
+    /// resource path.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn get_set_media_track_info_get_icon<R>(
+        &self,
+        track: MediaTrack,
+        use_icon: impl FnOnce(&ReaperStr) -> R,
+    ) -> Option<R>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let ptr = self.get_set_media_track_info(track, TrackAttributeKey::Icon, null_mut());
+        create_passing_c_str(ptr as *const c_char).map(use_icon)
+    }
+
+    /// Convenience function which sets the track's icon (`P_ICON`).
+    ///
+    /// Pass an empty string in order to remove the icon.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn get_set_media_track_info_set_icon<'a>(
+        &self,
+        track: MediaTrack,
+        icon: impl Into<ReaperStringArg<'a>>,
+    ) where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        self.get_set_media_track_info(track, TrackAttributeKey::Icon, icon.into().as_ptr() as _);
+    }
+
+    /// Convenience function which grants temporary access to the name of the layout which the
+    /// given track uses in the given area (`P_TCP_LAYOUT` or `P_MCP_LAYOUT`).
+    ///
+    /// An empty name means that the default layout is used.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn get_set_media_track_info_get_layout<R>(
+        &self,
+        track: MediaTrack,
+        area: TrackArea,
+        use_layout: impl FnOnce(&ReaperStr) -> R,
+    ) -> Option<R>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let ptr = self.get_set_media_track_info(track, layout_attribute_key(area), null_mut());
+        create_passing_c_str(ptr as *const c_char).map(use_layout)
+    }
+
+    /// Convenience function which sets the layout which the given track uses in the given area
+    /// (`P_TCP_LAYOUT` or `P_MCP_LAYOUT`).
+    ///
+    /// Pass an empty string in order to use the default layout.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn get_set_media_track_info_set_layout<'a>(
+        &self,
+        track: MediaTrack,
+        area: TrackArea,
+        layout: impl Into<ReaperStringArg<'a>>,
+    ) where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        self.get_set_media_track_info(
+            track,
+            layout_attribute_key(area),
+            layout.into().as_ptr() as _,
+        );
+    }
+
+    /// Returns a string attribute of the given track, e.g. `P_RAZOREDITS`.
+    ///
+    /// With `buffer_size` you can tell REAPER how many bytes of the value you want. Buffer sizes
+    /// below 4096 bytes are raised to 4096 bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the attribute is not supported.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given buffer size is 0.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    ///
+    /// REAPER isn't told about the buffer size and writes the complete value. You must pass a
+    /// buffer size which is large enough for the value of the given attribute. Some values (e.g.
+    /// `P_RAZOREDITS`) have no upper bound, so choose generously.
+    pub unsafe fn get_set_media_track_info_string_get(
+        &self,
+        track: MediaTrack,
+        attribute_key: TrackAttributeKey,
+        buffer_size: u32,
+    ) -> ReaperFunctionResult<ReaperString>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        assert!(buffer_size > 0);
+        let buffer_size = buffer_size.max(MIN_NEED_BIG_BUFFER_SIZE);
+        let (value, successful) = with_string_buffer(buffer_size, |buffer, _| {
+            self.low.GetSetMediaTrackInfo_String(
+                track.as_ptr(),
+                attribute_key.into_raw().as_ptr(),
+                buffer,
+                false,
+            )
+        });
+        if !successful {
+            return Err(ReaperFunctionError::new(
+                "couldn't get track info string (probably unsupported attribute)",
+            ));
+        }
+        Ok(value)
+    }
+
+    /// Sets a string attribute of the given track, e.g. `P_RAZOREDITS`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the attribute is not supported or the value is invalid.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn get_set_media_track_info_string_set<'a>(
+        &self,
+        track: MediaTrack,
+        attribute_key: TrackAttributeKey,
+        value: impl Into<ReaperStringArg<'a>>,
+    ) -> ReaperFunctionResult<()>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let successful = self.low.GetSetMediaTrackInfo_String(
+            track.as_ptr(),
+            attribute_key.into_raw().as_ptr(),
+            value.into().as_ptr() as *mut _,
+            true,
+        );
+        if !successful {
+            return Err(ReaperFunctionError::new(
+                "couldn't set track info string (probably unsupported attribute or invalid value)",
+            ));
+        }
+        Ok(())
+    }
+
     /// Convenience function which returns the given track's input monitoring mode (`I_RECMON`).
     ///
     /// # Safety
@@ -2620,6 +2856,32 @@ impl<UsageScope> Reaper<UsageScope> {
         );
     }
 
+    /// Moves all selected tracks of the current project so they end up immediately above the
+    /// track at the given index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tracks couldn't be moved (e.g. because no track is selected).
+    pub fn reorder_selected_tracks(
+        &self,
+        before_track_index: u32,
+        behavior: TrackReorderBehavior,
+    ) -> ReaperFunctionResult<()>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let successful = self
+            .low
+            .ReorderSelectedTracks(before_track_index as i32, behavior.to_raw());
+        if !successful {
+            return Err(ReaperFunctionError::new(
+                "couldn't reorder tracks (probably no track selected)",
+            ));
+        }
+        Ok(())
+    }
+
     /// Resets all MIDI devices.
     pub fn midi_reinit(&self)
     where
@@ -2788,6 +3050,40 @@ impl<UsageScope> Reaper<UsageScope> {
         use_name(Some(name))
     }
 
+    /// Grants temporary access to the name and identifier of the installed FX at the given index.
+    ///
+    /// Start querying at index 0. Passes `None` when there are no more installed FX.
+    pub fn enum_installed_fx<R>(
+        &self,
+        index: u32,
+        use_result: impl FnOnce(Option<EnumInstalledFxResult>) -> R,
+    ) -> R
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let mut name = MaybeUninit::zeroed();
+        let mut ident = MaybeUninit::zeroed();
+        let exists = unsafe {
+            self.low
+                .EnumInstalledFX(index as i32, name.as_mut_ptr(), ident.as_mut_ptr())
+        };
+        if !exists {
+            return use_result(None);
+        }
+        let (name, ident) = unsafe {
+            (
+                create_passing_c_str(name.assume_init()),
+                create_passing_c_str(ident.assume_init()),
+            )
+        };
+        let result = match (name, ident) {
+            (Some(name), Some(ident)) => Some(EnumInstalledFxResult { name, ident }),
+            _ => None,
+        };
+        use_result(result)
+    }
+
     /// Returns a new resample instance.
     pub fn resampler_create(&self) -> OwnedReaperResample
     where
@@ -2888,6 +3184,51 @@ impl<UsageScope> Reaper<UsageScope> {
         }
     }
 
+    /// Returns an identifier of the given track FX parameter which doesn't depend on the parameter
+    /// index.
+    ///
+    /// With `buffer_size` you can tell REAPER how many bytes of the identifier you want.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given buffer size is 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the FX or parameter doesn't exist.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn track_fx_get_param_ident(
+        &self,
+        track: MediaTrack,
+        fx_location: TrackFxLocation,
+        param_index: u32,
+        buffer_size: u32,
+    ) -> ReaperFunctionResult<ReaperString>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        assert!(buffer_size > 0);
+        let (ident, successful) = with_string_buffer(buffer_size, |buffer, max_size| {
+            self.low.TrackFX_GetParamIdent(
+                track.as_ptr(),
+                fx_location.to_raw(),
+                param_index as i32,
+                buffer,
+                max_size,
+            )
+        });
+        if !successful {
+            return Err(ReaperFunctionError::new(
+                "couldn't get FX parameter ident (probably FX or parameter doesn't exist)",
+            ));
+        }
+        Ok(ident)
+    }
+
     /// Adds an instance of an FX to a track or monitoring FX chain.
     ///
     /// See [`track_fx_add_by_name_query()`] for possible FX name prefixes.
@@ -3091,21 +3432,193 @@ impl<UsageScope> Reaper<UsageScope> {
         Some(index as u32)
     }
 
-    /// Enables or disables a track FX.
+    /// Returns the index of the first ReaEQ instance in the normal FX chain of the given track.
+    ///
+    /// If `instantiate` is `true` and there's no ReaEQ yet, it will be added.
     ///
     /// # Safety
     ///
     /// REAPER can crash if you pass an invalid track.
-    pub unsafe fn track_fx_set_enabled(
-        &self,
-        track: MediaTrack,
-        fx_location: TrackFxLocation,
-        enabled: bool,
-    ) where
+    pub unsafe fn track_fx_get_eq(&self, track: MediaTrack, instantiate: bool) -> Option<u32>
+    where
         UsageScope: MainThreadOnly,
     {
         self.require_main_thread();
-        self.low
+        let index = self.low.TrackFX_GetEQ(track.as_ptr(), instantiate);
+        if index < 0 {
+            return None;
+        }
+        Some(index as u32)
+    }
+
+    /// Returns which ReaEQ band and band parameter the given FX parameter corresponds to, together
+    /// with its current normalized value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the FX is not ReaEQ or the parameter doesn't exist.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn track_fx_get_eq_param(
+        &self,
+        track: MediaTrack,
+        fx_location: TrackFxLocation,
+        param_index: u32,
+    ) -> ReaperFunctionResult<TrackFxGetEqParamResult>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let mut band_type = MaybeUninit::zeroed();
+        let mut band_index = MaybeUninit::zeroed();
+        let mut param_type = MaybeUninit::zeroed();
+        let mut normalized_value = MaybeUninit::zeroed();
+        let successful = self.low.TrackFX_GetEQParam(
+            track.as_ptr(),
+            fx_location.to_raw(),
+            param_index as i32,
+            band_type.as_mut_ptr(),
+            band_index.as_mut_ptr(),
+            param_type.as_mut_ptr(),
+            normalized_value.as_mut_ptr(),
+        );
+        if !successful {
+            return Err(ReaperFunctionError::new(
+                "couldn't get EQ parameter (probably FX is not ReaEQ or parameter doesn't exist)",
+            ));
+        }
+        let result = TrackFxGetEqParamResult {
+            band: EqBandRef::from_raw(band_type.assume_init(), band_index.assume_init()),
+            param_type: EqParamType::from_raw(param_type.assume_init()),
+            normalized_value: ReaperNormalizedFxParamValue::new(normalized_value.assume_init()),
+        };
+        Ok(result)
+    }
+
+    /// Sets a parameter of the given ReaEQ band.
+    ///
+    /// The parameter type is ignored for the master gain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the FX is not ReaEQ or the band doesn't exist.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn track_fx_set_eq_param(
+        &self,
+        track: MediaTrack,
+        fx_location: TrackFxLocation,
+        band: EqBandRef,
+        param_type: EqParamType,
+        value: EqParamValue,
+    ) -> ReaperFunctionResult<()>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let (band_type, band_index) = band.to_raw();
+        let (value, is_normalized) = match value {
+            EqParamValue::Normalized(v) => (v.get(), true),
+            EqParamValue::Native(v) => (v, false),
+        };
+        let successful = self.low.TrackFX_SetEQParam(
+            track.as_ptr(),
+            fx_location.to_raw(),
+            band_type,
+            band_index,
+            param_type.to_raw(),
+            value,
+            is_normalized,
+        );
+        if !successful {
+            return Err(ReaperFunctionError::new(
+                "couldn't set EQ parameter (probably FX is not ReaEQ or band doesn't exist)",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns whether the given ReaEQ band is enabled.
+    ///
+    /// Also returns `false` if the FX is not ReaEQ.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn track_fx_get_eq_band_enabled(
+        &self,
+        track: MediaTrack,
+        fx_location: TrackFxLocation,
+        band: EqBandRef,
+    ) -> bool
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let (band_type, band_index) = band.to_raw();
+        self.low.TrackFX_GetEQBandEnabled(
+            track.as_ptr(),
+            fx_location.to_raw(),
+            band_type,
+            band_index,
+        )
+    }
+
+    /// Enables or disables the given ReaEQ band.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the FX is not ReaEQ or the band doesn't exist.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn track_fx_set_eq_band_enabled(
+        &self,
+        track: MediaTrack,
+        fx_location: TrackFxLocation,
+        band: EqBandRef,
+        enabled: bool,
+    ) -> ReaperFunctionResult<()>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let (band_type, band_index) = band.to_raw();
+        let successful = self.low.TrackFX_SetEQBandEnabled(
+            track.as_ptr(),
+            fx_location.to_raw(),
+            band_type,
+            band_index,
+            enabled,
+        );
+        if !successful {
+            return Err(ReaperFunctionError::new(
+                "couldn't enable/disable EQ band (probably FX is not ReaEQ or band doesn't exist)",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Enables or disables a track FX.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn track_fx_set_enabled(
+        &self,
+        track: MediaTrack,
+        fx_location: TrackFxLocation,
+        enabled: bool,
+    ) where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        self.low
             .TrackFX_SetEnabled(track.as_ptr(), fx_location.to_raw(), enabled);
     }
 
@@ -3903,6 +4416,21 @@ impl<UsageScope> Reaper<UsageScope> {
         create_passing_c_str(ptr).map(use_description)
     }
 
+    /// Returns a number which changes whenever the state of the given project changes, e.g. when
+    /// an undo point is created or an action is undone.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given project is not valid anymore.
+    pub fn get_project_state_change_count(&self, project: ProjectContext) -> u32
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        self.require_valid_project(project);
+        unsafe { self.low.GetProjectStateChangeCount(project.to_raw()) as u32 }
+    }
+
     /// Makes the last undoable operation undone.
     ///
     /// Returns `false` if there was nothing to be undone.
@@ -4080,7 +4608,182 @@ impl<UsageScope> Reaper<UsageScope> {
     {
         self.require_main_thread();
         let value = self.low.GetTrackColor(track.as_ptr());
-        NativeColorResult::from_track_color_value(value)
+        NativeColorResult::from_raw(value)
+    }
+
+    /// Sets the custom color of the given track.
+    ///
+    /// In order to remove the custom color, set [`TrackAttributeKey::CustomColor`] to 0 via
+    /// [`set_media_track_info_value()`].
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    ///
+    /// [`TrackAttributeKey::CustomColor`]: enum.TrackAttributeKey.html#variant.CustomColor
+    /// [`set_media_track_info_value()`]: #method.set_media_track_info_value
+    pub unsafe fn set_track_color(&self, track: MediaTrack, color: NativeColor)
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        self.low.SetTrackColor(track.as_ptr(), color.to_raw());
+    }
+
+    /// Gets or modifies the membership of the given track in groups 1 to 32 for the given
+    /// parameter.
+    ///
+    /// Each bit represents one group (bit 0 = group 1). The bits set in `set_mask` are changed to
+    /// the corresponding bits in `set_value`. Pass 0 as mask in order to just query the
+    /// membership. Returns the membership as it was before this call.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn get_set_track_group_membership(
+        &self,
+        track: MediaTrack,
+        group_param: TrackGroupParam,
+        set_mask: u32,
+        set_value: u32,
+    ) -> u32
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        self.low.GetSetTrackGroupMembership(
+            track.as_ptr(),
+            group_param.into_raw().as_ptr(),
+            set_mask,
+            set_value,
+        )
+    }
+
+    /// Like [`get_set_track_group_membership()`] but for groups 33 to 64 (bit 0 = group 33).
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    ///
+    /// [`get_set_track_group_membership()`]: #method.get_set_track_group_membership
+    pub unsafe fn get_set_track_group_membership_high(
+        &self,
+        track: MediaTrack,
+        group_param: TrackGroupParam,
+        set_mask: u32,
+        set_value: u32,
+    ) -> u32
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        self.low.GetSetTrackGroupMembershipHigh(
+            track.as_ptr(),
+            group_param.into_raw().as_ptr(),
+            set_mask,
+            set_value,
+        )
+    }
+
+    /// Returns the custom color of the given item or take which is actually used to display the
+    /// item (according to the user preferences).
+    ///
+    /// Returns `None` if the item is displayed without custom color.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid item or take.
+    pub unsafe fn get_displayed_media_item_color_2(
+        &self,
+        item: MediaItem,
+        take: Option<MediaItemTake>,
+    ) -> Option<NativeColor>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let value = self.low.GetDisplayedMediaItemColor2(
+            item.as_ptr(),
+            take.map(|t| t.as_ptr()).unwrap_or(null_mut()),
+        );
+        let result = NativeColorResult::from_raw(value)?;
+        if !result.is_used {
+            return None;
+        }
+        Some(result.color)
+    }
+
+    /// Returns the given theme color.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the theme color doesn't exist.
+    pub fn get_theme_color<'a>(
+        &self,
+        ini_key: impl Into<ReaperStringArg<'a>>,
+        transform_behavior: ThemeColorTransformBehavior,
+    ) -> ReaperFunctionResult<NativeColor>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let value = unsafe {
+            self.low.GetThemeColor(
+                ini_key.into().as_ptr(),
+                theme_color_flags(transform_behavior),
+            )
+        };
+        if value < 0 {
+            return Err(ReaperFunctionError::new(
+                "couldn't get theme color (probably invalid key)",
+            ));
+        }
+        Ok(NativeColor::new(value as _))
+    }
+
+    /// Temporarily overrides the given theme color.
+    ///
+    /// Passing `None` restores the color defined by the theme. The UI is not updated by this.
+    /// Returns the resulting color.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the theme color doesn't exist.
+    pub fn set_theme_color<'a>(
+        &self,
+        ini_key: impl Into<ReaperStringArg<'a>>,
+        color: Option<NativeColor>,
+        transform_behavior: ThemeColorTransformBehavior,
+    ) -> ReaperFunctionResult<NativeColor>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let value = unsafe {
+            self.low.SetThemeColor(
+                ini_key.into().as_ptr(),
+                color.map(|c| c.to_raw()).unwrap_or(-1),
+                theme_color_flags(transform_behavior),
+            )
+        };
+        if value < 0 {
+            return Err(ReaperFunctionError::new(
+                "couldn't set theme color (probably invalid key)",
+            ));
+        }
+        Ok(NativeColor::new(value as _))
+    }
+
+    /// Converts the given RGB color into an OS-dependent color.
+    pub fn color_to_native(&self, color: RgbColor) -> NativeColor
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let value = self
+            .low
+            .ColorToNative(color.r as _, color.g as _, color.b as _);
+        NativeColor::new(value as _)
     }
 
     /// Extracts an RGB color from the given OS-dependent color.
@@ -4233,6 +4936,23 @@ impl<UsageScope> Reaper<UsageScope> {
         ReaperVolumeValue::new(result)
     }
 
+    /// Returns the current peak hold value for the given track channel and optionally clears it.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn track_get_peak_hold_db(&self, track: MediaTrack, channel: u32, clear: bool) -> Db
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        // REAPER returns hundredths of dB (-0.01 = -1 dB)
+        let result = self
+            .low
+            .Track_GetPeakHoldDB(track.as_ptr(), channel as _, clear);
+        Db::try_from(result * 100.0).unwrap_or(Db::MINUS_INF)
+    }
+
     /// Gets a track attribute as numerical value.
     ///
     /// # Safety
@@ -4288,6 +5008,19 @@ impl<UsageScope> Reaper<UsageScope> {
         self.low.CountTrackMediaItems(track.as_ptr()) as u32
     }
 
+    /// Counts the number of envelopes of the given track.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn count_track_envelopes(&self, track: MediaTrack) -> u32
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        self.low.CountTrackEnvelopes(track.as_ptr()) as u32
+    }
+
     /// Counts the number of FX parameter knobs displayed on the track control panel.
     ///
     /// # Safety
@@ -4350,6 +5083,61 @@ impl<UsageScope> Reaper<UsageScope> {
         NonNull::new(ptr)
     }
 
+    /// Returns the envelope of the given track at the given index.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn get_track_envelope(
+        &self,
+        track: MediaTrack,
+        envelope_index: u32,
+    ) -> Option<TrackEnvelope>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let ptr = self
+            .low
+            .GetTrackEnvelope(track.as_ptr(), envelope_index as _);
+        NonNull::new(ptr)
+    }
+
+    /// Returns the name of the given envelope, e.g. "Volume".
+    ///
+    /// With `buffer_size` you can tell REAPER how many bytes of the name you want.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given buffer size is 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not successful.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid envelope.
+    pub unsafe fn get_envelope_name(
+        &self,
+        envelope: TrackEnvelope,
+        buffer_size: u32,
+    ) -> ReaperFunctionResult<ReaperString>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        assert!(buffer_size > 0);
+        let (name, successful) = with_string_buffer(buffer_size, |buffer, max_size| {
+            self.low
+                .GetEnvelopeName(envelope.as_ptr(), buffer, max_size)
+        });
+        if !successful {
+            return Err(ReaperFunctionError::new("couldn't get envelope name"));
+        }
+        Ok(name)
+    }
+
     /// Gets the number of FX instances on the given track's normal FX chain.
     ///
     /// # Safety
@@ -4446,85 +5234,199 @@ impl<UsageScope> Reaper<UsageScope> {
     {
         self.require_main_thread();
         self.require_valid_project(project);
-        unsafe { self.get_master_track_unchecked(project) }
+        unsafe { self.get_master_track_unchecked(project) }
+    }
+
+    /// Like [`get_master_track()`] but doesn't check if project is valid.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid project.
+    ///
+    /// [`get_master_track()`]: #method.get_master_track
+    pub unsafe fn get_master_track_unchecked(&self, project: ProjectContext) -> MediaTrack
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let ptr = self.low.GetMasterTrack(project.to_raw());
+        require_non_null_panic(ptr)
+    }
+
+    /// Converts the given GUID to a string (including braces).
+    pub fn guid_to_string(&self, guid: &GUID) -> ReaperString
+    where
+        UsageScope: AnyThread,
+    {
+        let (guid_string, _) = with_string_buffer(64, |buffer, _| unsafe {
+            self.low.guidToString(guid as *const GUID, buffer)
+        });
+        guid_string
+    }
+
+    /// Converts the given accelerator key to a human-readable name.
+    pub fn kbd_format_key_name(&self, accel: Accel) -> ReaperString
+    where
+        UsageScope: AnyThread,
+    {
+        let (key_string, _) = with_string_buffer(64, |buffer, _| unsafe {
+            let mut accel = accel.to_raw();
+            self.low.kbd_formatKeyName(&mut accel as *mut _, buffer)
+        });
+        key_string
+    }
+
+    /// Returns the project recording path.
+    ///
+    /// With `buffer_size` you can tell REAPER how many bytes of the resulting path you want.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given buffer size is 0.
+    pub fn get_project_path_ex(&self, project: ProjectContext, buffer_size: u32) -> PathBuf
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_valid_project(project);
+        unsafe { self.get_project_path_ex_unchecked(project, buffer_size) }
+    }
+
+    /// Like [`get_project_path_ex()`] but doesn't check if project is valid.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid project.
+    ///
+    /// [`get_project_path_ex()`]: #method.get_project_path_ex
+    pub unsafe fn get_project_path_ex_unchecked(
+        &self,
+        project: ProjectContext,
+        buffer_size: u32,
+    ) -> PathBuf
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let (reaper_string, _) = with_string_buffer(buffer_size, |buffer, max_size| {
+            self.low
+                .GetProjectPathEx(project.to_raw(), buffer, max_size)
+        });
+        let owned_string = reaper_string.into_string();
+        PathBuf::from(owned_string)
+    }
+
+    /// Returns a string attribute of the given project, e.g. `TRACK_GROUP_NAME:1`.
+    ///
+    /// With `buffer_size` you can tell REAPER how many bytes of the value you want. REAPER isn't
+    /// told about the buffer size, so buffer sizes below 4096 bytes are raised to 4096 bytes, which
+    /// is enough for all string attributes REAPER provides for projects.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the attribute is not supported.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given project is not valid anymore or if the given buffer size is 0.
+    pub fn get_set_project_info_string_get<'a>(
+        &self,
+        project: ProjectContext,
+        attribute: impl Into<ReaperStringArg<'a>>,
+        buffer_size: u32,
+    ) -> ReaperFunctionResult<ReaperString>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_valid_project(project);
+        unsafe { self.get_set_project_info_string_get_unchecked(project, attribute, buffer_size) }
     }
 
-    /// Like [`get_master_track()`] but doesn't check if project is valid.
+    /// Like [`get_set_project_info_string_get()`] but doesn't check if project is valid.
     ///
     /// # Safety
     ///
     /// REAPER can crash if you pass an invalid project.
     ///
-    /// [`get_master_track()`]: #method.get_master_track
-    pub unsafe fn get_master_track_unchecked(&self, project: ProjectContext) -> MediaTrack
+    /// [`get_set_project_info_string_get()`]: #method.get_set_project_info_string_get
+    pub unsafe fn get_set_project_info_string_get_unchecked<'a>(
+        &self,
+        project: ProjectContext,
+        attribute: impl Into<ReaperStringArg<'a>>,
+        buffer_size: u32,
+    ) -> ReaperFunctionResult<ReaperString>
     where
         UsageScope: MainThreadOnly,
     {
         self.require_main_thread();
-        let ptr = self.low.GetMasterTrack(project.to_raw());
-        require_non_null_panic(ptr)
-    }
-
-    /// Converts the given GUID to a string (including braces).
-    pub fn guid_to_string(&self, guid: &GUID) -> ReaperString
-    where
-        UsageScope: AnyThread,
-    {
-        let (guid_string, _) = with_string_buffer(64, |buffer, _| unsafe {
-            self.low.guidToString(guid as *const GUID, buffer)
-        });
-        guid_string
-    }
-
-    /// Converts the given accelerator key to a human-readable name.
-    pub fn kbd_format_key_name(&self, accel: Accel) -> ReaperString
-    where
-        UsageScope: AnyThread,
-    {
-        let (key_string, _) = with_string_buffer(64, |buffer, _| unsafe {
-            let mut accel = accel.to_raw();
-            self.low.kbd_formatKeyName(&mut accel as *mut _, buffer)
+        assert!(buffer_size > 0);
+        let buffer_size = buffer_size.max(MIN_NEED_BIG_BUFFER_SIZE);
+        let (value, successful) = with_string_buffer(buffer_size, |buffer, _| {
+            self.low.GetSetProjectInfo_String(
+                project.to_raw(),
+                attribute.into().as_ptr(),
+                buffer,
+                false,
+            )
         });
-        key_string
+        if !successful {
+            return Err(ReaperFunctionError::new(
+                "couldn't get project info string (probably unsupported attribute)",
+            ));
+        }
+        Ok(value)
     }
 
-    /// Returns the project recording path.
+    /// Sets a string attribute of the given project, e.g. `TRACK_GROUP_NAME:1`.
     ///
-    /// With `buffer_size` you can tell REAPER how many bytes of the resulting path you want.
+    /// # Errors
+    ///
+    /// Returns an error if the attribute is not supported.
     ///
     /// # Panics
     ///
-    /// Panics if the given buffer size is 0.
-    pub fn get_project_path_ex(&self, project: ProjectContext, buffer_size: u32) -> PathBuf
+    /// Panics if the given project is not valid anymore.
+    pub fn get_set_project_info_string_set<'a>(
+        &self,
+        project: ProjectContext,
+        attribute: impl Into<ReaperStringArg<'a>>,
+        value: impl Into<ReaperStringArg<'a>>,
+    ) -> ReaperFunctionResult<()>
     where
         UsageScope: MainThreadOnly,
     {
         self.require_valid_project(project);
-        unsafe { self.get_project_path_ex_unchecked(project, buffer_size) }
+        unsafe { self.get_set_project_info_string_set_unchecked(project, attribute, value) }
     }
 
-    /// Like [`get_project_path_ex()`] but doesn't check if project is valid.
+    /// Like [`get_set_project_info_string_set()`] but doesn't check if project is valid.
     ///
     /// # Safety
     ///
     /// REAPER can crash if you pass an invalid project.
     ///
-    /// [`get_project_path_ex()`]: #method.get_project_path_ex
-    pub unsafe fn get_project_path_ex_unchecked(
+    /// [`get_set_project_info_string_set()`]: #method.get_set_project_info_string_set
+    pub unsafe fn get_set_project_info_string_set_unchecked<'a>(
         &self,
         project: ProjectContext,
-        buffer_size: u32,
-    ) -> PathBuf
+        attribute: impl Into<ReaperStringArg<'a>>,
+        value: impl Into<ReaperStringArg<'a>>,
+    ) -> ReaperFunctionResult<()>
     where
         UsageScope: MainThreadOnly,
     {
         self.require_main_thread();
-        let (reaper_string, _) = with_string_buffer(buffer_size, |buffer, max_size| {
-            self.low
-                .GetProjectPathEx(project.to_raw(), buffer, max_size)
-        });
-        let owned_string = reaper_string.into_string();
-        PathBuf::from(owned_string)
+        let successful = self.low.GetSetProjectInfo_String(
+            project.to_raw(),
+            attribute.into().as_ptr(),
+            value.into().as_ptr() as *mut _,
+            true,
+        );
+        if !successful {
+            return Err(ReaperFunctionError::new(
+                "couldn't set project info string (probably unsupported attribute)",
+            ));
+        }
+        Ok(())
     }
 
     /// Returns the master tempo of the current project.
@@ -5340,6 +6242,98 @@ impl<UsageScope> Reaper<UsageScope> {
         NonNull::new(ptr)
     }
 
+    /// Counts the number of selected items in the given project.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given project is not valid anymore.
+    pub fn count_selected_media_items(&self, project: ProjectContext) -> u32
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_valid_project(project);
+        unsafe { self.count_selected_media_items_unchecked(project) }
+    }
+
+    /// Like [`count_selected_media_items()`] but doesn't check if project is valid.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid project.
+    ///
+    /// [`count_selected_media_items()`]: #method.count_selected_media_items
+    pub unsafe fn count_selected_media_items_unchecked(&self, project: ProjectContext) -> u32
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        self.low.CountSelectedMediaItems(project.to_raw()) as u32
+    }
+
+    /// Returns the selected envelope of the given project.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given project is not valid anymore.
+    pub fn get_selected_envelope(&self, project: ProjectContext) -> Option<TrackEnvelope>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_valid_project(project);
+        unsafe { self.get_selected_envelope_unchecked(project) }
+    }
+
+    /// Like [`get_selected_envelope()`] but doesn't check if project is valid.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid project.
+    ///
+    /// [`get_selected_envelope()`]: #method.get_selected_envelope
+    pub unsafe fn get_selected_envelope_unchecked(
+        &self,
+        project: ProjectContext,
+    ) -> Option<TrackEnvelope>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let ptr = self.low.GetSelectedEnvelope(project.to_raw());
+        NonNull::new(ptr)
+    }
+
+    /// Returns the focused part of the arrange view.
+    ///
+    /// If `want_last_valid` is `true`, returns the last valid context if the current one is
+    /// unknown. Returns `None` if the context is unknown.
+    pub fn get_cursor_context_2(&self, want_last_valid: bool) -> Option<CursorContext>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        CursorContext::from_raw(self.low.GetCursorContext2(want_last_valid))
+    }
+
+    /// Changes the focused part of the arrange view.
+    ///
+    /// If the context is [`CursorContext::Envelopes`], the given envelope gets selected.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid envelope.
+    ///
+    /// [`CursorContext::Envelopes`]: enum.CursorContext.html#variant.Envelopes
+    pub unsafe fn set_cursor_context(&self, context: CursorContext, envelope: Option<TrackEnvelope>)
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        self.low.SetCursorContext(
+            context.to_raw(),
+            envelope.map(|e| e.as_ptr()).unwrap_or(null_mut()),
+        );
+    }
+
     /// Returns the media source of the given media item take.
     ///
     /// # Safety
@@ -5385,6 +6379,20 @@ impl<UsageScope> Reaper<UsageScope> {
         NonNull::new(ptr)
     }
 
+    /// Returns the track on which the given item is located.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid item.
+    pub unsafe fn get_media_item_track(&self, item: MediaItem) -> Option<MediaTrack>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let ptr = self.low.GetMediaItem_Track(item.as_ptr());
+        NonNull::new(ptr)
+    }
+
     /// Returns the take that is currently being edited in the given MIDI editor.
     ///
     /// # Safety
@@ -5584,6 +6592,46 @@ impl<UsageScope> Reaper<UsageScope> {
         Ok(chunk_content)
     }
 
+    /// Returns the RPPXML state of the given envelope.
+    ///
+    /// With `buffer_size` you can tell REAPER how many bytes of the chunk you want.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given buffer size is 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not successful (unclear when this happens).
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid envelope.
+    pub unsafe fn get_envelope_state_chunk(
+        &self,
+        envelope: TrackEnvelope,
+        buffer_size: u32,
+        cache_hint: ChunkCacheHint,
+    ) -> ReaperFunctionResult<ReaperString>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        assert!(buffer_size > 0);
+        let (chunk_content, successful) = with_string_buffer(buffer_size, |buffer, max_size| {
+            self.low.GetEnvelopeStateChunk(
+                envelope.as_ptr(),
+                buffer,
+                max_size,
+                cache_hint == ChunkCacheHint::UndoMode,
+            )
+        });
+        if !successful {
+            return Err(ReaperFunctionError::new("couldn't get envelope chunk"));
+        }
+        Ok(chunk_content)
+    }
+
     /// Prompts the user for string values.
     ///
     /// If a caption begins with `*`, for example `*password`, the edit field will not display the
@@ -6348,6 +7396,45 @@ impl<UsageScope> Reaper<UsageScope> {
         Ok(())
     }
 
+    /// Returns the path of the file in which REAPER stores the user presets of the given track FX.
+    ///
+    /// The file doesn't necessarily exist. Returns `None` if REAPER returns an empty path (probably
+    /// FX doesn't exist).
+    ///
+    /// With `buffer_size` you can tell REAPER how many bytes of the resulting path you want.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given buffer size is 0.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn track_fx_get_user_preset_filename(
+        &self,
+        track: MediaTrack,
+        fx_location: TrackFxLocation,
+        buffer_size: u32,
+    ) -> Option<PathBuf>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        assert!(buffer_size > 0);
+        let (reaper_string, _) = with_string_buffer(buffer_size, |buffer, max_size| {
+            self.low.TrackFX_GetUserPresetFilename(
+                track.as_ptr(),
+                fx_location.to_raw(),
+                buffer,
+                max_size,
+            )
+        });
+        if reaper_string.is_empty() {
+            return None;
+        }
+        Some(PathBuf::from(reaper_string.into_string()))
+    }
+
     /// Returns the index of the currently selected FX preset as well as the total preset count.
     ///
     /// # Safety
@@ -6387,6 +7474,38 @@ impl<UsageScope> Reaper<UsageScope> {
         }
     }
 
+    /// Selects a preset of the given track FX by its name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error e.g. if the FX or preset doesn't exist.
+    ///
+    /// # Safety
+    ///
+    /// REAPER can crash if you pass an invalid track.
+    pub unsafe fn track_fx_set_preset<'a>(
+        &self,
+        track: MediaTrack,
+        fx_location: TrackFxLocation,
+        preset_name: impl Into<ReaperStringArg<'a>>,
+    ) -> ReaperFunctionResult<()>
+    where
+        UsageScope: MainThreadOnly,
+    {
+        self.require_main_thread();
+        let successful = self.low.TrackFX_SetPreset(
+            track.as_ptr(),
+            fx_location.to_raw(),
+            preset_name.into().as_ptr(),
+        );
+        if !successful {
+            return Err(ReaperFunctionError::new(
+                "couldn't select FX preset (maybe FX or preset doesn't exist)",
+            ));
+        }
+        Ok(())
+    }
+
     /// Selects a preset of the given track FX.
     ///
     /// # Errors
@@ -6807,6 +7926,14 @@ pub enum EnumPitchShiftModesResult<'a> {
     },
 }
 
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct EnumInstalledFxResult<'a> {
+    /// Display name of the FX, e.g. "VST: ReaEQ (Cockos)".
+    pub name: &'a ReaperStr,
+    /// Identifier of the FX, e.g. "reaeq.dll" or "utility/volume".
+    pub ident: &'a ReaperStr,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct GetInputOutputLatencyResult {
     pub input_latency: u32,
@@ -6824,6 +7951,16 @@ pub struct TrackFxGetPresetResult {
     pub name: Option<ReaperString>,
 }
 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TrackFxGetEqParamResult {
+    /// The band to which the parameter belongs.
+    pub band: EqBandRef,
+    /// The type of the parameter (ignore it for the master gain).
+    pub param_type: EqParamType,
+    /// Current value of the parameter.
+    pub normalized_value: ReaperNormalizedFxParamValue,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct TrackFxGetPresetIndexResult {
     /// Preset index or `None` if no preset or factory preset is selected or the FX doesn't exist.
@@ -7040,11 +8177,14 @@ pub struct NativeColorResult {
 }
 
 impl NativeColorResult {
-    fn from_track_color_value(value: i32) -> Option<Self> {
+    /// Creates a result from a custom color value as used by `I_CUSTOMCOLOR`.
+    ///
+    /// Returns `None` if the value is 0, which means that no custom color is set.
+    pub fn from_raw(value: i32) -> Option<Self> {
         if value == 0 {
             return None;
         }
-        let used_offset = 0x1000000;
+        let used_offset = CUSTOM_COLOR_USED_FLAG;
         let res = if value < used_offset {
             Self {
                 color: NativeColor::new(value as _),
@@ -7058,6 +8198,36 @@ impl NativeColorResult {
         };
         Some(res)
     }
+
+    /// Converts this value to a custom color value as expected by `I_CUSTOMCOLOR`.
+    pub fn to_raw(self) -> i32 {
+        if self.is_used {
+            self.color.to_raw() | CUSTOM_COLOR_USED_FLAG
+        } else {
+            self.color.to_raw()
+        }
+    }
+}
+
+const CUSTOM_COLOR_USED_FLAG: i32 = 0x1000000;
+
+/// Minimum buffer size for REAPER functions which write into a buffer without being told its size
+/// (parameters named `...NeedBig`).
+const MIN_NEED_BIG_BUFFER_SIZE: u32 = 4096;
+
+fn theme_color_flags(transform_behavior: ThemeColorTransformBehavior) -> i32 {
+    use ThemeColorTransformBehavior::*;
+    match transform_behavior {
+        ApplyTransforms => 0,
+        IgnoreTransforms => 1,
+    }
+}
+
+fn layout_attribute_key(area: TrackArea) -> TrackAttributeKey<'static> {
+    match area {
+        TrackArea::Tcp => TrackAttributeKey::TcpLayout,
+        TrackArea::Mcp => TrackAttributeKey::McpLayout,
+    }
 }
 
 fn make_some_if_greater_than_zero(value: f64) -> Option<f64> {
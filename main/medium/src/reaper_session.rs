@@ -13,10 +13,10 @@ use crate::{
     AcceleratorPosition, BufferingBehavior, CommandId, ControlSurface, ControlSurfaceAdapter,
     HookCommand, HookCommand2, HookPostCommand, HookPostCommand2, MainThreadScope,
     MeasureAlignment, OnAudioBuffer, OwnedAcceleratorRegister, OwnedAudioHookRegister,
-    OwnedGaccelRegister, OwnedPreviewRegister, PluginRegistration, ProjectContext,
-    RealTimeAudioThreadScope, Reaper, ReaperFunctionError, ReaperFunctionResult, ReaperMutex,
-    ReaperString, ReaperStringArg, RegistrationHandle, RegistrationObject, ToggleAction,
-    TranslateAccel,
+    OwnedGaccelRegister, OwnedPreviewRegister, OwnedProjectConfigExtension, PluginRegistration,
+    ProjectConfigExtension, ProjectContext, RealTimeAudioThreadScope, Reaper, ReaperFunctionError,
+    ReaperFunctionResult, ReaperMutex, ReaperString, ReaperStringArg, RegistrationHandle,
+    RegistrationObject, ToggleAction, TranslateAccel,
 };
 use reaper_low::raw::audio_hook_register_t;
 
@@ -67,6 +67,8 @@ pub struct ReaperSession {
     gaccel_registers: Keeper<OwnedGaccelRegister, raw::gaccel_register_t>,
     /// Provides a safe place in memory for accelerator registers.
     accelerator_registers: Keeper<OwnedAcceleratorRegister, raw::accelerator_register_t>,
+    /// Provides a safe place in memory for project config extensions.
+    project_config_extensions: Keeper<OwnedProjectConfigExtension, raw::project_config_extension_t>,
     /// Provides a safe place in memory for currently playing preview registers.
     preview_registers: SharedKeeper<ReaperMutex<OwnedPreviewRegister>, raw::preview_register_t>,
     /// Provides a safe place in memory for command names used in command ID registrations.
@@ -112,6 +114,7 @@ impl ReaperSession {
             reaper: Reaper::new(low),
             gaccel_registers: Default::default(),
             accelerator_registers: Default::default(),
+            project_config_extensions: Default::default(),
             preview_registers: Default::default(),
             command_names: Default::default(),
             api_defs: Default::default(),
@@ -564,6 +567,41 @@ impl ReaperSession {
         Ok(handle)
     }
 
+    /// Registers a project config extension.
+    ///
+    /// This lets you get notified whenever REAPER loads or saves project state (also as part of
+    /// undo/redo) and lets you read and write custom lines in the project state.
+    ///
+    /// This function returns a handle which you can use to unregister the extension at any time
+    /// via [`plugin_register_remove_project_config()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the registration failed.
+    ///
+    /// [`plugin_register_remove_project_config()`]: #method.plugin_register_remove_project_config
+    pub fn plugin_register_add_project_config<T>(
+        &mut self,
+        callback: Box<T>,
+    ) -> ReaperFunctionResult<RegistrationHandle<T>>
+    where
+        T: ProjectConfigExtension + 'static,
+    {
+        // Create thin pointer of callback before making it a trait object (for being able to
+        // restore the original callback later).
+        let callback_thin_ptr: NonNull<T> = callback.as_ref().into();
+        // Create project config extension and make it own the callback (as user data)
+        let extension = OwnedProjectConfigExtension::new(callback);
+        // Store it in memory. Although we keep it here, conceptually it's owned by REAPER, so we
+        // should not access it while being registered.
+        let reaper_ptr = self.project_config_extensions.keep(extension);
+        // Register the low-level extension at REAPER
+        unsafe { self.plugin_register_add(RegistrationObject::ProjectConfig(reaper_ptr))? };
+        // Returns a handle which the consumer can use to unregister
+        let handle = RegistrationHandle::new(callback_thin_ptr, reaper_ptr.cast());
+        Ok(handle)
+    }
+
     /// Plays a preview register.
     ///
     /// # Errors
@@ -829,6 +867,31 @@ impl ReaperSession {
         Some(callback)
     }
 
+    /// Unregisters a project config extension and hands ownership back to you.
+    ///
+    /// Returns `None` if the extension was not registered (anymore).
+    pub fn plugin_register_remove_project_config<T>(
+        &mut self,
+        handle: RegistrationHandle<T>,
+    ) -> Option<Box<T>>
+    where
+        T: ProjectConfigExtension,
+    {
+        // Unregister the low-level extension from REAPER
+        let reaper_ptr = handle.reaper_ptr().cast();
+        unsafe { self.plugin_register_remove(RegistrationObject::ProjectConfig(reaper_ptr)) };
+        // Take the owned extension out of its storage
+        let owned_extension = self
+            .project_config_extensions
+            .release(handle.reaper_ptr().cast())?;
+        // Reconstruct the initial value for handing ownership back to the consumer. See
+        // plugin_register_remove_accelerator() for an explanation.
+        let dyn_callback = owned_extension.into_callback();
+        Box::leak(dyn_callback);
+        let callback = unsafe { handle.restore_original() };
+        Some(callback)
+    }
+
     /// Registers a hidden control surface.
     ///
     /// This is very useful for being notified by REAPER about all kinds of events in the main
@@ -0,0 +1,312 @@
+use crate::{Hidden, MidiOutputDeviceId};
+
+use helgoboss_midi::Channel;
+use std::convert::TryInto;
+
+/// Determines at which point of the track signal flow a send taps the signal.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum SendMode {
+    PostFader,
+    PreFx,
+    /// Pre-fader but post-FX.
+    PreFader,
+    /// Represents a variant unknown to *reaper-rs*. Please contribute if you encounter a variant
+    /// that is supported by REAPER but not yet by *reaper-rs*. Thanks!
+    Unknown(Hidden<i32>),
+}
+
+impl SendMode {
+    /// Converts an integer as returned by the low-level API to a send mode.
+    pub fn from_raw(v: i32) -> SendMode {
+        use SendMode::*;
+        match v {
+            0 => PostFader,
+            1 => PreFx,
+            // 2 is a deprecated variant of 3
+            2 | 3 => PreFader,
+            x => Unknown(Hidden(x)),
+        }
+    }
+
+    /// Converts this value to an integer as expected by the low-level API.
+    pub fn to_raw(self) -> i32 {
+        use SendMode::*;
+        match self {
+            PostFader => 0,
+            PreFx => 1,
+            PreFader => 3,
+            Unknown(Hidden(x)) => x,
+        }
+    }
+}
+
+/// Consecutive audio channels of the track which are sent.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SendSourceChannels {
+    /// Index of the first channel (0 = channel 1).
+    pub offset: u32,
+    /// Number of channels (1 = mono, 2 = stereo, 4, 6, ...).
+    pub count: u32,
+}
+
+/// Audio channel mapping of a send, receive or hardware output send.
+///
+/// Corresponds to the combination of `I_SRCCHAN` and `I_DSTCHAN`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SendChannelMapping {
+    /// `None` means that no audio is sent.
+    pub source: Option<SendSourceChannels>,
+    /// Index of the first destination channel.
+    ///
+    /// For hardware output sends, this is the index of the first hardware output channel.
+    pub destination_offset: u32,
+    /// Whether the signal is mixed down to mono at the destination.
+    pub mix_to_mono: bool,
+    /// Whether the destination is a ReaRoute channel (hardware output sends only).
+    pub rea_route: bool,
+}
+
+impl SendChannelMapping {
+    /// Converts the integers as returned by the low-level API (`I_SRCCHAN`, `I_DSTCHAN`) to a
+    /// channel mapping.
+    pub fn from_raw(source_channel: i32, destination_channel: i32) -> SendChannelMapping {
+        let source = if source_channel < 0 {
+            None
+        } else {
+            let count = match source_channel >> 10 {
+                0 => 2,
+                1 => 1,
+                n => n as u32 * 2,
+            };
+            Some(SendSourceChannels {
+                offset: (source_channel & CHANNEL_INDEX_MASK) as u32,
+                count,
+            })
+        };
+        SendChannelMapping {
+            source,
+            destination_offset: (destination_channel & DESTINATION_INDEX_MASK) as u32,
+            mix_to_mono: destination_channel & MIX_TO_MONO_FLAG != 0,
+            rea_route: destination_channel & REA_ROUTE_FLAG != 0,
+        }
+    }
+
+    /// Converts this value to integers as expected by the low-level API (`I_SRCCHAN`,
+    /// `I_DSTCHAN`).
+    pub fn to_raw(self) -> (i32, i32) {
+        let source_channel = match self.source {
+            None => -1,
+            Some(s) => {
+                let count_code = match s.count {
+                    1 => 1,
+                    2 => 0,
+                    n => (n / 2) as i32,
+                };
+                (count_code << 10) | s.offset as i32
+            }
+        };
+        let mut destination_channel = self.destination_offset as i32;
+        if self.mix_to_mono {
+            destination_channel |= MIX_TO_MONO_FLAG;
+        }
+        if self.rea_route {
+            destination_channel |= REA_ROUTE_FLAG;
+        }
+        (source_channel, destination_channel)
+    }
+}
+
+/// MIDI mapping of a send or receive.
+///
+/// Corresponds to `I_MIDIFLAGS`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct MidiSendMapping {
+    /// `None` means all channels.
+    pub source_channel: Option<Channel>,
+    /// `None` means all buses, `Some(0)` is the default bus.
+    pub source_bus: Option<u8>,
+    /// `None` means that the original channel is kept.
+    pub destination_channel: Option<Channel>,
+    /// `None` means that the original bus is kept, `Some(0)` is the default bus.
+    pub destination_bus: Option<u8>,
+    /// Whether the send's volume and pan faders send MIDI volume and pan messages.
+    pub link_volume_and_pan: bool,
+}
+
+impl MidiSendMapping {
+    /// Value of `I_MIDIFLAGS` which disables sending MIDI.
+    pub const DISABLED_RAW: i32 = 31;
+
+    /// Converts an integer as returned by the low-level API (`I_MIDIFLAGS`) to a MIDI mapping.
+    ///
+    /// Returns `None` if MIDI sending is disabled.
+    pub fn from_raw(flags: i32) -> Option<MidiSendMapping> {
+        let source_channel = flags & 0b11111;
+        if source_channel == Self::DISABLED_RAW {
+            return None;
+        }
+        let mapping = MidiSendMapping {
+            source_channel: channel_from_raw(source_channel),
+            source_bus: bus_from_raw((flags >> 14) & 0xff),
+            destination_channel: channel_from_raw((flags >> 5) & 0b11111),
+            destination_bus: bus_from_raw((flags >> 22) & 0xff),
+            link_volume_and_pan: flags & LINK_VOLUME_AND_PAN_FLAG != 0,
+        };
+        Some(mapping)
+    }
+
+    /// Converts this value to an integer as expected by the low-level API (`I_MIDIFLAGS`).
+    pub fn to_raw(self) -> i32 {
+        let mut flags = channel_to_raw(self.source_channel)
+            | (channel_to_raw(self.destination_channel) << 5)
+            | (bus_to_raw(self.source_bus) << 14)
+            | (bus_to_raw(self.destination_bus) << 22);
+        if self.link_volume_and_pan {
+            flags |= LINK_VOLUME_AND_PAN_FLAG;
+        }
+        flags
+    }
+}
+
+/// MIDI hardware output of a track.
+///
+/// Corresponds to `I_MIDIHWOUT`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct MidiHardwareOutput {
+    pub device_id: MidiOutputDeviceId,
+    /// `None` means that the original channel is kept.
+    pub channel: Option<Channel>,
+}
+
+impl MidiHardwareOutput {
+    /// Converts an integer as returned by the low-level API (`I_MIDIHWOUT`) to a MIDI hardware
+    /// output.
+    ///
+    /// Returns `None` if the track doesn't send to a MIDI hardware output.
+    pub fn from_raw(v: i32) -> Option<MidiHardwareOutput> {
+        if v < 0 {
+            return None;
+        }
+        let output = MidiHardwareOutput {
+            device_id: MidiOutputDeviceId::new(((v >> 5) & 0b11111) as u8),
+            channel: channel_from_raw(v & 0b11111),
+        };
+        Some(output)
+    }
+
+    /// Converts the given value to an integer as expected by the low-level API (`I_MIDIHWOUT`).
+    pub fn to_raw(output: Option<MidiHardwareOutput>) -> i32 {
+        match output {
+            None => -1,
+            Some(o) => ((o.device_id.get() as i32) << 5) | channel_to_raw(o.channel),
+        }
+    }
+}
+
+const CHANNEL_INDEX_MASK: i32 = 0x3ff;
+const DESTINATION_INDEX_MASK: i32 = 0x1ff;
+const REA_ROUTE_FLAG: i32 = 512;
+const MIX_TO_MONO_FLAG: i32 = 1024;
+const LINK_VOLUME_AND_PAN_FLAG: i32 = 1024;
+
+fn channel_from_raw(v: i32) -> Option<Channel> {
+    if v == 0 {
+        return None;
+    }
+    ((v - 1) as u8).try_into().ok()
+}
+
+fn channel_to_raw(channel: Option<Channel>) -> i32 {
+    match channel {
+        None => 0,
+        Some(ch) => u8::from(ch) as i32 + 1,
+    }
+}
+
+fn bus_from_raw(v: i32) -> Option<u8> {
+    if v == 0 {
+        return None;
+    }
+    Some((v - 1) as u8)
+}
+
+fn bus_to_raw(bus: Option<u8>) -> i32 {
+    match bus {
+        None => 0,
+        Some(b) => b as i32 + 1,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn send_mode() {
+        assert_eq!(SendMode::from_raw(2), SendMode::PreFader);
+        assert_eq!(SendMode::PreFader.to_raw(), 3);
+        assert!(matches!(SendMode::from_raw(7), SendMode::Unknown(_)));
+    }
+
+    #[test]
+    fn channel_mapping() {
+        let mapping = SendChannelMapping::from_raw(2 << 10 | 4, 1024 | 2);
+        assert_eq!(
+            mapping,
+            SendChannelMapping {
+                source: Some(SendSourceChannels {
+                    offset: 4,
+                    count: 4
+                }),
+                destination_offset: 2,
+                mix_to_mono: true,
+                rea_route: false,
+            }
+        );
+        assert_eq!(mapping.to_raw(), (2 << 10 | 4, 1024 | 2));
+        let stereo = SendChannelMapping::from_raw(0, 0);
+        assert_eq!(stereo.source.unwrap().count, 2);
+        assert_eq!(stereo.to_raw(), (0, 0));
+        let mono = SendChannelMapping::from_raw(1024 | 3, 0);
+        assert_eq!(mono.source.unwrap().count, 1);
+        assert_eq!(mono.to_raw(), (1024 | 3, 0));
+        assert_eq!(SendChannelMapping::from_raw(-1, 0).source, None);
+    }
+
+    #[test]
+    fn midi_mapping() {
+        assert_eq!(
+            MidiSendMapping::from_raw(MidiSendMapping::DISABLED_RAW),
+            None
+        );
+        let all = MidiSendMapping::from_raw(0).unwrap();
+        assert_eq!(all.source_channel, None);
+        assert_eq!(all.destination_bus, None);
+        let mapping = MidiSendMapping {
+            source_channel: Some(Channel::new(1)),
+            source_bus: Some(0),
+            destination_channel: Some(Channel::new(15)),
+            destination_bus: Some(2),
+            link_volume_and_pan: true,
+        };
+        let raw = mapping.to_raw();
+        assert_eq!(raw & 0b11111, 2);
+        assert_eq!((raw >> 5) & 0b11111, 16);
+        assert_eq!(MidiSendMapping::from_raw(raw), Some(mapping));
+    }
+
+    #[test]
+    fn midi_hardware_output() {
+        assert_eq!(MidiHardwareOutput::from_raw(-1), None);
+        assert_eq!(MidiHardwareOutput::to_raw(None), -1);
+        let output = MidiHardwareOutput {
+            device_id: MidiOutputDeviceId::new(3),
+            channel: Some(Channel::new(9)),
+        };
+        let raw = MidiHardwareOutput::to_raw(Some(output));
+        assert_eq!(raw, 3 << 5 | 10);
+        assert_eq!(MidiHardwareOutput::from_raw(raw), Some(output));
+        let all_channels = MidiHardwareOutput::from_raw(0).unwrap();
+        assert_eq!(all_channels.channel, None);
+    }
+}
@@ -0,0 +1,1001 @@
+//! Fake implementations of REAPER API functions.
+//!
+//! Each function has exactly the name and signature of the REAPER function it fakes, so it can be
+//! handed out by the fake `GetFunc` and loaded into `reaper_low::Reaper` like the real one.
+#![allow(non_snake_case)]
+#![allow(clippy::missing_safety_doc)]
+use crate::guid::{format_guid, parse_guid};
+use crate::host::with_model;
+use crate::model::{
+    fx_name_matches, FxData, Model, ProjectData, Registration, RouteCategory, RouteLocation,
+    SendData, TrackData, TrackId,
+};
+use reaper_low::raw::{audio_hook_register_t, MediaTrack, ReaProject, GUID};
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr::{copy_nonoverlapping, null, null_mut};
+
+macro_rules! fake_functions {
+    ($($name:ident),* $(,)?) => {
+        /// Names of all REAPER functions which are faked.
+        pub(crate) const FAKE_FUNCTION_NAMES: &[&str] = &[$(stringify!($name)),*];
+
+        /// Returns the fake for the REAPER function with the given name or `null` if it's not
+        /// faked.
+        pub(crate) fn get_fake_function(name: &str) -> *mut c_void {
+            match name {
+                $(stringify!($name) => $name as *const () as *mut c_void,)*
+                _ => null_mut(),
+            }
+        }
+    };
+}
+
+fake_functions![
+    GetAppVersion,
+    GetResourcePath,
+    ShowConsoleMsg,
+    plugin_register,
+    Audio_RegHardwareHook,
+    EnumProjects,
+    GetCurrentProjectInLoadSave,
+    ValidatePtr,
+    ValidatePtr2,
+    genGuid,
+    guidToString,
+    stringToGuid,
+    GetPlayState,
+    GetPlayStateEx,
+    GetCursorPosition,
+    GetCursorPositionEx,
+    SetEditCurPos,
+    SetEditCurPos2,
+    GetSet_LoopTimeRange2,
+    GetProjectName,
+    GetProjectPathEx,
+    MarkProjectDirty,
+    IsProjectDirty,
+    UpdateArrange,
+    UpdateTimeline,
+    TrackList_AdjustWindows,
+    TrackList_UpdateAllExternalSurfaces,
+    CountTracks,
+    GetTrack,
+    GetMasterTrack,
+    InsertTrackAtIndex,
+    DeleteTrack,
+    GetTrackGUID,
+    CSurf_TrackToID,
+    CSurf_TrackFromID,
+    SetTrackSelected,
+    IsTrackSelected,
+    CountSelectedTracks2,
+    GetSelectedTrack2,
+    GetSetMediaTrackInfo,
+    GetSetMediaTrackInfo_String,
+    GetMediaTrackInfo_Value,
+    SetMediaTrackInfo_Value,
+    TrackFX_GetCount,
+    TrackFX_GetRecCount,
+    TrackFX_AddByName,
+    TrackFX_Delete,
+    TrackFX_GetFXName,
+    TrackFX_GetFXGUID,
+    TrackFX_GetEnabled,
+    TrackFX_SetEnabled,
+    TrackFX_GetNumParams,
+    TrackFX_GetParamName,
+    TrackFX_GetParam,
+    TrackFX_GetParamNormalized,
+    TrackFX_SetParamNormalized,
+    CreateTrackSend,
+    RemoveTrackSend,
+    GetTrackNumSends,
+    GetSetTrackSendInfo,
+    GetTrackSendInfo_Value,
+    SetTrackSendInfo_Value,
+    AddProjectMarker2,
+    DeleteProjectMarker,
+    CountProjectMarkers,
+    EnumProjectMarkers3,
+    Master_GetTempo,
+    SetCurrentBPM,
+    GetProjectTimeSignature2,
+    TimeMap_GetDividedBpmAtTime,
+    Undo_BeginBlock,
+    Undo_BeginBlock2,
+    Undo_EndBlock,
+    Undo_EndBlock2,
+    Undo_OnStateChange,
+    Undo_OnStateChange2,
+    Undo_OnStateChangeEx,
+    Undo_OnStateChangeEx2,
+    Undo_CanUndo2,
+    Undo_CanRedo2,
+    Undo_DoUndo2,
+    Undo_DoRedo2,
+];
+
+// General
+
+unsafe extern "C" fn GetAppVersion() -> *const c_char {
+    with_model(|m| m.app_version.as_ptr())
+}
+
+unsafe extern "C" fn GetResourcePath() -> *const c_char {
+    with_model(|m| m.resource_path.as_ptr())
+}
+
+unsafe extern "C" fn ShowConsoleMsg(msg: *const c_char) {
+    let msg = str_arg(msg);
+    with_model(|m| m.console_output.push_str(&msg));
+}
+
+pub(crate) unsafe extern "C" fn plugin_register(
+    name: *const c_char,
+    infostruct: *mut c_void,
+) -> c_int {
+    let name = str_arg(name);
+    with_model(|m| {
+        if let Some(name) = name.strip_prefix('-') {
+            let registration = Registration {
+                name: name.to_string(),
+                info_struct: infostruct as usize,
+            };
+            m.registrations.retain(|r| r != &registration);
+        } else {
+            m.registrations.push(Registration {
+                name,
+                info_struct: infostruct as usize,
+            });
+        }
+    });
+    1
+}
+
+unsafe extern "C" fn Audio_RegHardwareHook(
+    _isAdd: bool,
+    _reg: *mut audio_hook_register_t,
+) -> c_int {
+    // The audio hook is never called because there's no audio device
+    1
+}
+
+unsafe extern "C" fn EnumProjects(
+    idx: c_int,
+    projfnOutOptional: *mut c_char,
+    projfnOutOptional_sz: c_int,
+) -> *mut ReaProject {
+    if idx > 0 {
+        return null_mut();
+    }
+    copy_to_buffer(&CString::default(), projfnOutOptional, projfnOutOptional_sz);
+    with_model(|m| m.project_ptr())
+}
+
+unsafe extern "C" fn GetCurrentProjectInLoadSave() -> *mut ReaProject {
+    null_mut()
+}
+
+unsafe extern "C" fn ValidatePtr(pointer: *mut c_void, ctypename: *const c_char) -> bool {
+    ValidatePtr2(null_mut(), pointer, ctypename)
+}
+
+unsafe extern "C" fn ValidatePtr2(
+    proj: *mut ReaProject,
+    pointer: *mut c_void,
+    ctypename: *const c_char,
+) -> bool {
+    let type_name = str_arg(ctypename);
+    with_model(|m| match type_name.as_str() {
+        "ReaProject*" => pointer as *mut ReaProject == m.project_ptr(),
+        "MediaTrack*" => match m.project_mut(proj) {
+            None => false,
+            Some(p) => p.contains_track(pointer as TrackId),
+        },
+        _ => false,
+    })
+}
+
+unsafe extern "C" fn genGuid(g: *mut GUID) {
+    *g = with_model(|m| m.generate_guid());
+}
+
+unsafe extern "C" fn guidToString(g: *const GUID, destNeed64: *mut c_char) {
+    let text = CString::new(format_guid(&*g)).unwrap();
+    copy_to_buffer(&text, destNeed64, 64);
+}
+
+unsafe extern "C" fn stringToGuid(str: *const c_char, g: *mut GUID) {
+    *g = parse_guid(&str_arg(str)).unwrap_or(GUID {
+        Data1: 0,
+        Data2: 0,
+        Data3: 0,
+        Data4: [0; 8],
+    });
+}
+
+// Project
+
+unsafe extern "C" fn GetPlayState() -> c_int {
+    0
+}
+
+unsafe extern "C" fn GetPlayStateEx(_proj: *mut ReaProject) -> c_int {
+    0
+}
+
+unsafe extern "C" fn GetCursorPosition() -> f64 {
+    GetCursorPositionEx(null_mut())
+}
+
+unsafe extern "C" fn GetCursorPositionEx(proj: *mut ReaProject) -> f64 {
+    with_project(proj, 0.0, |p| p.edit_cursor_position)
+}
+
+unsafe extern "C" fn SetEditCurPos(time: f64, moveview: bool, seekplay: bool) {
+    SetEditCurPos2(null_mut(), time, moveview, seekplay);
+}
+
+unsafe extern "C" fn SetEditCurPos2(
+    proj: *mut ReaProject,
+    time: f64,
+    _moveview: bool,
+    _seekplay: bool,
+) {
+    with_project(proj, (), |p| p.edit_cursor_position = time.max(0.0));
+}
+
+unsafe extern "C" fn GetSet_LoopTimeRange2(
+    proj: *mut ReaProject,
+    isSet: bool,
+    isLoop: bool,
+    startOut: *mut f64,
+    endOut: *mut f64,
+    _allowautoseek: bool,
+) {
+    with_project(proj, (), |p| {
+        let range = if isLoop {
+            &mut p.loop_points
+        } else {
+            &mut p.time_selection
+        };
+        if isSet {
+            *range = (*startOut, *endOut);
+        } else {
+            *startOut = range.0;
+            *endOut = range.1;
+        }
+    });
+}
+
+unsafe extern "C" fn GetProjectName(_proj: *mut ReaProject, bufOut: *mut c_char, bufOut_sz: c_int) {
+    // The fake project has never been saved
+    copy_to_buffer(&CString::default(), bufOut, bufOut_sz);
+}
+
+unsafe extern "C" fn GetProjectPathEx(
+    _proj: *mut ReaProject,
+    bufOut: *mut c_char,
+    bufOut_sz: c_int,
+) {
+    let path = with_model(|m| m.resource_path.clone());
+    copy_to_buffer(&path, bufOut, bufOut_sz);
+}
+
+unsafe extern "C" fn MarkProjectDirty(proj: *mut ReaProject) {
+    with_project(proj, (), |p| p.is_dirty = true);
+}
+
+unsafe extern "C" fn IsProjectDirty(proj: *mut ReaProject) -> c_int {
+    with_project(proj, 0, |p| p.is_dirty as c_int)
+}
+
+unsafe extern "C" fn UpdateArrange() {}
+
+unsafe extern "C" fn UpdateTimeline() {}
+
+unsafe extern "C" fn TrackList_AdjustWindows(_isMinor: bool) {}
+
+unsafe extern "C" fn TrackList_UpdateAllExternalSurfaces() {}
+
+// Tracks
+
+unsafe extern "C" fn CountTracks(projOptional: *mut ReaProject) -> c_int {
+    with_project(projOptional, 0, |p| p.track_count() as c_int)
+}
+
+unsafe extern "C" fn GetTrack(proj: *mut ReaProject, trackidx: c_int) -> *mut MediaTrack {
+    with_project(proj, null_mut(), |p| {
+        if trackidx < 0 {
+            return null_mut();
+        }
+        track_ptr(p.track_id(trackidx as usize))
+    })
+}
+
+unsafe extern "C" fn GetMasterTrack(proj: *mut ReaProject) -> *mut MediaTrack {
+    with_project(proj, null_mut(), |p| p.master_id() as *mut MediaTrack)
+}
+
+unsafe extern "C" fn InsertTrackAtIndex(idx: c_int, _wantDefaults: bool) {
+    with_model(|m| m.insert_track(idx.max(0) as usize));
+}
+
+unsafe extern "C" fn DeleteTrack(tr: *mut MediaTrack) {
+    with_model(|m| m.project.remove_track(tr as TrackId));
+}
+
+unsafe extern "C" fn GetTrackGUID(tr: *mut MediaTrack) -> *mut GUID {
+    with_track(tr, null_mut(), |_, t| &mut t.guid as *mut GUID)
+}
+
+unsafe extern "C" fn CSurf_TrackToID(track: *mut MediaTrack, _mcpView: bool) -> c_int {
+    with_model(|m| {
+        let id = track as TrackId;
+        if id == m.project.master_id() {
+            0
+        } else {
+            m.project
+                .track_index(id)
+                .map(|i| i as c_int + 1)
+                .unwrap_or(-1)
+        }
+    })
+}
+
+unsafe extern "C" fn CSurf_TrackFromID(idx: c_int, _mcpView: bool) -> *mut MediaTrack {
+    with_model(|m| match idx {
+        0 => m.project.master_id() as *mut MediaTrack,
+        i if i > 0 => track_ptr(m.project.track_id(i as usize - 1)),
+        _ => null_mut(),
+    })
+}
+
+unsafe extern "C" fn SetTrackSelected(track: *mut MediaTrack, selected: bool) {
+    with_track(track, (), |_, t| {
+        t.attributes.set("I_SELECTED", selected as i32 as f64);
+    });
+}
+
+unsafe extern "C" fn IsTrackSelected(track: *mut MediaTrack) -> bool {
+    with_track(track, false, |_, t| {
+        t.attributes.get("I_SELECTED").unwrap_or(0.0) != 0.0
+    })
+}
+
+unsafe extern "C" fn CountSelectedTracks2(proj: *mut ReaProject, wantmaster: bool) -> c_int {
+    with_project(proj, 0, |p| p.selected_track_ids(wantmaster).len() as c_int)
+}
+
+unsafe extern "C" fn GetSelectedTrack2(
+    proj: *mut ReaProject,
+    seltrackidx: c_int,
+    wantmaster: bool,
+) -> *mut MediaTrack {
+    with_project(proj, null_mut(), |p| {
+        if seltrackidx < 0 {
+            return null_mut();
+        }
+        track_ptr(
+            p.selected_track_ids(wantmaster)
+                .get(seltrackidx as usize)
+                .copied(),
+        )
+    })
+}
+
+unsafe extern "C" fn GetSetMediaTrackInfo(
+    tr: *mut MediaTrack,
+    parmname: *const c_char,
+    setNewValue: *mut c_void,
+) -> *mut c_void {
+    let key = str_arg(parmname);
+    let id = tr as TrackId;
+    with_model(|m| {
+        if !m.project.contains_track(id) {
+            return null_mut();
+        }
+        let is_master = id == m.project.master_id();
+        match key.as_str() {
+            "P_PROJECT" => return m.project_ptr() as *mut c_void,
+            "P_PARTRACK" => return track_ptr(m.project.parent_track(id)) as *mut c_void,
+            "IP_TRACKNUMBER" => {
+                let number = if is_master {
+                    -1
+                } else {
+                    m.project.track_index(id).unwrap() as isize + 1
+                };
+                return number as *mut c_void;
+            }
+            "P_NAME" if is_master => return null_mut(),
+            _ => {}
+        }
+        let t = m.project.track_mut(id).unwrap();
+        if key == "GUID" {
+            if !setNewValue.is_null() {
+                t.guid = *(setNewValue as *const GUID);
+            }
+            return &mut t.guid as *mut GUID as *mut c_void;
+        }
+        if is_string_attribute(&key) {
+            if !setNewValue.is_null() {
+                let value = CStr::from_ptr(setNewValue as *const c_char).to_owned();
+                t.strings.insert(key.clone(), value);
+            }
+            return t.strings.entry(key).or_default().as_ptr() as *mut c_void;
+        }
+        match t.attributes.value_mut(&key) {
+            None => null_mut(),
+            Some(v) => {
+                if !setNewValue.is_null() {
+                    v.set_from_ptr(setNewValue);
+                }
+                v.as_mut_ptr()
+            }
+        }
+    })
+}
+
+unsafe extern "C" fn GetSetMediaTrackInfo_String(
+    tr: *mut MediaTrack,
+    parmname: *const c_char,
+    stringNeedBig: *mut c_char,
+    setNewValue: bool,
+) -> bool {
+    let key = str_arg(parmname);
+    with_track(tr, false, |_, t| {
+        if key == "GUID" {
+            if setNewValue {
+                match parse_guid(&str_arg(stringNeedBig)) {
+                    None => return false,
+                    Some(g) => t.guid = g,
+                }
+            } else {
+                let text = CString::new(format_guid(&t.guid)).unwrap();
+                copy_to_unsized_buffer(&text, stringNeedBig);
+            }
+            return true;
+        }
+        if !is_string_attribute(&key) {
+            return false;
+        }
+        if setNewValue {
+            let value = CStr::from_ptr(stringNeedBig).to_owned();
+            t.strings.insert(key, value);
+        } else {
+            let value = t.strings.get(&key).cloned().unwrap_or_default();
+            copy_to_unsized_buffer(&value, stringNeedBig);
+        }
+        true
+    })
+}
+
+unsafe extern "C" fn GetMediaTrackInfo_Value(tr: *mut MediaTrack, parmname: *const c_char) -> f64 {
+    let key = str_arg(parmname);
+    if key == "IP_TRACKNUMBER" {
+        return GetSetMediaTrackInfo(tr, parmname, null_mut()) as isize as f64;
+    }
+    with_track(tr, 0.0, |_, t| t.attributes.get(&key).unwrap_or(0.0))
+}
+
+unsafe extern "C" fn SetMediaTrackInfo_Value(
+    tr: *mut MediaTrack,
+    parmname: *const c_char,
+    newvalue: f64,
+) -> bool {
+    let key = str_arg(parmname);
+    with_track(tr, false, |_, t| t.attributes.set(&key, newvalue))
+}
+
+// FX
+
+unsafe extern "C" fn TrackFX_GetCount(track: *mut MediaTrack) -> c_int {
+    with_track(track, 0, |_, t| t.fx_chain.len() as c_int)
+}
+
+unsafe extern "C" fn TrackFX_GetRecCount(track: *mut MediaTrack) -> c_int {
+    with_track(track, 0, |_, t| t.input_fx_chain.len() as c_int)
+}
+
+unsafe extern "C" fn TrackFX_AddByName(
+    track: *mut MediaTrack,
+    fxname: *const c_char,
+    recFX: bool,
+    instantiate: c_int,
+) -> c_int {
+    let query = str_arg(fxname);
+    let id = track as TrackId;
+    with_model(|m| {
+        let existing_index = {
+            let t = match m.project.track_mut(id) {
+                None => return -1,
+                Some(t) => t,
+            };
+            t.fx_chain_mut(recFX)
+                .iter()
+                .position(|fx| fx_name_matches(&fx.name.to_string_lossy(), &query))
+        };
+        let insert_index = match instantiate {
+            0 => return existing_index.map(|i| i as c_int).unwrap_or(-1),
+            1 => {
+                if let Some(i) = existing_index {
+                    return i as c_int;
+                }
+                None
+            }
+            i if i <= -1000 => Some((-i - 1000) as usize),
+            _ => None,
+        };
+        m.add_fx(id, recFX, &query, insert_index)
+            .map(|i| i as c_int)
+            .unwrap_or(-1)
+    })
+}
+
+unsafe extern "C" fn TrackFX_Delete(track: *mut MediaTrack, fx: c_int) -> bool {
+    with_track(track, false, |_, t| t.remove_fx(fx))
+}
+
+unsafe extern "C" fn TrackFX_GetFXName(
+    track: *mut MediaTrack,
+    fx: c_int,
+    bufOut: *mut c_char,
+    bufOut_sz: c_int,
+) -> bool {
+    with_fx(track, fx, false, |fx| {
+        copy_to_buffer(&fx.name, bufOut, bufOut_sz);
+        true
+    })
+}
+
+unsafe extern "C" fn TrackFX_GetFXGUID(track: *mut MediaTrack, fx: c_int) -> *mut GUID {
+    with_fx(track, fx, null_mut(), |fx| &mut fx.guid as *mut GUID)
+}
+
+unsafe extern "C" fn TrackFX_GetEnabled(track: *mut MediaTrack, fx: c_int) -> bool {
+    with_fx(track, fx, false, |fx| fx.enabled)
+}
+
+unsafe extern "C" fn TrackFX_SetEnabled(track: *mut MediaTrack, fx: c_int, enabled: bool) {
+    with_fx(track, fx, (), |fx| fx.enabled = enabled);
+}
+
+unsafe extern "C" fn TrackFX_GetNumParams(track: *mut MediaTrack, fx: c_int) -> c_int {
+    with_fx(track, fx, 0, |fx| fx.parameters.len() as c_int)
+}
+
+unsafe extern "C" fn TrackFX_GetParamName(
+    track: *mut MediaTrack,
+    fx: c_int,
+    param: c_int,
+    bufOut: *mut c_char,
+    bufOut_sz: c_int,
+) -> bool {
+    with_fx(track, fx, false, |fx| {
+        match fx.parameters.get(param as usize) {
+            None => false,
+            Some(p) => {
+                copy_to_buffer(&p.name, bufOut, bufOut_sz);
+                true
+            }
+        }
+    })
+}
+
+unsafe extern "C" fn TrackFX_GetParam(
+    track: *mut MediaTrack,
+    fx: c_int,
+    param: c_int,
+    minvalOut: *mut f64,
+    maxvalOut: *mut f64,
+) -> f64 {
+    // All fake parameters have the range 0.0 to 1.0
+    if !minvalOut.is_null() {
+        *minvalOut = 0.0;
+    }
+    if !maxvalOut.is_null() {
+        *maxvalOut = 1.0;
+    }
+    TrackFX_GetParamNormalized(track, fx, param)
+}
+
+unsafe extern "C" fn TrackFX_GetParamNormalized(
+    track: *mut MediaTrack,
+    fx: c_int,
+    param: c_int,
+) -> f64 {
+    with_fx(track, fx, 0.0, |fx| {
+        fx.parameters
+            .get(param as usize)
+            .map(|p| p.value)
+            .unwrap_or(0.0)
+    })
+}
+
+unsafe extern "C" fn TrackFX_SetParamNormalized(
+    track: *mut MediaTrack,
+    fx: c_int,
+    param: c_int,
+    value: f64,
+) -> bool {
+    with_fx(track, fx, false, |fx| {
+        match fx.parameters.get_mut(param as usize) {
+            None => false,
+            Some(p) => {
+                p.value = value.clamp(0.0, 1.0);
+                true
+            }
+        }
+    })
+}
+
+// Sends, receives and hardware outputs
+
+unsafe extern "C" fn CreateTrackSend(
+    tr: *mut MediaTrack,
+    desttrInOptional: *mut MediaTrack,
+) -> c_int {
+    let source = tr as TrackId;
+    let destination = desttrInOptional as TrackId;
+    with_model(|m| {
+        if !m.project.contains_track(source)
+            || (destination != 0 && !m.project.contains_track(destination))
+        {
+            return -1;
+        }
+        let t = m.project.track_mut(source).unwrap();
+        let (routes, destination) = if destination == 0 {
+            (&mut t.hardware_outputs, None)
+        } else {
+            (&mut t.sends, Some(destination))
+        };
+        routes.push(SendData::new(destination));
+        routes.len() as c_int - 1
+    })
+}
+
+unsafe extern "C" fn RemoveTrackSend(tr: *mut MediaTrack, category: c_int, sendidx: c_int) -> bool {
+    with_route(tr, category, sendidx, false, |m, location| {
+        m.project.remove_route(location)
+    })
+}
+
+unsafe extern "C" fn GetTrackNumSends(tr: *mut MediaTrack, category: c_int) -> c_int {
+    with_model(|m| {
+        m.project
+            .route_count(tr as TrackId, RouteCategory::from_raw(category)) as c_int
+    })
+}
+
+unsafe extern "C" fn GetSetTrackSendInfo(
+    tr: *mut MediaTrack,
+    category: c_int,
+    sendidx: c_int,
+    parmname: *const c_char,
+    setNewValue: *mut c_void,
+) -> *mut c_void {
+    let key = str_arg(parmname);
+    with_route(tr, category, sendidx, null_mut(), |m, location| {
+        let route = m.project.route_mut(location).unwrap();
+        match key.as_str() {
+            "P_SRCTRACK" => location.source as *mut c_void,
+            "P_DESTTRACK" => track_ptr(route.destination) as *mut c_void,
+            _ => match route.attributes.value_mut(&key) {
+                None => null_mut(),
+                Some(v) => {
+                    if !setNewValue.is_null() {
+                        v.set_from_ptr(setNewValue);
+                    }
+                    v.as_mut_ptr()
+                }
+            },
+        }
+    })
+}
+
+unsafe extern "C" fn GetTrackSendInfo_Value(
+    tr: *mut MediaTrack,
+    category: c_int,
+    sendidx: c_int,
+    parmname: *const c_char,
+) -> f64 {
+    let key = str_arg(parmname);
+    with_route(tr, category, sendidx, 0.0, |m, location| {
+        let route = m.project.route_mut(location).unwrap();
+        match key.as_str() {
+            // REAPER returns pointers as numbers here
+            "P_SRCTRACK" => location.source as f64,
+            "P_DESTTRACK" => route.destination.unwrap_or(0) as f64,
+            _ => route.attributes.get(&key).unwrap_or(0.0),
+        }
+    })
+}
+
+unsafe extern "C" fn SetTrackSendInfo_Value(
+    tr: *mut MediaTrack,
+    category: c_int,
+    sendidx: c_int,
+    parmname: *const c_char,
+    newvalue: f64,
+) -> bool {
+    let key = str_arg(parmname);
+    with_route(tr, category, sendidx, false, |m, location| {
+        let route = m.project.route_mut(location).unwrap();
+        route.attributes.set(&key, newvalue)
+    })
+}
+
+// Markers and regions
+
+unsafe extern "C" fn AddProjectMarker2(
+    proj: *mut ReaProject,
+    isrgn: bool,
+    pos: f64,
+    rgnend: f64,
+    name: *const c_char,
+    wantidx: c_int,
+    color: c_int,
+) -> c_int {
+    let name = if name.is_null() {
+        CString::default()
+    } else {
+        CStr::from_ptr(name).to_owned()
+    };
+    with_project(proj, -1, |p| {
+        p.add_marker(isrgn, pos, rgnend, &name, wantidx, color)
+    })
+}
+
+unsafe extern "C" fn DeleteProjectMarker(
+    proj: *mut ReaProject,
+    markrgnindexnumber: c_int,
+    isrgn: bool,
+) -> bool {
+    with_project(proj, false, |p| p.remove_marker(markrgnindexnumber, isrgn))
+}
+
+unsafe extern "C" fn CountProjectMarkers(
+    proj: *mut ReaProject,
+    num_markersOut: *mut c_int,
+    num_regionsOut: *mut c_int,
+) -> c_int {
+    with_project(proj, 0, |p| {
+        let region_count = p.markers.iter().filter(|m| m.is_region).count() as c_int;
+        let total_count = p.markers.len() as c_int;
+        if !num_markersOut.is_null() {
+            *num_markersOut = total_count - region_count;
+        }
+        if !num_regionsOut.is_null() {
+            *num_regionsOut = region_count;
+        }
+        total_count
+    })
+}
+
+unsafe extern "C" fn EnumProjectMarkers3(
+    proj: *mut ReaProject,
+    idx: c_int,
+    isrgnOut: *mut bool,
+    posOut: *mut f64,
+    rgnendOut: *mut f64,
+    nameOut: *mut *const c_char,
+    markrgnindexnumberOut: *mut c_int,
+    colorOut: *mut c_int,
+) -> c_int {
+    with_project(proj, 0, |p| {
+        if idx < 0 {
+            return 0;
+        }
+        let marker = match p.markers.get(idx as usize) {
+            None => return 0,
+            Some(m) => m,
+        };
+        set_out(isrgnOut, marker.is_region);
+        set_out(posOut, marker.position);
+        set_out(rgnendOut, marker.region_end);
+        set_out(nameOut, marker.name.as_ptr());
+        set_out(markrgnindexnumberOut, marker.index_number);
+        set_out(colorOut, marker.color);
+        idx + 1
+    })
+}
+
+// Tempo
+
+unsafe extern "C" fn Master_GetTempo() -> f64 {
+    with_model(|m| m.project.tempo)
+}
+
+unsafe extern "C" fn SetCurrentBPM(__proj: *mut ReaProject, bpm: f64, wantUndo: bool) {
+    with_project(__proj, (), |p| {
+        p.tempo = bpm;
+        if wantUndo {
+            p.add_undo_point(&CString::new("Tempo change").unwrap());
+        }
+    });
+}
+
+unsafe extern "C" fn GetProjectTimeSignature2(
+    proj: *mut ReaProject,
+    bpmOut: *mut f64,
+    bpiOut: *mut f64,
+) {
+    with_project(proj, (), |p| {
+        set_out(bpmOut, p.tempo);
+        set_out(bpiOut, p.beats_per_measure);
+    });
+}
+
+unsafe extern "C" fn TimeMap_GetDividedBpmAtTime(_time: f64) -> f64 {
+    // There are no tempo markers, so the tempo is the same everywhere
+    Master_GetTempo()
+}
+
+// Undo
+
+unsafe extern "C" fn Undo_BeginBlock() {
+    Undo_BeginBlock2(null_mut());
+}
+
+unsafe extern "C" fn Undo_BeginBlock2(proj: *mut ReaProject) {
+    with_project(proj, (), |p| p.begin_undo_block());
+}
+
+unsafe extern "C" fn Undo_EndBlock(descchange: *const c_char, extraflags: c_int) {
+    Undo_EndBlock2(null_mut(), descchange, extraflags);
+}
+
+unsafe extern "C" fn Undo_EndBlock2(
+    proj: *mut ReaProject,
+    descchange: *const c_char,
+    _extraflags: c_int,
+) {
+    let description = CStr::from_ptr(descchange).to_owned();
+    with_project(proj, (), |p| p.end_undo_block(&description));
+}
+
+unsafe extern "C" fn Undo_OnStateChange(descchange: *const c_char) {
+    Undo_OnStateChange2(null_mut(), descchange);
+}
+
+unsafe extern "C" fn Undo_OnStateChange2(proj: *mut ReaProject, descchange: *const c_char) {
+    let description = CStr::from_ptr(descchange).to_owned();
+    with_project(proj, (), |p| p.add_undo_point(&description));
+}
+
+unsafe extern "C" fn Undo_OnStateChangeEx(
+    descchange: *const c_char,
+    whichStates: c_int,
+    trackparm: c_int,
+) {
+    Undo_OnStateChangeEx2(null_mut(), descchange, whichStates, trackparm);
+}
+
+unsafe extern "C" fn Undo_OnStateChangeEx2(
+    proj: *mut ReaProject,
+    descchange: *const c_char,
+    _whichStates: c_int,
+    _trackparm: c_int,
+) {
+    Undo_OnStateChange2(proj, descchange);
+}
+
+unsafe extern "C" fn Undo_CanUndo2(proj: *mut ReaProject) -> *const c_char {
+    with_project(proj, null(), |p| {
+        p.undo_description().map(|d| d.as_ptr()).unwrap_or(null())
+    })
+}
+
+unsafe extern "C" fn Undo_CanRedo2(proj: *mut ReaProject) -> *const c_char {
+    with_project(proj, null(), |p| {
+        p.redo_description().map(|d| d.as_ptr()).unwrap_or(null())
+    })
+}
+
+unsafe extern "C" fn Undo_DoUndo2(proj: *mut ReaProject) -> c_int {
+    with_project(proj, 0, |p| p.undo() as c_int)
+}
+
+unsafe extern "C" fn Undo_DoRedo2(proj: *mut ReaProject) -> c_int {
+    with_project(proj, 0, |p| p.redo() as c_int)
+}
+
+// Helpers
+
+fn with_project<R>(
+    project: *mut ReaProject,
+    default: R,
+    f: impl FnOnce(&mut ProjectData) -> R,
+) -> R {
+    with_model(|m| match m.project_mut(project) {
+        None => default,
+        Some(p) => f(p),
+    })
+}
+
+fn with_track<R>(
+    track: *mut MediaTrack,
+    default: R,
+    f: impl FnOnce(TrackId, &mut TrackData) -> R,
+) -> R {
+    let id = track as TrackId;
+    with_model(|m| match m.project.track_mut(id) {
+        None => default,
+        Some(t) => f(id, t),
+    })
+}
+
+fn with_fx<R>(
+    track: *mut MediaTrack,
+    fx_index: c_int,
+    default: R,
+    f: impl FnOnce(&mut FxData) -> R,
+) -> R {
+    with_track(track, None, |_, t| t.fx_mut(fx_index).map(f)).unwrap_or(default)
+}
+
+fn with_route<R>(
+    track: *mut MediaTrack,
+    category: c_int,
+    index: c_int,
+    default: R,
+    f: impl FnOnce(&mut Model, RouteLocation) -> R,
+) -> R {
+    if index < 0 {
+        return default;
+    }
+    with_model(|m| {
+        let location = m.project.route_location(
+            track as TrackId,
+            RouteCategory::from_raw(category),
+            index as usize,
+        );
+        match location {
+            None => default,
+            Some(l) => f(m, l),
+        }
+    })
+}
+
+fn track_ptr(id: Option<TrackId>) -> *mut MediaTrack {
+    id.map(|id| id as *mut MediaTrack).unwrap_or(null_mut())
+}
+
+fn is_string_attribute(key: &str) -> bool {
+    key.starts_with("P_") && !matches!(key, "P_PROJECT" | "P_PARTRACK") && !key.starts_with("P_ENV")
+}
+
+unsafe fn str_arg(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    CStr::from_ptr(ptr).to_string_lossy().into_owned()
+}
+
+unsafe fn set_out<T>(ptr: *mut T, value: T) {
+    if !ptr.is_null() {
+        *ptr = value;
+    }
+}
+
+/// Copies the given string into the given buffer, truncating it if necessary.
+unsafe fn copy_to_buffer(text: &CStr, buffer: *mut c_char, buffer_size: c_int) {
+    if buffer.is_null() || buffer_size <= 0 {
+        return;
+    }
+    let bytes = text.to_bytes();
+    let count = bytes.len().min(buffer_size as usize - 1);
+    copy_nonoverlapping(bytes.as_ptr() as *const c_char, buffer, count);
+    *buffer.add(count) = 0;
+}
+
+/// Copies the given string into a buffer whose size is not known, just like REAPER does it for
+/// functions with a `NeedBig` parameter.
+unsafe fn copy_to_unsized_buffer(text: &CStr, buffer: *mut c_char) {
+    if buffer.is_null() {
+        return;
+    }
+    let bytes = text.to_bytes_with_nul();
+    copy_nonoverlapping(bytes.as_ptr() as *const c_char, buffer, bytes.len());
+}
@@ -0,0 +1,59 @@
+use reaper_low::raw::GUID;
+
+/// Formats the given GUID the way REAPER does (uppercase, with braces).
+pub(crate) fn format_guid(guid: &GUID) -> String {
+    let d = &guid.Data4;
+    format!(
+        "{{{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}}}",
+        guid.Data1, guid.Data2, guid.Data3, d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]
+    )
+}
+
+/// Parses a GUID string with braces, e.g. `{8E3D1C2A-6B1F-4E1D-9C3A-2D7B5F0A1E44}`.
+pub(crate) fn parse_guid(text: &str) -> Option<GUID> {
+    let inner = text.strip_prefix('{')?.strip_suffix('}')?;
+    let groups: Vec<_> = inner.split('-').collect();
+    if groups
+        .iter()
+        .map(|g| g.len())
+        .ne([8, 4, 4, 4, 12].iter().copied())
+    {
+        return None;
+    }
+    let data_4_hex = format!("{}{}", groups[3], groups[4]);
+    let mut data_4 = [0u8; 8];
+    for (i, byte) in data_4.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&data_4_hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    let guid = GUID {
+        Data1: u32::from_str_radix(groups[0], 16).ok()?,
+        Data2: u16::from_str_radix(groups[1], 16).ok()?,
+        Data3: u16::from_str_radix(groups[2], 16).ok()?,
+        Data4: data_4,
+    };
+    Some(guid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        // Given
+        let text = "{8E3D1C2A-6B1F-4E1D-9C3A-2D7B5F0A1E44}";
+        // When
+        let guid = parse_guid(text).unwrap();
+        // Then
+        assert_eq!(guid.Data1, 0x8E3D1C2A);
+        assert_eq!(guid.Data4, [0x9C, 0x3A, 0x2D, 0x7B, 0x5F, 0x0A, 0x1E, 0x44]);
+        assert_eq!(format_guid(&guid), text);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(parse_guid("8E3D1C2A-6B1F-4E1D-9C3A-2D7B5F0A1E44").is_none());
+        assert!(parse_guid("{8E3D1C2A-6B1F-4E1D-9C3A}").is_none());
+        assert!(parse_guid("{XE3D1C2A-6B1F-4E1D-9C3A-2D7B5F0A1E44}").is_none());
+    }
+}
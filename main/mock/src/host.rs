@@ -0,0 +1,345 @@
+use crate::functions::{get_fake_function, plugin_register, FAKE_FUNCTION_NAMES};
+use crate::model::{InstalledFx, Model};
+use reaper_low::{raw, PluginContext, StaticExtensionPluginContext};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::ptr::null_mut;
+use std::sync::{mpsc, Mutex, OnceLock, PoisonError};
+use std::thread;
+
+thread_local! {
+    static MODEL: RefCell<Option<Model>> = const { RefCell::new(None) };
+}
+
+/// Sends tasks to the thread which owns the shared mock (see [`with_shared_mock()`]).
+static SHARED_MOCK_TASK_SENDER: OnceLock<Mutex<mpsc::Sender<SharedMockTask>>> = OnceLock::new();
+
+type SharedMockTask = Box<dyn FnOnce(&MockReaper) + Send>;
+
+/// Gives the fake REAPER functions access to the model of the current thread.
+pub(crate) fn with_model<R>(f: impl FnOnce(&mut Model) -> R) -> R {
+    MODEL.with(|model| {
+        let mut model = model.borrow_mut();
+        let model = model
+            .as_mut()
+            .expect("there's no MockReaper on this thread");
+        f(model)
+    })
+}
+
+/// An in-process fake REAPER host.
+///
+/// Provides a [`PluginContext`] which can be passed to `reaper_low::Reaper::load()`,
+/// `reaper_medium::ReaperSession::load()` or `reaper_high::ReaperBuilder` instead of the one
+/// which REAPER passes to the extension entry point. The REAPER functions listed by
+/// [`supported_functions()`] then operate on an in-memory project.
+///
+/// The fake host state lives in a thread-local, so everything must happen on the thread which
+/// created the mock. There can be only one mock per thread at a time. The state is discarded when
+/// the mock is dropped.
+///
+/// [`PluginContext`]: ../reaper_low/struct.PluginContext.html
+/// [`supported_functions()`]: #method.supported_functions
+#[derive(Debug)]
+pub struct MockReaper {
+    // The state is thread-local, so make sure the mock can't be sent to another thread.
+    _p: PhantomData<*const ()>,
+}
+
+impl Default for MockReaper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockReaper {
+    /// Creates a fake REAPER host with an empty project.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's already a mock on this thread.
+    pub fn new() -> MockReaper {
+        MODEL.with(|model| {
+            let mut model = model.borrow_mut();
+            assert!(
+                model.is_none(),
+                "there's already a MockReaper on this thread"
+            );
+            *model = Some(Model::new());
+        });
+        MockReaper { _p: PhantomData }
+    }
+
+    /// Returns a plug-in context whose function provider hands out the fake REAPER functions.
+    ///
+    /// Functions which are not faked are reported as not available, exactly like REAPER does it
+    /// for functions which don't exist in the running version.
+    pub fn plugin_context(&self) -> PluginContext {
+        let rec = raw::reaper_plugin_info_t {
+            caller_version: raw::REAPER_PLUGIN_VERSION as c_int,
+            hwnd_main: null_mut(),
+            Register: Some(plugin_register),
+            GetFunc: Some(get_func),
+        };
+        unsafe {
+            PluginContext::from_extension_plugin(
+                null_mut(),
+                rec,
+                StaticExtensionPluginContext::default(),
+            )
+            .expect("couldn't create plug-in context from fake plug-in info")
+        }
+    }
+
+    /// Returns the names of all REAPER functions which are faked.
+    pub fn supported_functions(&self) -> &'static [&'static str] {
+        FAKE_FUNCTION_NAMES
+    }
+
+    /// Sets the version string returned by `GetAppVersion`, e.g. `6.80/linux-x86_64`.
+    pub fn set_app_version(&self, version: &str) {
+        let version = CString::new(version).expect("version contains nul byte");
+        with_model(|m| m.app_version = version);
+    }
+
+    /// Sets the path returned by `GetResourcePath`.
+    pub fn set_resource_path(&self, path: &str) {
+        let path = CString::new(path).expect("path contains nul byte");
+        with_model(|m| m.resource_path = path);
+    }
+
+    /// Makes an FX with the given name and parameters available to `TrackFX_AddByName`.
+    ///
+    /// All parameters have the normalized value 0.0 when the FX is added.
+    pub fn register_fx(&self, name: &str, parameter_names: &[&str]) {
+        let fx = InstalledFx {
+            name: name.to_string(),
+            parameter_names: parameter_names.iter().map(|n| n.to_string()).collect(),
+        };
+        with_model(|m| m.installed_fxs.push(fx));
+    }
+
+    /// Returns everything which has been written to the console via `ShowConsoleMsg`.
+    pub fn console_output(&self) -> String {
+        with_model(|m| m.console_output.clone())
+    }
+
+    /// Returns the names of the things which are currently registered via `plugin_register`
+    /// (e.g. `"hookcommand"` or `"csurf_inst"`), in the order of registration.
+    pub fn registrations(&self) -> Vec<String> {
+        with_model(|m| m.registrations.iter().map(|r| r.name.clone()).collect())
+    }
+
+    /// Replaces the project with an empty one.
+    ///
+    /// Everything else (e.g. registrations and registered FX) is kept. Useful for starting each
+    /// test with a clean project when using [`with_shared_mock()`].
+    pub fn reset_project(&self) {
+        with_model(|m| m.reset_project());
+    }
+
+    /// Returns the descriptions of all undo points in the project, oldest first.
+    ///
+    /// Undo points which have been undone are not included.
+    pub fn undo_history(&self) -> Vec<String> {
+        with_model(|m| m.project.undo_descriptions())
+    }
+}
+
+impl Drop for MockReaper {
+    fn drop(&mut self) {
+        // Take the model out first so that it's not dropped while borrowed
+        let model = MODEL.with(|model| model.borrow_mut().take());
+        drop(model);
+    }
+}
+
+/// Executes the given function on the thread of a mock which is shared by the whole test binary.
+///
+/// The high-level `reaper_high::Reaper` is a process-wide singleton which can be set up only once
+/// and only be used on the thread which set it up. Tests which use it can't create their own
+/// [`MockReaper`] because `cargo test` runs each test on its own thread and the mock state is
+/// thread-local. This function solves that: The first call spawns a dedicated thread with a mock
+/// which lives until the test binary exits. All functions are executed on that thread, one after
+/// the other, so tests using it are serialized even if `cargo test` runs them in parallel.
+///
+/// The mock state is shared between all calls, so set up the high-level `Reaper` just once (e.g.
+/// using `std::sync::Once`) and call [`MockReaper::reset_project()`] at the beginning of each test.
+/// Panics are propagated to the caller.
+///
+/// [`MockReaper::reset_project()`]: struct.MockReaper.html#method.reset_project
+pub fn with_shared_mock<R, F>(f: F) -> R
+where
+    F: FnOnce(&MockReaper) -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let task_sender = SHARED_MOCK_TASK_SENDER
+        .get_or_init(|| Mutex::new(spawn_shared_mock_thread()))
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    let (result_sender, result_receiver) = mpsc::channel();
+    let task: SharedMockTask = Box::new(move |mock| {
+        let result = catch_unwind(AssertUnwindSafe(|| f(mock)));
+        let _ = result_sender.send(result);
+    });
+    task_sender
+        .send(task)
+        .expect("shared mock thread has terminated");
+    match result_receiver
+        .recv()
+        .expect("shared mock thread has terminated")
+    {
+        Ok(result) => result,
+        Err(panic_payload) => resume_unwind(panic_payload),
+    }
+}
+
+fn spawn_shared_mock_thread() -> mpsc::Sender<SharedMockTask> {
+    let (task_sender, task_receiver) = mpsc::channel::<SharedMockTask>();
+    thread::Builder::new()
+        .name("reaper-mock".to_string())
+        .spawn(move || {
+            let mock = MockReaper::new();
+            for task in task_receiver {
+                task(&mock);
+            }
+        })
+        .expect("couldn't spawn shared mock thread");
+    task_sender
+}
+
+extern "C" fn get_func(name: *const c_char) -> *mut c_void {
+    if name.is_null() {
+        return null_mut();
+    }
+    let name = unsafe { CStr::from_ptr(name) };
+    match name.to_str() {
+        Ok(name) => get_fake_function(name),
+        Err(_) => null_mut(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reaper_high::ActionKind;
+    use reaper_medium::{
+        AddFxBehavior, ProjectContext::CurrentProject, ReaperSession, SendTarget,
+        TrackDefaultsBehavior, TrackFxChainType, TrackFxLocation, UndoScope,
+    };
+    use std::sync::Once;
+
+    #[test]
+    fn medium_api() {
+        // Given
+        let mock = MockReaper::new();
+        mock.register_fx("VST: ReaEQ (Cockos)", &["Gain", "Frequency"]);
+        let session = ReaperSession::load(mock.plugin_context());
+        let reaper = session.reaper();
+        // When
+        reaper.insert_track_at_index(0, TrackDefaultsBehavior::OmitDefaultEnvAndFx);
+        reaper.insert_track_at_index(1, TrackDefaultsBehavior::OmitDefaultEnvAndFx);
+        let first = reaper.get_track(CurrentProject, 0).unwrap();
+        let second = reaper.get_track(CurrentProject, 1).unwrap();
+        reaper.undo_begin_block_2(CurrentProject);
+        unsafe {
+            reaper.get_set_media_track_info_set_name(first, "Drums");
+            reaper
+                .track_fx_add_by_name_add(
+                    first,
+                    "ReaEQ",
+                    TrackFxChainType::NormalFxChain,
+                    AddFxBehavior::AlwaysAdd,
+                )
+                .unwrap();
+            reaper
+                .create_track_send(first, SendTarget::OtherTrack(second))
+                .unwrap();
+        }
+        reaper.undo_end_block_2(CurrentProject, "Set up drums", UndoScope::All);
+        // Then
+        assert_eq!(reaper.count_tracks(CurrentProject), 2);
+        unsafe {
+            let name = reaper
+                .get_set_media_track_info_get_name(first, |n| n.to_owned())
+                .unwrap();
+            assert_eq!(name.to_str(), "Drums");
+            assert_eq!(reaper.track_fx_get_count(first), 1);
+            let fx_name = reaper
+                .track_fx_get_fx_name(first, TrackFxLocation::NormalFxChain(0), 64)
+                .unwrap();
+            assert_eq!(fx_name.to_str(), "VST: ReaEQ (Cockos)");
+            assert_eq!(
+                reaper.track_fx_get_num_params(first, TrackFxLocation::NormalFxChain(0)),
+                2
+            );
+        }
+        assert_eq!(mock.undo_history(), vec!["Set up drums".to_string()]);
+        assert!(reaper.undo_do_undo_2(CurrentProject));
+        assert!(mock.undo_history().is_empty());
+    }
+
+    /// Sets up the high-level API once for the whole test binary.
+    fn setup_high_level_reaper(mock: &MockReaper) {
+        static SETUP: Once = Once::new();
+        SETUP.call_once(|| {
+            reaper_high::Reaper::load(mock.plugin_context()).setup();
+            let reaper = reaper_high::Reaper::get();
+            reaper.wake_up().unwrap();
+            reaper.register_action(
+                "reaperMockTest",
+                "reaper-mock test action",
+                || {},
+                ActionKind::NotToggleable,
+            );
+        });
+    }
+
+    #[test]
+    fn high_api() {
+        with_shared_mock(|mock| {
+            // Given
+            mock.reset_project();
+            setup_high_level_reaper(mock);
+            let project = reaper_high::Reaper::get().current_project();
+            // When
+            let track = project.add_track().unwrap();
+            track.set_name("Drums");
+            // Then
+            assert_eq!(project.track_count(), 1);
+            assert_eq!(track.name().unwrap().to_str(), "Drums");
+            let registrations = mock.registrations();
+            for name in [
+                "csurf_inst",
+                "hookcommand",
+                "toggleaction",
+                "command_id",
+                "gaccel",
+            ] {
+                assert!(
+                    registrations.iter().any(|r| r == name),
+                    "{} not registered",
+                    name
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn shared_mock_propagates_panics() {
+        // When
+        let result = catch_unwind(|| with_shared_mock(|_| panic!("expected panic")));
+        let project_count = with_shared_mock(|mock| {
+            mock.reset_project();
+            setup_high_level_reaper(mock);
+            reaper_high::Reaper::get().projects().count()
+        });
+        // Then
+        assert!(result.is_err());
+        assert_eq!(project_count, 1);
+    }
+}
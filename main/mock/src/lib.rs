@@ -0,0 +1,41 @@
+//! An in-process fake REAPER host for unit-testing extensions without running REAPER.
+//!
+//! [`MockReaper`] provides a plug-in context whose function provider hands out fake
+//! implementations of a subset of the REAPER API. They operate on an in-memory model of one
+//! project with tracks, FX, sends, markers, tempo and an undo history. This is enough to
+//! exercise a lot of extension logic in plain `cargo test` runs.
+//!
+//! # Example
+//!
+//! ```
+//! use reaper_medium::{ProjectContext::CurrentProject, ReaperSession, TrackDefaultsBehavior};
+//! use reaper_mock::MockReaper;
+//!
+//! let mock = MockReaper::new();
+//! let session = ReaperSession::load(mock.plugin_context());
+//! let reaper = session.reaper();
+//! reaper.insert_track_at_index(0, TrackDefaultsBehavior::OmitDefaultEnvAndFx);
+//! assert_eq!(reaper.count_tracks(CurrentProject), 1);
+//! ```
+//!
+//! # Limitations
+//!
+//! - The model is thread-local. All REAPER functions must be called on the thread which created
+//!   the mock.
+//! - Functions which are not faked are not available, exactly like functions which don't exist
+//!   in older REAPER versions. Calling them panics in the low-level API. See
+//!   [`MockReaper::supported_functions()`].
+//! - Things registered via `plugin_register` (e.g. control surfaces or actions) are recorded but
+//!   never invoked.
+//! - The high-level `Reaper` is a process-wide singleton which is bound to the thread that set it
+//!   up. Tests which use it must run on the thread of the shared mock, see [`with_shared_mock()`].
+//!
+//! [`MockReaper`]: struct.MockReaper.html
+//! [`with_shared_mock()`]: fn.with_shared_mock.html
+//! [`MockReaper::supported_functions()`]: struct.MockReaper.html#method.supported_functions
+mod functions;
+mod guid;
+mod host;
+mod model;
+
+pub use host::*;
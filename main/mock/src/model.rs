@@ -0,0 +1,967 @@
+use reaper_low::raw::{ReaProject, GUID};
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_void;
+
+/// Identifies a track by the address of its data, which is also its `MediaTrack` pointer.
+pub(crate) type TrackId = usize;
+
+/// Offset which REAPER adds to FX indexes in order to address the input FX chain.
+pub(crate) const INPUT_FX_OFFSET: i32 = 0x1000000;
+
+/// The complete state of the fake REAPER instance.
+#[derive(Debug)]
+pub(crate) struct Model {
+    pub app_version: CString,
+    pub resource_path: CString,
+    pub console_output: String,
+    pub project: Box<ProjectData>,
+    pub installed_fxs: Vec<InstalledFx>,
+    /// Things registered via `plugin_register`.
+    pub registrations: Vec<Registration>,
+    next_guid: u32,
+}
+
+/// An FX which can be added to FX chains.
+#[derive(Clone, Debug)]
+pub(crate) struct InstalledFx {
+    pub name: String,
+    pub parameter_names: Vec<String>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) struct Registration {
+    pub name: String,
+    pub info_struct: usize,
+}
+
+impl Model {
+    pub fn new() -> Model {
+        let mut next_guid = 0;
+        let master_guid = generate_guid(&mut next_guid);
+        Model {
+            app_version: CString::new("6.80/linux-x86_64").unwrap(),
+            resource_path: CString::new("").unwrap(),
+            console_output: String::new(),
+            project: Box::new(ProjectData::new(master_guid)),
+            installed_fxs: vec![],
+            registrations: vec![],
+            next_guid,
+        }
+    }
+
+    /// Returns a new unique GUID.
+    ///
+    /// GUIDs are generated deterministically, which makes test failures reproducible.
+    pub fn generate_guid(&mut self) -> GUID {
+        generate_guid(&mut self.next_guid)
+    }
+
+    pub fn project_ptr(&self) -> *mut ReaProject {
+        &*self.project as *const ProjectData as *mut ReaProject
+    }
+
+    /// Returns the given project, `null` meaning the current project.
+    pub fn project_mut(&mut self, project: *mut ReaProject) -> Option<&mut ProjectData> {
+        if project.is_null() || project == self.project_ptr() {
+            Some(&mut self.project)
+        } else {
+            None
+        }
+    }
+
+    pub fn find_installed_fx(&self, query: &str) -> Option<&InstalledFx> {
+        self.installed_fxs
+            .iter()
+            .find(|fx| fx.name.eq_ignore_ascii_case(query))
+            .or_else(|| {
+                self.installed_fxs
+                    .iter()
+                    .find(|fx| fx_name_matches(&fx.name, query))
+            })
+    }
+
+    /// Adds an FX to the given chain and returns its index.
+    pub fn add_fx(
+        &mut self,
+        track: TrackId,
+        input_chain: bool,
+        query: &str,
+        index: Option<usize>,
+    ) -> Option<usize> {
+        let installed_fx = self.find_installed_fx(query)?.clone();
+        let guid = self.generate_guid();
+        let chain = self.project.track_mut(track)?.fx_chain_mut(input_chain);
+        let index = index.unwrap_or(chain.len()).min(chain.len());
+        chain.insert(index, FxData::new(&installed_fx, guid));
+        Some(index)
+    }
+
+    pub fn reset_project(&mut self) {
+        let master_guid = self.generate_guid();
+        self.project = Box::new(ProjectData::new(master_guid));
+    }
+
+    pub fn insert_track(&mut self, index: usize) -> TrackId {
+        let guid = self.generate_guid();
+        self.project.insert_track(index, guid)
+    }
+}
+
+fn generate_guid(next_guid: &mut u32) -> GUID {
+    *next_guid += 1;
+    GUID {
+        Data1: *next_guid,
+        Data2: 0x6d6f,
+        Data3: 0x636b,
+        Data4: [0x80, 0, 0, 0, 0, 0, 0, 1],
+    }
+}
+
+/// Returns whether the given FX name matches the given query in the lenient way REAPER does it.
+pub(crate) fn fx_name_matches(name: &str, query: &str) -> bool {
+    name.to_lowercase().contains(&query.to_lowercase())
+}
+
+/// Value of a numeric attribute, typed according to the prefix of its key (e.g. `D_VOL`).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum AttributeValue {
+    Bool(bool),
+    Char(i8),
+    Int(i32),
+    Float(f32),
+    Double(f64),
+}
+
+impl AttributeValue {
+    /// Creates a value of the type implied by the given key.
+    ///
+    /// Returns `None` if the key doesn't denote a numeric attribute.
+    pub fn new(key: &str, value: f64) -> Option<AttributeValue> {
+        use AttributeValue::*;
+        let (prefix, _) = key.split_once('_')?;
+        let v = match prefix {
+            "B" => Bool(value != 0.0),
+            "C" => Char(value as i8),
+            "I" => Int(value as i32),
+            "F" => Float(value as f32),
+            "D" => Double(value),
+            _ => return None,
+        };
+        Some(v)
+    }
+
+    pub fn get(self) -> f64 {
+        use AttributeValue::*;
+        match self {
+            Bool(v) => v as i32 as f64,
+            Char(v) => v as f64,
+            Int(v) => v as f64,
+            Float(v) => v as f64,
+            Double(v) => v,
+        }
+    }
+
+    pub fn set(&mut self, value: f64) {
+        use AttributeValue::*;
+        match self {
+            Bool(v) => *v = value != 0.0,
+            Char(v) => *v = value as i8,
+            Int(v) => *v = value as i32,
+            Float(v) => *v = value as f32,
+            Double(v) => *v = value,
+        }
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut c_void {
+        use AttributeValue::*;
+        match self {
+            Bool(v) => v as *mut bool as _,
+            Char(v) => v as *mut i8 as _,
+            Int(v) => v as *mut i32 as _,
+            Float(v) => v as *mut f32 as _,
+            Double(v) => v as *mut f64 as _,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The given pointer must point to a value of the type of this attribute.
+    pub unsafe fn set_from_ptr(&mut self, ptr: *const c_void) {
+        use AttributeValue::*;
+        match self {
+            Bool(v) => *v = *(ptr as *const bool),
+            Char(v) => *v = *(ptr as *const i8),
+            Int(v) => *v = *(ptr as *const i32),
+            Float(v) => *v = *(ptr as *const f32),
+            Double(v) => *v = *(ptr as *const f64),
+        }
+    }
+}
+
+/// Numeric attributes of a track or send.
+#[derive(Clone, Debug)]
+pub(crate) struct Attributes {
+    // Boxed so that pointers handed out to the caller stay valid when the map grows.
+    values: HashMap<String, Box<AttributeValue>>,
+    defaults: fn(&str) -> f64,
+}
+
+impl Attributes {
+    pub fn new(defaults: fn(&str) -> f64) -> Attributes {
+        Attributes {
+            values: Default::default(),
+            defaults,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<f64> {
+        match self.values.get(key) {
+            Some(v) => Some(v.get()),
+            None => AttributeValue::new(key, (self.defaults)(key)).map(AttributeValue::get),
+        }
+    }
+
+    /// Returns `false` if the key doesn't denote a numeric attribute.
+    pub fn set(&mut self, key: &str, value: f64) -> bool {
+        match self.value_mut(key) {
+            None => false,
+            Some(v) => {
+                v.set(value);
+                true
+            }
+        }
+    }
+
+    pub fn value_mut(&mut self, key: &str) -> Option<&mut AttributeValue> {
+        if !self.values.contains_key(key) {
+            let value = AttributeValue::new(key, (self.defaults)(key))?;
+            self.values.insert(key.to_string(), Box::new(value));
+        }
+        self.values.get_mut(key).map(|v| v.as_mut())
+    }
+}
+
+fn track_attribute_default(key: &str) -> f64 {
+    match key {
+        "D_VOL" | "D_WIDTH" | "I_FXEN" | "B_SHOWINTCP" | "B_SHOWINMIXER" | "B_MAINSEND" => 1.0,
+        "I_NCHAN" => 2.0,
+        "D_PANLAW" => -1.0,
+        _ => 0.0,
+    }
+}
+
+fn send_attribute_default(key: &str) -> f64 {
+    match key {
+        "D_VOL" => 1.0,
+        _ => 0.0,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct TrackData {
+    pub guid: GUID,
+    pub attributes: Attributes,
+    /// String attributes such as `P_NAME` or `P_EXT:xyz`.
+    pub strings: HashMap<String, CString>,
+    pub fx_chain: Vec<FxData>,
+    pub input_fx_chain: Vec<FxData>,
+    pub sends: Vec<SendData>,
+    pub hardware_outputs: Vec<SendData>,
+}
+
+impl TrackData {
+    fn new(guid: GUID) -> TrackData {
+        TrackData {
+            guid,
+            attributes: Attributes::new(track_attribute_default),
+            strings: Default::default(),
+            fx_chain: vec![],
+            input_fx_chain: vec![],
+            sends: vec![],
+            hardware_outputs: vec![],
+        }
+    }
+
+    pub fn fx_chain_mut(&mut self, input_chain: bool) -> &mut Vec<FxData> {
+        if input_chain {
+            &mut self.input_fx_chain
+        } else {
+            &mut self.fx_chain
+        }
+    }
+
+    /// Resolves an FX index as passed to the `TrackFX_` functions.
+    pub fn fx_mut(&mut self, fx_index: i32) -> Option<&mut FxData> {
+        let (input_chain, index) = decode_fx_index(fx_index)?;
+        self.fx_chain_mut(input_chain).get_mut(index)
+    }
+
+    pub fn remove_fx(&mut self, fx_index: i32) -> bool {
+        let (input_chain, index) = match decode_fx_index(fx_index) {
+            None => return false,
+            Some(r) => r,
+        };
+        let chain = self.fx_chain_mut(input_chain);
+        if index >= chain.len() {
+            return false;
+        }
+        chain.remove(index);
+        true
+    }
+}
+
+/// Returns whether the index addresses the input FX chain and the index within the chain.
+pub(crate) fn decode_fx_index(fx_index: i32) -> Option<(bool, usize)> {
+    if fx_index >= INPUT_FX_OFFSET {
+        Some((true, (fx_index - INPUT_FX_OFFSET) as usize))
+    } else if fx_index >= 0 {
+        Some((false, fx_index as usize))
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct FxData {
+    pub name: CString,
+    pub guid: GUID,
+    pub enabled: bool,
+    pub parameters: Vec<FxParameterData>,
+}
+
+impl FxData {
+    fn new(installed_fx: &InstalledFx, guid: GUID) -> FxData {
+        FxData {
+            name: CString::new(installed_fx.name.as_str()).unwrap_or_default(),
+            guid,
+            enabled: true,
+            parameters: installed_fx
+                .parameter_names
+                .iter()
+                .map(|name| FxParameterData {
+                    name: CString::new(name.as_str()).unwrap_or_default(),
+                    value: 0.0,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct FxParameterData {
+    pub name: CString,
+    /// Normalized value.
+    pub value: f64,
+}
+
+/// A send to another track or to a hardware output (stored at the source track).
+#[derive(Clone, Debug)]
+pub(crate) struct SendData {
+    /// `None` for hardware outputs.
+    pub destination: Option<TrackId>,
+    pub attributes: Attributes,
+}
+
+impl SendData {
+    pub fn new(destination: Option<TrackId>) -> SendData {
+        SendData {
+            destination,
+            attributes: Attributes::new(send_attribute_default),
+        }
+    }
+}
+
+/// Kind of route as denoted by the `category` parameter of the send functions.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum RouteCategory {
+    Receive,
+    Send,
+    HardwareOutput,
+}
+
+impl RouteCategory {
+    pub fn from_raw(category: i32) -> RouteCategory {
+        use RouteCategory::*;
+        match category {
+            c if c < 0 => Receive,
+            0 => Send,
+            _ => HardwareOutput,
+        }
+    }
+}
+
+/// A resolved route: the track which owns the send data and the index within its sends or
+/// hardware outputs.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) struct RouteLocation {
+    pub source: TrackId,
+    pub is_hardware_output: bool,
+    pub index: usize,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Marker {
+    pub is_region: bool,
+    pub position: f64,
+    pub region_end: f64,
+    pub name: CString,
+    pub index_number: i32,
+    pub color: i32,
+}
+
+// Tracks are boxed because their addresses serve as `MediaTrack` pointers and must be stable.
+#[allow(clippy::vec_box)]
+#[derive(Debug)]
+pub(crate) struct ProjectData {
+    pub master: Box<TrackData>,
+    tracks: Vec<Box<TrackData>>,
+    /// Removed tracks are kept alive so their addresses are never reused and undo can bring them
+    /// back at the same address.
+    removed_tracks: Vec<Box<TrackData>>,
+    pub markers: Vec<Marker>,
+    pub tempo: f64,
+    pub beats_per_measure: f64,
+    pub edit_cursor_position: f64,
+    pub time_selection: (f64, f64),
+    pub loop_points: (f64, f64),
+    pub is_dirty: bool,
+    undo: UndoHistory,
+}
+
+#[derive(Debug)]
+struct UndoHistory {
+    /// The first state is the initial state of the project.
+    states: Vec<UndoState>,
+    /// Index of the state which the project is currently in.
+    position: usize,
+    block_depth: u32,
+}
+
+#[derive(Debug)]
+struct UndoState {
+    description: CString,
+    snapshot: ProjectSnapshot,
+}
+
+/// Everything which is affected by undo/redo.
+#[derive(Clone, Debug)]
+struct ProjectSnapshot {
+    master: TrackData,
+    tracks: Vec<(TrackId, TrackData)>,
+    markers: Vec<Marker>,
+    tempo: f64,
+    beats_per_measure: f64,
+}
+
+impl ProjectData {
+    fn new(master_guid: GUID) -> ProjectData {
+        let mut project = ProjectData {
+            master: Box::new(TrackData::new(master_guid)),
+            tracks: vec![],
+            removed_tracks: vec![],
+            markers: vec![],
+            tempo: 120.0,
+            beats_per_measure: 4.0,
+            edit_cursor_position: 0.0,
+            time_selection: (0.0, 0.0),
+            loop_points: (0.0, 0.0),
+            is_dirty: false,
+            undo: UndoHistory {
+                states: vec![],
+                position: 0,
+                block_depth: 0,
+            },
+        };
+        let initial_state = UndoState {
+            description: Default::default(),
+            snapshot: project.snapshot(),
+        };
+        project.undo.states.push(initial_state);
+        project
+    }
+
+    fn id_of(track: &TrackData) -> TrackId {
+        track as *const TrackData as TrackId
+    }
+
+    pub fn master_id(&self) -> TrackId {
+        Self::id_of(&self.master)
+    }
+
+    pub fn track_count(&self) -> usize {
+        self.tracks.len()
+    }
+
+    /// Returns the IDs of all tracks excluding the master track.
+    pub fn track_ids(&self) -> Vec<TrackId> {
+        self.tracks.iter().map(|t| Self::id_of(t)).collect()
+    }
+
+    pub fn track_id(&self, index: usize) -> Option<TrackId> {
+        self.tracks.get(index).map(|t| Self::id_of(t))
+    }
+
+    /// Returns the index of the given track, `None` if it's the master track or doesn't exist.
+    pub fn track_index(&self, id: TrackId) -> Option<usize> {
+        self.tracks.iter().position(|t| Self::id_of(t) == id)
+    }
+
+    /// Returns whether the given track is the master track or a track of this project.
+    pub fn contains_track(&self, id: TrackId) -> bool {
+        id == self.master_id() || self.track_index(id).is_some()
+    }
+
+    pub fn track_mut(&mut self, id: TrackId) -> Option<&mut TrackData> {
+        if id == self.master_id() {
+            return Some(&mut self.master);
+        }
+        self.tracks
+            .iter_mut()
+            .find(|t| Self::id_of(t) == id)
+            .map(|t| t.as_mut())
+    }
+
+    pub fn insert_track(&mut self, index: usize, guid: GUID) -> TrackId {
+        let track = Box::new(TrackData::new(guid));
+        let id = Self::id_of(&track);
+        let index = index.min(self.tracks.len());
+        self.tracks.insert(index, track);
+        id
+    }
+
+    pub fn remove_track(&mut self, id: TrackId) -> bool {
+        let index = match self.track_index(id) {
+            None => return false,
+            Some(i) => i,
+        };
+        let track = self.tracks.remove(index);
+        self.removed_tracks.push(track);
+        self.master.sends.retain(|s| s.destination != Some(id));
+        for t in &mut self.tracks {
+            t.sends.retain(|s| s.destination != Some(id));
+        }
+        true
+    }
+
+    /// Determines the parent track by evaluating the folder depth changes of preceding tracks.
+    pub fn parent_track(&self, id: TrackId) -> Option<TrackId> {
+        let index = self.track_index(id)?;
+        let mut folder_stack = vec![];
+        for t in &self.tracks[..index] {
+            let depth_change = t.attributes.get("I_FOLDERDEPTH").unwrap_or(0.0) as i32;
+            if depth_change > 0 {
+                folder_stack.push(Self::id_of(t));
+            } else {
+                for _ in 0..-depth_change {
+                    folder_stack.pop();
+                }
+            }
+        }
+        folder_stack.last().copied()
+    }
+
+    /// Returns the tracks in the given selection state, the master track first (if desired).
+    pub fn selected_track_ids(&self, want_master: bool) -> Vec<TrackId> {
+        let master = if want_master {
+            Some(&self.master)
+        } else {
+            None
+        };
+        master
+            .into_iter()
+            .chain(self.tracks.iter())
+            .filter(|t| t.attributes.get("I_SELECTED").unwrap_or(0.0) != 0.0)
+            .map(|t| Self::id_of(t))
+            .collect()
+    }
+
+    /// Returns the locations of all sends which have the given track as destination.
+    fn receive_locations(&self, destination: TrackId) -> Vec<RouteLocation> {
+        std::iter::once(&self.master)
+            .chain(self.tracks.iter())
+            .flat_map(|t| {
+                t.sends
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, s)| s.destination == Some(destination))
+                    .map(move |(i, _)| RouteLocation {
+                        source: Self::id_of(t),
+                        is_hardware_output: false,
+                        index: i,
+                    })
+            })
+            .collect()
+    }
+
+    pub fn route_count(&mut self, track: TrackId, category: RouteCategory) -> usize {
+        use RouteCategory::*;
+        match category {
+            Receive => self.receive_locations(track).len(),
+            Send => self.track_mut(track).map(|t| t.sends.len()).unwrap_or(0),
+            HardwareOutput => self
+                .track_mut(track)
+                .map(|t| t.hardware_outputs.len())
+                .unwrap_or(0),
+        }
+    }
+
+    pub fn route_location(
+        &mut self,
+        track: TrackId,
+        category: RouteCategory,
+        index: usize,
+    ) -> Option<RouteLocation> {
+        use RouteCategory::*;
+        if category == Receive {
+            return self.receive_locations(track).get(index).copied();
+        }
+        let is_hardware_output = category == HardwareOutput;
+        let t = self.track_mut(track)?;
+        let count = if is_hardware_output {
+            t.hardware_outputs.len()
+        } else {
+            t.sends.len()
+        };
+        if index >= count {
+            return None;
+        }
+        Some(RouteLocation {
+            source: track,
+            is_hardware_output,
+            index,
+        })
+    }
+
+    pub fn route_mut(&mut self, location: RouteLocation) -> Option<&mut SendData> {
+        let t = self.track_mut(location.source)?;
+        let routes = if location.is_hardware_output {
+            &mut t.hardware_outputs
+        } else {
+            &mut t.sends
+        };
+        routes.get_mut(location.index)
+    }
+
+    pub fn remove_route(&mut self, location: RouteLocation) -> bool {
+        let t = match self.track_mut(location.source) {
+            None => return false,
+            Some(t) => t,
+        };
+        let routes = if location.is_hardware_output {
+            &mut t.hardware_outputs
+        } else {
+            &mut t.sends
+        };
+        if location.index >= routes.len() {
+            return false;
+        }
+        routes.remove(location.index);
+        true
+    }
+
+    /// Adds a marker or region and returns its index number.
+    pub fn add_marker(
+        &mut self,
+        is_region: bool,
+        position: f64,
+        region_end: f64,
+        name: &CStr,
+        wanted_index_number: i32,
+        color: i32,
+    ) -> i32 {
+        let numbers_in_use: Vec<_> = self
+            .markers
+            .iter()
+            .filter(|m| m.is_region == is_region)
+            .map(|m| m.index_number)
+            .collect();
+        let index_number =
+            if wanted_index_number >= 0 && !numbers_in_use.contains(&wanted_index_number) {
+                wanted_index_number
+            } else {
+                numbers_in_use.iter().max().map(|n| n + 1).unwrap_or(1)
+            };
+        let marker = Marker {
+            is_region,
+            position,
+            region_end: if is_region { region_end } else { 0.0 },
+            name: name.to_owned(),
+            index_number,
+            color,
+        };
+        let index = self
+            .markers
+            .iter()
+            .position(|m| m.position > position)
+            .unwrap_or(self.markers.len());
+        self.markers.insert(index, marker);
+        index_number
+    }
+
+    pub fn remove_marker(&mut self, index_number: i32, is_region: bool) -> bool {
+        let count_before = self.markers.len();
+        self.markers
+            .retain(|m| m.is_region != is_region || m.index_number != index_number);
+        self.markers.len() != count_before
+    }
+
+    pub fn begin_undo_block(&mut self) {
+        self.undo.block_depth += 1;
+    }
+
+    pub fn end_undo_block(&mut self, description: &CStr) {
+        if self.undo.block_depth == 0 {
+            return;
+        }
+        self.undo.block_depth -= 1;
+        if self.undo.block_depth == 0 {
+            self.push_undo_state(description);
+        }
+    }
+
+    /// Records an undo point unless an undo block is open.
+    pub fn add_undo_point(&mut self, description: &CStr) {
+        if self.undo.block_depth == 0 {
+            self.push_undo_state(description);
+        }
+    }
+
+    fn push_undo_state(&mut self, description: &CStr) {
+        let state = UndoState {
+            description: description.to_owned(),
+            snapshot: self.snapshot(),
+        };
+        self.undo.states.truncate(self.undo.position + 1);
+        self.undo.states.push(state);
+        self.undo.position += 1;
+        self.is_dirty = true;
+    }
+
+    pub fn undo_description(&self) -> Option<&CStr> {
+        if self.undo.position == 0 {
+            return None;
+        }
+        Some(&self.undo.states[self.undo.position].description)
+    }
+
+    pub fn redo_description(&self) -> Option<&CStr> {
+        let state = self.undo.states.get(self.undo.position + 1)?;
+        Some(&state.description)
+    }
+
+    /// Returns the descriptions of all undo points, oldest first.
+    pub fn undo_descriptions(&self) -> Vec<String> {
+        self.undo.states[1..=self.undo.position]
+            .iter()
+            .map(|s| s.description.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        if self.undo.position == 0 {
+            return false;
+        }
+        self.undo.position -= 1;
+        self.restore_undo_state();
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        if self.undo.position + 1 >= self.undo.states.len() {
+            return false;
+        }
+        self.undo.position += 1;
+        self.restore_undo_state();
+        true
+    }
+
+    fn restore_undo_state(&mut self) {
+        let snapshot = self.undo.states[self.undo.position].snapshot.clone();
+        self.restore(snapshot);
+        self.is_dirty = true;
+    }
+
+    fn snapshot(&self) -> ProjectSnapshot {
+        ProjectSnapshot {
+            master: (*self.master).clone(),
+            tracks: self
+                .tracks
+                .iter()
+                .map(|t| (Self::id_of(t), (**t).clone()))
+                .collect(),
+            markers: self.markers.clone(),
+            tempo: self.tempo,
+            beats_per_measure: self.beats_per_measure,
+        }
+    }
+
+    fn restore(&mut self, snapshot: ProjectSnapshot) {
+        let mut all_tracks: Vec<_> = self
+            .tracks
+            .drain(..)
+            .chain(self.removed_tracks.drain(..))
+            .collect();
+        for (id, data) in snapshot.tracks {
+            let index = all_tracks
+                .iter()
+                .position(|t| Self::id_of(t) == id)
+                .expect("track data is never deallocated");
+            let mut track = all_tracks.swap_remove(index);
+            *track = data;
+            self.tracks.push(track);
+        }
+        self.removed_tracks = all_tracks;
+        *self.master = snapshot.master;
+        self.markers = snapshot.markers;
+        self.tempo = snapshot.tempo;
+        self.beats_per_measure = snapshot.beats_per_measure;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> ProjectData {
+        ProjectData::new(GUID {
+            Data1: 1,
+            Data2: 0,
+            Data3: 0,
+            Data4: [0; 8],
+        })
+    }
+
+    fn guid(n: u32) -> GUID {
+        GUID {
+            Data1: n,
+            Data2: 0,
+            Data3: 0,
+            Data4: [0; 8],
+        }
+    }
+
+    #[test]
+    fn attributes() {
+        // Given
+        let mut attributes = Attributes::new(track_attribute_default);
+        // When
+        let set_successful = attributes.set("I_SOLO", 2.7);
+        // Then
+        assert!(set_successful);
+        assert_eq!(attributes.get("I_SOLO"), Some(2.0));
+        assert_eq!(attributes.get("D_VOL"), Some(1.0));
+        assert_eq!(attributes.get("B_MUTE"), Some(0.0));
+        assert_eq!(attributes.get("P_NAME"), None);
+        assert!(!attributes.set("GUID", 1.0));
+        let ptr = attributes.value_mut("D_PAN").unwrap().as_mut_ptr();
+        unsafe {
+            *(ptr as *mut f64) = -0.5;
+        }
+        assert_eq!(attributes.get("D_PAN"), Some(-0.5));
+    }
+
+    #[test]
+    fn parent_track() {
+        // Given
+        let mut project = project();
+        let folder = project.insert_track(0, guid(2));
+        let child = project.insert_track(1, guid(3));
+        let after = project.insert_track(2, guid(4));
+        // When
+        project
+            .track_mut(folder)
+            .unwrap()
+            .attributes
+            .set("I_FOLDERDEPTH", 1.0);
+        project
+            .track_mut(child)
+            .unwrap()
+            .attributes
+            .set("I_FOLDERDEPTH", -1.0);
+        // Then
+        assert_eq!(project.parent_track(folder), None);
+        assert_eq!(project.parent_track(child), Some(folder));
+        assert_eq!(project.parent_track(after), None);
+    }
+
+    #[test]
+    fn receives() {
+        // Given
+        let mut project = project();
+        let source = project.insert_track(0, guid(2));
+        let destination = project.insert_track(1, guid(3));
+        project
+            .track_mut(source)
+            .unwrap()
+            .sends
+            .push(SendData::new(Some(destination)));
+        // When
+        let location = project
+            .route_location(destination, RouteCategory::Receive, 0)
+            .unwrap();
+        // Then
+        assert_eq!(project.route_count(destination, RouteCategory::Receive), 1);
+        assert_eq!(location.source, source);
+        project.remove_track(destination);
+        assert_eq!(project.route_count(source, RouteCategory::Send), 0);
+    }
+
+    #[test]
+    fn markers() {
+        // Given
+        let mut project = project();
+        let name = CString::new("Verse").unwrap();
+        // When
+        let first = project.add_marker(false, 10.0, 0.0, &name, -1, 0);
+        let second = project.add_marker(false, 5.0, 0.0, &name, -1, 0);
+        let region = project.add_marker(true, 0.0, 20.0, &name, 7, 0);
+        // Then
+        assert_eq!((first, second, region), (1, 2, 7));
+        assert_eq!(project.markers[0].index_number, region);
+        assert_eq!(project.markers[1].index_number, second);
+        assert!(project.remove_marker(1, false));
+        assert!(!project.remove_marker(1, false));
+        assert_eq!(project.markers.len(), 2);
+    }
+
+    #[test]
+    fn undo_redo() {
+        // Given
+        let mut project = project();
+        let description = CString::new("Add track").unwrap();
+        let track = project.insert_track(0, guid(2));
+        project.add_undo_point(&description);
+        project.begin_undo_block();
+        project
+            .track_mut(track)
+            .unwrap()
+            .attributes
+            .set("B_MUTE", 1.0);
+        project.add_undo_point(&CString::new("Ignored").unwrap());
+        project.tempo = 90.0;
+        project.end_undo_block(&CString::new("Mute and tempo").unwrap());
+        // When
+        let undone = project.undo();
+        // Then
+        assert!(undone);
+        assert_eq!(project.tempo, 120.0);
+        assert_eq!(
+            project.track_mut(track).unwrap().attributes.get("B_MUTE"),
+            Some(0.0)
+        );
+        assert_eq!(project.undo_description(), Some(description.as_c_str()));
+        assert!(project.undo());
+        assert_eq!(project.track_count(), 0);
+        assert!(!project.undo());
+        assert!(project.redo());
+        assert_eq!(project.track_id(0), Some(track));
+        assert!(project.redo());
+        assert_eq!(project.tempo, 90.0);
+        assert!(!project.redo());
+        assert_eq!(
+            project.undo_descriptions(),
+            vec!["Add track".to_string(), "Mute and tempo".to_string()]
+        );
+    }
+}
@@ -1,5 +1,11 @@
 use crate::{EventStreamSubject, ReactiveEvent};
-use reaper_high::{AvailablePanValue, ChangeEvent, Fx, FxParameter, Project, Track, TrackRoute};
+use reaper_high::{
+    AutomationItemsChangedEvent, AvailablePanValue, ChangeEvent, EnvelopeArmChangedEvent,
+    EnvelopePointsChangedEvent, EnvelopeVisibilityChangedEvent, Fx, FxParameter, Item,
+    MarkerAddedEvent, MarkerMovedEvent, MarkerRemovedEvent, MarkerRenamedEvent, Project,
+    RedoPerformedEvent, RegionResizedEvent, Track, TrackRoute, UndoPerformedEvent,
+    UndoPointCreatedEvent,
+};
 use reaper_medium::Pan;
 use rxrust::prelude::*;
 use std::cell::RefCell;
@@ -135,6 +141,55 @@ impl ControlSurfaceRxMiddleware {
                 .borrow_mut()
                 .next(()),
             BookmarksChanged(_) => self.rx.bookmarks_changed.borrow_mut().next(()),
+            MarkerAdded(e) => self.rx.marker_added.borrow_mut().next(e),
+            MarkerRemoved(e) => self.rx.marker_removed.borrow_mut().next(e),
+            MarkerMoved(e) => self.rx.marker_moved.borrow_mut().next(e),
+            MarkerRenamed(e) => self.rx.marker_renamed.borrow_mut().next(e),
+            RegionResized(e) => self.rx.region_resized.borrow_mut().next(e),
+            EnvelopePointsChanged(e) => self.rx.envelope_points_changed.borrow_mut().next(e),
+            EnvelopeVisibilityChanged(e) => {
+                self.rx.envelope_visibility_changed.borrow_mut().next(e)
+            }
+            EnvelopeArmChanged(e) => self.rx.envelope_arm_changed.borrow_mut().next(e),
+            AutomationItemsChanged(e) => self.rx.automation_items_changed.borrow_mut().next(e),
+            UndoPointCreated(e) => self.rx.undo_point_created.borrow_mut().next(e),
+            UndoPerformed(e) => self.rx.undo_performed.borrow_mut().next(e),
+            RedoPerformed(e) => self.rx.redo_performed.borrow_mut().next(e),
+            ProjectLoaded(e) => self.rx.project_loaded.borrow_mut().next(e.project),
+            ProjectSaved(e) => self.rx.project_saved.borrow_mut().next(e.project),
+            ProjectDirtyStateChanged(e) => self
+                .rx
+                .project_dirty_state_changed
+                .borrow_mut()
+                .next((e.project, e.new_value)),
+            ItemAdded(e) => self.rx.item_added.borrow_mut().next(e.item),
+            ItemRemoved(e) => self.rx.item_removed.borrow_mut().next(e.item),
+            ItemMoved(e) => {
+                self.rx.item_moved.borrow_mut().next(e.item);
+                self.rx.item_changed.borrow_mut().next(e.item);
+            }
+            ItemResized(e) => {
+                self.rx.item_resized.borrow_mut().next(e.item);
+                self.rx.item_changed.borrow_mut().next(e.item);
+            }
+            ItemSelectedChanged(e) => {
+                self.rx
+                    .item_selected_changed
+                    .borrow_mut()
+                    .next((e.item, e.new_value));
+                self.rx.item_changed.borrow_mut().next(e.item);
+            }
+            ItemMuteChanged(e) => {
+                self.rx
+                    .item_mute_changed
+                    .borrow_mut()
+                    .next((e.item, e.new_value));
+                self.rx.item_changed.borrow_mut().next(e.item);
+            }
+            ItemActiveTakeChanged(e) => {
+                self.rx.item_active_take_changed.borrow_mut().next(e.item);
+                self.rx.item_changed.borrow_mut().next(e.item);
+            }
             ReceiveCountChanged(e) => self.rx.receive_count_changed.borrow_mut().next(e.track),
             HardwareOutputSendCountChanged(e) => self
                 .rx
@@ -193,6 +248,29 @@ pub struct ControlSurfaceRx {
     pub repeat_state_changed: EventStreamSubject<()>,
     pub project_closed: EventStreamSubject<Project>,
     pub bookmarks_changed: EventStreamSubject<()>,
+    pub marker_added: EventStreamSubject<MarkerAddedEvent>,
+    pub marker_removed: EventStreamSubject<MarkerRemovedEvent>,
+    pub marker_moved: EventStreamSubject<MarkerMovedEvent>,
+    pub marker_renamed: EventStreamSubject<MarkerRenamedEvent>,
+    pub region_resized: EventStreamSubject<RegionResizedEvent>,
+    pub item_added: EventStreamSubject<Item>,
+    pub item_removed: EventStreamSubject<Item>,
+    pub item_changed: EventStreamSubject<Item>,
+    pub item_moved: EventStreamSubject<Item>,
+    pub item_resized: EventStreamSubject<Item>,
+    pub item_selected_changed: EventStreamSubject<(Item, bool)>,
+    pub item_mute_changed: EventStreamSubject<(Item, bool)>,
+    pub item_active_take_changed: EventStreamSubject<Item>,
+    pub envelope_points_changed: EventStreamSubject<EnvelopePointsChangedEvent>,
+    pub envelope_visibility_changed: EventStreamSubject<EnvelopeVisibilityChangedEvent>,
+    pub envelope_arm_changed: EventStreamSubject<EnvelopeArmChangedEvent>,
+    pub automation_items_changed: EventStreamSubject<AutomationItemsChangedEvent>,
+    pub undo_point_created: EventStreamSubject<UndoPointCreatedEvent>,
+    pub undo_performed: EventStreamSubject<UndoPerformedEvent>,
+    pub redo_performed: EventStreamSubject<RedoPerformedEvent>,
+    pub project_loaded: EventStreamSubject<Project>,
+    pub project_saved: EventStreamSubject<Project>,
+    pub project_dirty_state_changed: EventStreamSubject<(Project, bool)>,
 }
 
 impl fmt::Debug for ControlSurfaceRx {
@@ -251,6 +329,29 @@ impl ControlSurfaceRx {
             repeat_state_changed: default(),
             project_closed: default(),
             bookmarks_changed: default(),
+            marker_added: default(),
+            marker_removed: default(),
+            marker_moved: default(),
+            marker_renamed: default(),
+            region_resized: default(),
+            item_added: default(),
+            item_removed: default(),
+            item_changed: default(),
+            item_moved: default(),
+            item_resized: default(),
+            item_selected_changed: default(),
+            item_mute_changed: default(),
+            item_active_take_changed: default(),
+            envelope_points_changed: default(),
+            envelope_visibility_changed: default(),
+            envelope_arm_changed: default(),
+            automation_items_changed: default(),
+            undo_point_created: default(),
+            undo_performed: default(),
+            redo_performed: default(),
+            project_loaded: default(),
+            project_saved: default(),
+            project_dirty_state_changed: default(),
         }
     }
 
@@ -262,10 +363,36 @@ impl ControlSurfaceRx {
         self.global_automation_override_changed.borrow().clone()
     }
 
+    /// Fires whenever something about the markers or regions has changed, additionally to the
+    /// more specific marker and region events.
     pub fn bookmarks_changed(&self) -> ReactiveEvent<()> {
         self.bookmarks_changed.borrow().clone()
     }
 
+    /// Also fires for regions.
+    pub fn marker_added(&self) -> ReactiveEvent<MarkerAddedEvent> {
+        self.marker_added.borrow().clone()
+    }
+
+    /// Also fires for regions.
+    pub fn marker_removed(&self) -> ReactiveEvent<MarkerRemovedEvent> {
+        self.marker_removed.borrow().clone()
+    }
+
+    /// Also fires for regions (if the region start has changed).
+    pub fn marker_moved(&self) -> ReactiveEvent<MarkerMovedEvent> {
+        self.marker_moved.borrow().clone()
+    }
+
+    /// Also fires for regions.
+    pub fn marker_renamed(&self) -> ReactiveEvent<MarkerRenamedEvent> {
+        self.marker_renamed.borrow().clone()
+    }
+
+    pub fn region_resized(&self) -> ReactiveEvent<RegionResizedEvent> {
+        self.region_resized.borrow().clone()
+    }
+
     pub fn fx_opened(&self) -> ReactiveEvent<Fx> {
         self.fx_opened.borrow().clone()
     }
@@ -427,6 +554,91 @@ impl ControlSurfaceRx {
         self.track_route_pan_touched.borrow().clone()
     }
 
+    /// Item events only fire if `ChangeDetectionMiddleware::run()` is called in each main loop
+    /// cycle.
+    pub fn item_added(&self) -> ReactiveEvent<Item> {
+        self.item_added.borrow().clone()
+    }
+
+    pub fn item_removed(&self) -> ReactiveEvent<Item> {
+        self.item_removed.borrow().clone()
+    }
+
+    /// Fires whenever an item has been moved, resized, (un)selected, (un)muted or has switched its
+    /// active take.
+    pub fn item_changed(&self) -> ReactiveEvent<Item> {
+        self.item_changed.borrow().clone()
+    }
+
+    pub fn item_moved(&self) -> ReactiveEvent<Item> {
+        self.item_moved.borrow().clone()
+    }
+
+    pub fn item_resized(&self) -> ReactiveEvent<Item> {
+        self.item_resized.borrow().clone()
+    }
+
+    pub fn item_selected_changed(&self) -> ReactiveEvent<(Item, bool)> {
+        self.item_selected_changed.borrow().clone()
+    }
+
+    pub fn item_mute_changed(&self) -> ReactiveEvent<(Item, bool)> {
+        self.item_mute_changed.borrow().clone()
+    }
+
+    pub fn item_active_take_changed(&self) -> ReactiveEvent<Item> {
+        self.item_active_take_changed.borrow().clone()
+    }
+
+    /// Envelope events only fire if `ChangeDetectionMiddleware::run()` is called in each main
+    /// loop cycle and envelope polling has been enabled via
+    /// `ChangeDetectionMiddleware::set_envelope_poll_budget()`.
+    pub fn envelope_points_changed(&self) -> ReactiveEvent<EnvelopePointsChangedEvent> {
+        self.envelope_points_changed.borrow().clone()
+    }
+
+    pub fn envelope_visibility_changed(&self) -> ReactiveEvent<EnvelopeVisibilityChangedEvent> {
+        self.envelope_visibility_changed.borrow().clone()
+    }
+
+    pub fn envelope_arm_changed(&self) -> ReactiveEvent<EnvelopeArmChangedEvent> {
+        self.envelope_arm_changed.borrow().clone()
+    }
+
+    pub fn automation_items_changed(&self) -> ReactiveEvent<AutomationItemsChangedEvent> {
+        self.automation_items_changed.borrow().clone()
+    }
+
+    /// Undo events only fire if `ChangeDetectionMiddleware::run()` is called in each main loop
+    /// cycle.
+    pub fn undo_point_created(&self) -> ReactiveEvent<UndoPointCreatedEvent> {
+        self.undo_point_created.borrow().clone()
+    }
+
+    pub fn undo_performed(&self) -> ReactiveEvent<UndoPerformedEvent> {
+        self.undo_performed.borrow().clone()
+    }
+
+    pub fn redo_performed(&self) -> ReactiveEvent<RedoPerformedEvent> {
+        self.redo_performed.borrow().clone()
+    }
+
+    /// Only fires if the `ProjectLifecycleExtension` of the `ChangeDetectionMiddleware` is
+    /// registered.
+    pub fn project_loaded(&self) -> ReactiveEvent<Project> {
+        self.project_loaded.borrow().clone()
+    }
+
+    /// Only fires if the `ProjectLifecycleExtension` of the `ChangeDetectionMiddleware` is
+    /// registered.
+    pub fn project_saved(&self) -> ReactiveEvent<Project> {
+        self.project_saved.borrow().clone()
+    }
+
+    pub fn project_dirty_state_changed(&self) -> ReactiveEvent<(Project, bool)> {
+        self.project_dirty_state_changed.borrow().clone()
+    }
+
     /// Only fires if `run()` is called on the driver.
     pub fn main_thread_idle(&self) -> ReactiveEvent<()> {
         self.main_thread_idle.borrow().clone()
@@ -1,10 +1,15 @@
 use crossbeam_channel::{Receiver, Sender};
-use reaper_high::{MainThreadTask, Reaper, TaskSupport, DEFAULT_MAIN_THREAD_TASK_CHANNEL_CAPACITY};
+use reaper_high::{
+    local_run_loop_executor, run_loop_executor, FutureSupport, MainThreadTask, Reaper, TaskSupport,
+    DEFAULT_MAIN_THREAD_TASK_BULK_SIZE, DEFAULT_MAIN_THREAD_TASK_CHANNEL_CAPACITY,
+};
 use reaper_medium::ReaperVersion;
 use reaper_rx::{ActionRx, ActionRxProvider, ControlSurfaceRx, MainRx};
 use rxrust::prelude::*;
 use std::borrow::Cow;
 use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
 
 type TestStepFinished = LocalSubject<'static, (), ()>;
 pub struct TestStepContext {
@@ -12,12 +17,20 @@ pub struct TestStepContext {
 }
 type TestStepResult = Result<(), Box<dyn Error>>;
 
-type TestOperation = dyn FnOnce(&Reaper, TestStepContext) -> TestStepResult;
+type SyncTestOperation = dyn FnOnce(&Reaper, TestStepContext) -> TestStepResult;
+type AsyncTestOperation =
+    dyn FnOnce(&'static Reaper) -> Pin<Box<dyn Future<Output = TestStepResult>>>;
+
+pub enum TestOperation {
+    Sync(Box<SyncTestOperation>),
+    /// The next step is executed as soon as the returned future has completed.
+    Async(Box<AsyncTestOperation>),
+}
 
 pub struct TestStep {
     pub name: Cow<'static, str>,
     pub version_restriction: VersionRestriction,
-    pub operation: Box<TestOperation>,
+    pub operation: TestOperation,
 }
 
 pub fn step<Op>(
@@ -31,7 +44,25 @@ where
     TestStep {
         version_restriction,
         name: name.into(),
-        operation: Box::new(operation),
+        operation: TestOperation::Sync(Box::new(operation)),
+    }
+}
+
+/// Like [`step()`] but for operations which need to wait for something that happens in a later
+/// main loop cycle.
+pub fn async_step<Op, Fut>(
+    version_restriction: VersionRestriction,
+    name: impl Into<Cow<'static, str>>,
+    operation: Op,
+) -> TestStep
+where
+    Op: FnOnce(&'static Reaper) -> Fut + 'static,
+    Fut: Future<Output = TestStepResult> + 'static,
+{
+    TestStep {
+        version_restriction,
+        name: name.into(),
+        operation: TestOperation::Async(Box::new(move |reaper| Box::pin(operation(reaper)))),
     }
 }
 
@@ -49,6 +80,9 @@ pub enum VersionRestriction {
 pub(crate) struct Test {
     main_rx: MainRx,
     task_support: TaskSupport,
+    future_support: FutureSupport,
+    pub(crate) future_executor: run_loop_executor::RunLoopExecutor,
+    pub(crate) local_future_executor: local_run_loop_executor::RunLoopExecutor,
     pub(crate) task_sender: Sender<MainThreadTask>,
     pub(crate) task_receiver: Receiver<MainThreadTask>,
 }
@@ -57,9 +91,21 @@ impl Default for Test {
     fn default() -> Self {
         let (sender, receiver) =
             crossbeam_channel::bounded(DEFAULT_MAIN_THREAD_TASK_CHANNEL_CAPACITY);
+        let (spawner, future_executor) = run_loop_executor::new_spawner_and_executor(
+            DEFAULT_MAIN_THREAD_TASK_CHANNEL_CAPACITY,
+            DEFAULT_MAIN_THREAD_TASK_BULK_SIZE,
+        );
+        let (local_spawner, local_future_executor) =
+            local_run_loop_executor::new_spawner_and_executor(
+                DEFAULT_MAIN_THREAD_TASK_CHANNEL_CAPACITY,
+                DEFAULT_MAIN_THREAD_TASK_BULK_SIZE,
+            );
         Self {
             main_rx: Default::default(),
             task_support: TaskSupport::new(sender.clone()),
+            future_support: FutureSupport::new(spawner, local_spawner),
+            future_executor,
+            local_future_executor,
             task_sender: sender,
             task_receiver: receiver,
         }
@@ -79,6 +125,10 @@ impl Test {
         &Test::get().task_support
     }
 
+    pub fn future_support() -> &'static FutureSupport {
+        &Test::get().future_support
+    }
+
     pub(crate) fn get() -> &'static Test {
         Reaper::get().require_main_thread();
         &TEST
@@ -4,11 +4,12 @@ mod api;
 mod invocation_mock;
 mod tests;
 
-use crate::api::{Test, TestStep, TestStepContext, VersionRestriction};
+use crate::api::{Test, TestOperation, TestStep, TestStepContext, VersionRestriction};
 use crate::tests::create_test_steps;
+use futures::FutureExt;
 use reaper_high::{
-    ChangeDetectionMiddleware, ControlSurfaceEvent, ControlSurfaceMiddleware, MainTaskMiddleware,
-    MiddlewareControlSurface, Reaper,
+    ChangeDetectionMiddleware, ControlSurfaceEvent, ControlSurfaceMiddleware, FutureMiddleware,
+    MainTaskMiddleware, MiddlewareControlSurface, ProjectLifecycleExtension, Reaper,
 };
 use rxrust::prelude::*;
 
@@ -43,6 +44,7 @@ struct TestControlSurfaceMiddleware {
     change_detection_middleware: ChangeDetectionMiddleware,
     rx_middleware: ControlSurfaceRxMiddleware,
     main_task_middleware: MainTaskMiddleware,
+    future_middleware: FutureMiddleware,
 }
 
 impl TestControlSurfaceMiddleware {
@@ -55,6 +57,11 @@ impl TestControlSurfaceMiddleware {
                 Test::get().task_sender.clone(),
                 Test::get().task_receiver.clone(),
             ),
+            future_middleware: FutureMiddleware::new(
+                Reaper::get().logger().clone(),
+                Test::get().future_executor.clone(),
+                Test::get().local_future_executor.clone(),
+            ),
         }
     }
 }
@@ -62,6 +69,10 @@ impl TestControlSurfaceMiddleware {
 impl ControlSurfaceMiddleware for TestControlSurfaceMiddleware {
     fn run(&mut self) {
         self.main_task_middleware.run();
+        self.future_middleware.run();
+        self.change_detection_middleware.run(|e| {
+            self.rx_middleware.handle_change(e);
+        });
     }
 
     fn handle_event(&self, event: ControlSurfaceEvent) -> bool {
@@ -74,6 +85,7 @@ impl ControlSurfaceMiddleware for TestControlSurfaceMiddleware {
 struct RxSetup {
     control_surface_reg_handle:
         RegistrationHandle<MiddlewareControlSurface<TestControlSurfaceMiddleware>>,
+    project_lifecycle_reg_handle: RegistrationHandle<ProjectLifecycleExtension>,
 }
 
 impl RxSetup {
@@ -83,18 +95,26 @@ impl RxSetup {
             .plugin_register_add_hook_post_command::<ActionRxHookPostCommand<Test>>()
             .unwrap();
         let _ = session.plugin_register_add_hook_post_command_2::<ActionRxHookPostCommand2<Test>>();
+        let middleware = TestControlSurfaceMiddleware::new();
+        let project_lifecycle_extension = middleware
+            .change_detection_middleware
+            .project_lifecycle_extension();
         RxSetup {
             control_surface_reg_handle: {
-                let surface = MiddlewareControlSurface::new(TestControlSurfaceMiddleware::new());
+                let surface = MiddlewareControlSurface::new(middleware);
                 session
                     .plugin_register_add_csurf_inst(Box::new(surface))
                     .expect("couldn't register test control surface")
             },
+            project_lifecycle_reg_handle: session
+                .plugin_register_add_project_config(Box::new(project_lifecycle_extension))
+                .expect("couldn't register project lifecycle extension"),
         }
     }
 
     fn teardown(&self) {
         let mut session = Reaper::get().medium_session();
+        session.plugin_register_remove_project_config(self.project_lifecycle_reg_handle);
         unsafe {
             let _ = session.plugin_register_remove_csurf_inst(self.control_surface_reg_handle);
         }
@@ -119,30 +139,29 @@ fn execute_next_step(
     log_step(step_count - steps.len() - 1, &step.name);
     let reaper = Reaper::get();
     if reaper_version_matches(&step) {
-        let result = {
-            let mut finished = LocalSubject::new();
-            let context = TestStepContext {
-                finished: finished.clone(),
-            };
-            let step_name = step.name.clone();
-            let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
-                (step.operation)(reaper.deref(), context)
-            }))
-            .unwrap_or_else(|_| Err(format!("Test [{}] panicked", step_name).into()));
-            finished.complete();
-            result
-        };
-        match result {
-            Ok(()) => {
-                Test::task_support()
-                    .do_later_in_main_thread_from_main_thread_asap(move || {
-                        execute_next_step(steps, step_count, on_finish)
-                    })
-                    .expect("couldn't schedule next test step");
+        let step_name = step.name.clone();
+        match step.operation {
+            TestOperation::Sync(operation) => {
+                let mut finished = LocalSubject::new();
+                let context = TestStepContext {
+                    finished: finished.clone(),
+                };
+                let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                    operation(reaper.deref(), context)
+                }))
+                .unwrap_or_else(|_| Err(format!("Test [{}] panicked", step_name).into()));
+                finished.complete();
+                continue_after_step(result, steps, step_count, on_finish);
             }
-            Err(e) => {
-                log_failure(&e);
-                on_finish(Err(e));
+            TestOperation::Async(operation) => {
+                let future = operation(reaper);
+                Test::future_support().spawn_in_main_thread_from_main_thread(async move {
+                    let result = AssertUnwindSafe(future)
+                        .catch_unwind()
+                        .await
+                        .unwrap_or_else(|_| Err(format!("Test [{}] panicked", step_name).into()));
+                    continue_after_step(result, steps, step_count, on_finish);
+                });
             }
         }
     } else {
@@ -161,6 +180,27 @@ fn execute_next_step(
     }
 }
 
+fn continue_after_step(
+    result: Result<(), Box<dyn Error>>,
+    steps: VecDeque<TestStep>,
+    step_count: usize,
+    on_finish: impl Fn(Result<(), Box<dyn Error>>) + 'static,
+) {
+    match result {
+        Ok(()) => {
+            Test::task_support()
+                .do_later_in_main_thread_from_main_thread_asap(move || {
+                    execute_next_step(steps, step_count, on_finish)
+                })
+                .expect("couldn't schedule next test step");
+        }
+        Err(e) => {
+            log_failure(&e);
+            on_finish(Err(e));
+        }
+    }
+}
+
 fn reaper_version_matches(step: &TestStep) -> bool {
     use VersionRestriction::*;
     match &step.version_restriction {
@@ -1,19 +1,26 @@
 #![allow(clippy::float_cmp)]
 use approx::*;
 
+use std::collections::VecDeque;
+use std::ffi::{CStr, CString};
 use std::iter;
 use std::ops::Deref;
 
 use c_str_macro::c_str;
 
+use futures::StreamExt;
 use reaper_high::{
-    get_media_track_guid, toggleable, ActionCharacter, ActionKind, FxChain, FxInfo,
-    FxParameterCharacter, Guid, Pan, PlayRate, Reaper, SendPartnerType, Tempo, Track,
-    TrackRoutePartner, Volume, Width,
+    get_media_track_guid, toggleable, ActionCharacter, ActionKind, BookmarkType,
+    ChangeDetectionMiddleware, ChangeEvent, ControlSurfaceEvent, FreezeMode, FxChain,
+    FxChainDifference, FxInfo, FxParameterCharacter, Guid, Pan, PlayRate, Project,
+    ProjectDataHandler, RazorEdit, Reaper, SendPartnerType, SerdeProjectConfigExtension, Tempo,
+    Track, TrackFolderCompactState, TrackGroupMembership, TrackMeterPoller, TrackRoutePartner,
+    Volume, Width,
 };
 use rxrust::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::api::{step, Test, TestStep, VersionRestriction};
+use crate::api::{async_step, step, Test, TestStep, VersionRestriction};
 
 use super::invocation_mock::observe_invocations;
 use crate::api::VersionRestriction::AllVersions;
@@ -22,19 +29,25 @@ use helgoboss_midi::{RawShortMessage, ShortMessageFactory};
 
 use reaper_medium::ProjectContext::CurrentProject;
 use reaper_medium::{
-    reaper_str, AutoSeekBehavior, AutomationMode, Bpm, CommandId, Db, DurationInSeconds, EditMode,
-    EnumPitchShiftModesResult, FxPresetRef, GangBehavior, GetParamExResult, InputMonitoringMode,
-    MasterTrackBehavior, MidiInputDeviceId, MidiOutputDeviceId, NormalizedPlayRate, PitchShiftMode,
-    PlaybackSpeedFactor, PositionInSeconds, ReaperNormalizedFxParamValue, ReaperPanValue,
-    ReaperVersion, ReaperVolumeValue, ReaperWidthValue, RecordingInput, SoloMode,
-    StuffMidiMessageTarget, TrackFxGetPresetIndexResult, TrackLocation, UndoBehavior, ValueChange,
+    reaper_str, AutoSeekBehavior, AutomationMode, BeginLoadProjectStateArgs, BookmarkId,
+    BorrowedProjectStateContext, Bpm, CommandId, Db, DurationInSeconds, EditMode,
+    EnumPitchShiftModesResult, EqBandType, ExtSetProjectMarkerChangeArgs, FxPresetRef,
+    GangBehavior, GetParamExResult, Hz, InputMonitoringMode, MasterTrackBehavior,
+    MidiHardwareOutput, MidiInputDeviceId, MidiOutputDeviceId, NormalizedPlayRate, PitchShiftMode,
+    PlaybackSpeedFactor, PositionInSeconds, ProcessExtensionLineArgs, ProjectConfigExtension,
+    ReaperNormalizedFxParamValue, ReaperPanValue, ReaperVersion, ReaperVolumeValue,
+    ReaperWidthValue, RecordingInput, RecordingMode, RgbColor, SaveExtensionConfigArgs,
+    SendChannelMapping, SendMode, SendSourceChannels, SoloMode, StuffMidiMessageTarget, TrackArea,
+    TrackFxGetPresetIndexResult, TrackGroupParam, TrackLocation, TrackPerformanceFlag,
+    TrackReorderBehavior, UiRefreshBehavior, UndoBehavior, ValueChange, VuMode,
 };
 
 use reaper_low::{raw, Swell};
 use reaper_rx::ActionRxProvider;
-use std::os::raw::{c_int, c_void};
-use std::ptr::null_mut;
+use std::os::raw::{c_char, c_int, c_longlong, c_void};
+use std::ptr::{null_mut, NonNull};
 use std::rc::Rc;
+use std::time::Duration;
 
 const EPSILON: f64 = 0.000_000_1;
 
@@ -53,6 +66,7 @@ pub fn create_test_steps() -> impl Iterator<Item = TestStep> {
         pitch_shift_modes(),
         volume_types(),
         create_empty_project_in_new_tab(),
+        detect_project_dirty_state_changes(),
         play_pause_stop_record(),
         change_repeat_state(),
         add_track(),
@@ -140,6 +154,27 @@ pub fn create_test_steps() -> impl Iterator<Item = TestStep> {
         get_project_tempo(),
         set_project_tempo(),
         swell(),
+        query_installed_fx(),
+        control_eq_bands(),
+        move_tracks_into_and_out_of_folders(),
+        move_and_duplicate_tracks(),
+        set_track_custom_color(),
+        query_theme_color(),
+        add_hardware_output_send(),
+        set_track_group_membership(),
+        query_track_freeze_state(),
+        freeze_and_unfreeze_track(),
+        set_track_layout_attributes(),
+        query_track_peaks(),
+        start_and_stop_track_meter_poller(),
+        preserve_selection(),
+        set_track_razor_edits(),
+        detect_item_changes(),
+        detect_envelope_changes(),
+        detect_bookmark_changes(),
+        detect_undo_history_changes(),
+        register_project_config_extension(),
+        save_and_load_project_data(),
     ]
     .into_iter();
     let output_fx_steps = create_fx_steps("Output FX chain", || {
@@ -175,6 +210,893 @@ fn swell() -> TestStep {
     })
 }
 
+fn add_hardware_output_send() -> TestStep {
+    step(AllVersions, "Add hardware output send", |_, _| {
+        // Given
+        let track = get_track(0)?;
+        let hw_send_count = track.typed_send_count(SendPartnerType::HardwareOutput);
+        // When
+        let route = track.add_hardware_output_send(2)?;
+        route.set_send_mode(SendMode::PreFx);
+        route.set_channel_mapping(SendChannelMapping {
+            source: Some(SendSourceChannels {
+                offset: 0,
+                count: 1,
+            }),
+            ..route.channel_mapping()
+        });
+        // Then
+        assert_eq!(
+            track.typed_send_count(SendPartnerType::HardwareOutput),
+            hw_send_count + 1
+        );
+        assert_eq!(
+            route.partner(),
+            Some(TrackRoutePartner::HardwareOutput(hw_send_count))
+        );
+        assert_eq!(route.send_mode(), SendMode::PreFx);
+        let mapping = route.channel_mapping();
+        assert_eq!(mapping.destination_offset, 2);
+        assert_eq!(mapping.source.ok_or("no audio source")?.count, 1);
+        route.delete()?;
+        Ok(())
+    })
+}
+
+fn set_track_group_membership() -> TestStep {
+    step(AllVersions, "Set track group membership", |reaper, _| {
+        // Given
+        let project = reaper.current_project();
+        let track = get_track(0)?;
+        let volume_lead = TrackGroupMembership::new(3, TrackGroupParam::VolumeLead);
+        let mute_follow = TrackGroupMembership::new(40, TrackGroupParam::MuteFollow);
+        // When
+        track.set_group_membership(volume_lead, true)?;
+        track.set_group_membership(mute_follow, true)?;
+        project.set_track_group_name(3, "Drums")?;
+        // Then
+        assert!(track.is_in_group(volume_lead));
+        assert!(track.is_in_group(mute_follow));
+        assert_eq!(track.groups(TrackGroupParam::MuteFollow), vec![40]);
+        assert_eq!(
+            track.group_membership().into_iter().collect::<Vec<_>>(),
+            vec![volume_lead, mute_follow]
+        );
+        assert_eq!(project.track_group_name(3)?, Some("Drums".to_string()));
+        track.set_group_membership(volume_lead, false)?;
+        assert!(!track.is_in_group(volume_lead));
+        track.set_group_memberships(vec![])?;
+        assert!(track.group_membership().is_empty());
+        Ok(())
+    })
+}
+
+fn query_track_freeze_state() -> TestStep {
+    step(AllVersions, "Query track freeze state", |_, _| {
+        // Given
+        let track = get_track(0)?;
+        // When
+        let state = track.freeze_state()?;
+        // Then
+        assert!(!state.is_frozen());
+        assert_eq!(state.frozen_fx_count(), 0);
+        Ok(())
+    })
+}
+
+fn freeze_and_unfreeze_track() -> TestStep {
+    step(AllVersions, "Freeze and unfreeze track", |reaper, _| {
+        // Given
+        let project = reaper.current_project();
+        let track = project.add_track()?;
+        let item = track.add_item()?;
+        item.set_length(DurationInSeconds::new(1.0), UiRefreshBehavior::NoRefresh)?;
+        track
+            .normal_fx_chain()
+            .add_fx_by_original_name("ReaEq (Cockos)")
+            .ok_or("Couldn't add ReaEq")?;
+        // When
+        let frozen_state = track.freeze(FreezeMode::Stereo);
+        let frozen_state_from_chunk = track.freeze_state();
+        let unfrozen_state = track.unfreeze();
+        let fx_count_after_unfreeze = track.normal_fx_chain().fx_count();
+        let item_count_after_unfreeze = track.item_count();
+        project.remove_track(&track);
+        // Then
+        let frozen_state = frozen_state?;
+        assert!(frozen_state.is_frozen());
+        assert_eq!(frozen_state.freeze_count(), 1);
+        assert_eq!(frozen_state.frozen_fx_count(), 1);
+        assert_eq!(frozen_state.levels()[0].item_chunks.len(), 1);
+        assert_eq!(frozen_state_from_chunk?, frozen_state);
+        let unfrozen_state = unfrozen_state?;
+        assert!(!unfrozen_state.is_frozen());
+        assert_eq!(unfrozen_state.freeze_count(), 0);
+        assert_eq!(fx_count_after_unfreeze, 1);
+        assert_eq!(item_count_after_unfreeze, 1);
+        Ok(())
+    })
+}
+
+fn set_track_layout_attributes() -> TestStep {
+    step(AllVersions, "Set track layout attributes", |_, _| {
+        // Given
+        let track = get_track(0)?;
+        let midi_output = MidiHardwareOutput {
+            device_id: MidiOutputDeviceId::new(0),
+            channel: Some(channel(3)),
+        };
+        let original_height_override = track.height_override();
+        let original_height_is_locked = track.height_is_locked();
+        let original_performance_flags = track.performance_flags();
+        let original_recording_mode = track.recording_mode();
+        let original_vu_mode = track.vu_mode();
+        let original_midi_output = track.midi_hardware_output();
+        // When
+        track.set_height_override(Some(120))?;
+        track.set_height_locked(true)?;
+        track.set_performance_flags(TrackPerformanceFlag::NoAnticipativeFx.into())?;
+        track.set_recording_mode(RecordingMode::MidiOverdub)?;
+        track.set_vu_mode(VuMode::StereoRms)?;
+        track.set_midi_hardware_output(Some(midi_output))?;
+        // Then
+        assert_eq!(track.height_override(), Some(120));
+        assert!(track.height_is_locked());
+        assert_eq!(
+            track.performance_flags(),
+            TrackPerformanceFlag::NoAnticipativeFx
+        );
+        assert_eq!(track.recording_mode(), RecordingMode::MidiOverdub);
+        assert_eq!(track.vu_mode(), VuMode::StereoRms);
+        assert_eq!(track.midi_hardware_output(), Some(midi_output));
+        assert_eq!(track.layout(TrackArea::Tcp), None);
+        // Restore so that subsequent steps aren't affected
+        track.set_height_locked(original_height_is_locked)?;
+        track.set_height_override(original_height_override)?;
+        track.set_performance_flags(original_performance_flags)?;
+        track.set_recording_mode(original_recording_mode)?;
+        track.set_vu_mode(original_vu_mode)?;
+        track.set_midi_hardware_output(original_midi_output)?;
+        assert_eq!(track.height_override(), original_height_override);
+        assert_eq!(track.height_is_locked(), original_height_is_locked);
+        assert_eq!(track.performance_flags(), original_performance_flags);
+        assert_eq!(track.recording_mode(), original_recording_mode);
+        assert_eq!(track.vu_mode(), original_vu_mode);
+        assert_eq!(track.midi_hardware_output(), original_midi_output);
+        Ok(())
+    })
+}
+
+fn query_track_peaks() -> TestStep {
+    step(AllVersions, "Query track peaks", |_, _| {
+        // Given
+        let track = get_track(0)?;
+        // When
+        track.reset_peak_hold();
+        let peaks = track.peaks();
+        // Then
+        assert_eq!(peaks.len() as u32, track.channel_count());
+        assert!(peaks.iter().all(|p| p.peak.db().get() <= 0.0));
+        Ok(())
+    })
+}
+
+fn start_and_stop_track_meter_poller() -> TestStep {
+    async_step(
+        AllVersions,
+        "Start and stop track meter poller",
+        |_| async move {
+            // Given
+            let track = get_track(0)?;
+            // When
+            let mut first = TrackMeterPoller::start(vec![], Duration::from_millis(50), 10)?;
+            let second =
+                TrackMeterPoller::start(vec![track.clone()], Duration::from_millis(50), 10)?;
+            first.set_tracks(vec![track.clone()]);
+            second.set_tracks(vec![]);
+            drop(second);
+            let frame = first.next().await.ok_or("meter stream ended")?;
+            drop(first);
+            // Then
+            assert_eq!(frame.tracks.len(), 1);
+            let meter = &frame.tracks[0];
+            assert_eq!(meter.track, track);
+            assert_eq!(meter.channels.len() as u32, track.channel_count());
+            Ok(())
+        },
+    )
+}
+
+fn preserve_selection() -> TestStep {
+    step(AllVersions, "Preserve selection", |reaper, _| {
+        // Given
+        let project = reaper.current_project();
+        let track = get_track(0)?;
+        let other_track = get_track(1)?;
+        track.select_exclusively();
+        project.set_time_selection(PositionInSeconds::new(1.0), PositionInSeconds::new(2.0));
+        let cursor_context = reaper.medium_reaper().get_cursor_context_2(false);
+        // When
+        let result = project.with_preserved_selection(|| {
+            other_track.select_exclusively();
+            project.clear_time_selection();
+            5
+        });
+        // Then
+        assert_eq!(result, 5);
+        assert_eq!(
+            reaper.medium_reaper().get_cursor_context_2(false),
+            cursor_context
+        );
+        assert!(track.is_selected());
+        assert!(!other_track.is_selected());
+        let time_selection = project.time_selection().ok_or("no time selection")?;
+        assert_eq!(time_selection.start, PositionInSeconds::new(1.0));
+        assert_eq!(time_selection.end, PositionInSeconds::new(2.0));
+        project.clear_time_selection();
+        Ok(())
+    })
+}
+
+fn detect_item_changes() -> TestStep {
+    step(AllVersions, "Detect item changes", |reaper, _| {
+        // Given
+        let track = get_track(0)?;
+        let middleware = ChangeDetectionMiddleware::new();
+        let mut events = Vec::new();
+        let poll = |events: &mut Vec<ChangeEvent>| {
+            // Removed items are only reported after a complete sweep
+            for _ in 0..3 {
+                middleware.run(|e| events.push(e));
+            }
+        };
+        poll(&mut events);
+        assert!(events.is_empty());
+        // When
+        let item = track.add_item()?;
+        poll(&mut events);
+        item.set_position(PositionInSeconds::new(2.0), UiRefreshBehavior::NoRefresh)?;
+        item.set_length(DurationInSeconds::new(1.5), UiRefreshBehavior::NoRefresh)?;
+        poll(&mut events);
+        unsafe {
+            reaper
+                .medium_reaper()
+                .delete_track_media_item(track.raw(), item.raw())?;
+        }
+        poll(&mut events);
+        // Then
+        assert_eq!(events.len(), 4);
+        assert!(matches!(&events[0], ChangeEvent::ItemAdded(e) if e.item == item));
+        match &events[1] {
+            ChangeEvent::ItemMoved(e) => {
+                assert_eq!(e.item, item);
+                assert_eq!(e.new_position, PositionInSeconds::new(2.0));
+            }
+            _ => return Err("expected item moved event".into()),
+        }
+        match &events[2] {
+            ChangeEvent::ItemResized(e) => {
+                assert_eq!(e.item, item);
+                assert_eq!(e.new_value, DurationInSeconds::new(1.5));
+            }
+            _ => return Err("expected item resized event".into()),
+        }
+        assert!(matches!(&events[3], ChangeEvent::ItemRemoved(e) if e.item == item));
+        Ok(())
+    })
+}
+
+fn detect_envelope_changes() -> TestStep {
+    step(AllVersions, "Detect envelope changes", |reaper, _| {
+        // Given
+        let project = reaper.current_project();
+        let track = project.add_track()?;
+        // "Track: Toggle track volume envelope visible"
+        let show_volume_envelope = reaper
+            .main_section()
+            .action_by_command_id(CommandId::new(40406));
+        project.with_preserved_selection(|| {
+            track.select_exclusively();
+            show_volume_envelope.invoke_as_trigger(Some(project))
+        })?;
+        let envelope = track.envelopes().next().ok_or("no volume envelope")?;
+        let middleware = ChangeDetectionMiddleware::new();
+        middleware.set_envelope_poll_budget(1);
+        let mut events = Vec::new();
+        let envelope_count: u32 = project
+            .master_track()
+            .into_iter()
+            .chain(project.tracks())
+            .map(|t| t.envelope_count())
+            .sum();
+        let poll = |events: &mut Vec<ChangeEvent>| {
+            // Each run examines one envelope
+            for _ in 0..envelope_count {
+                middleware.run(|e| {
+                    if matches!(e, ChangeEvent::EnvelopePointsChanged(_)) {
+                        events.push(e);
+                    }
+                });
+            }
+        };
+        poll(&mut events);
+        assert!(events.is_empty());
+        // When
+        let inserted = unsafe {
+            reaper.medium_reaper().low().InsertEnvelopePoint(
+                envelope.raw().as_ptr(),
+                1.0,
+                0.5,
+                0,
+                0.0,
+                false,
+                null_mut(),
+            )
+        };
+        poll(&mut events);
+        project.remove_track(&track);
+        // Then
+        assert!(inserted);
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            ChangeEvent::EnvelopePointsChanged(e) => {
+                assert_eq!(e.track, track);
+                assert_eq!(e.envelope, envelope);
+            }
+            _ => return Err("expected envelope points changed event".into()),
+        }
+        Ok(())
+    })
+}
+
+fn detect_bookmark_changes() -> TestStep {
+    step(AllVersions, "Detect bookmark changes", |reaper, _| {
+        // Given
+        let project = reaper.current_project();
+        let raw_project = project.raw().as_ptr();
+        let low = reaper.medium_reaper().low();
+        let middleware = ChangeDetectionMiddleware::new();
+        let mut events = Vec::new();
+        let notify = |events: &mut Vec<ChangeEvent>| {
+            let event =
+                ControlSurfaceEvent::ExtSetProjectMarkerChange(ExtSetProjectMarkerChangeArgs);
+            middleware.process(&event, |e| {
+                if !matches!(e, ChangeEvent::BookmarksChanged(_)) {
+                    events.push(e);
+                }
+            });
+        };
+        // Captures the initial bookmarks
+        notify(&mut events);
+        assert!(events.is_empty());
+        // When
+        let (marker_id, region_id) = unsafe {
+            let marker_id = low.AddProjectMarker2(
+                raw_project,
+                false,
+                1.0,
+                0.0,
+                c_str!("Marker").as_ptr(),
+                -1,
+                0,
+            );
+            let region_id = low.AddProjectMarker2(
+                raw_project,
+                true,
+                2.0,
+                4.0,
+                c_str!("Region").as_ptr(),
+                -1,
+                0,
+            );
+            (marker_id, region_id)
+        };
+        if marker_id < 0 || region_id < 0 {
+            return Err("couldn't add bookmarks".into());
+        }
+        notify(&mut events);
+        unsafe {
+            low.SetProjectMarker3(
+                raw_project,
+                marker_id,
+                false,
+                1.5,
+                0.0,
+                c_str!("Renamed marker").as_ptr(),
+                0,
+            );
+            low.SetProjectMarker3(
+                raw_project,
+                region_id,
+                true,
+                2.0,
+                5.0,
+                c_str!("Region").as_ptr(),
+                0,
+            );
+        }
+        notify(&mut events);
+        // Moving the region as a whole doesn't resize it
+        unsafe {
+            low.SetProjectMarker3(
+                raw_project,
+                region_id,
+                true,
+                3.0,
+                6.0,
+                c_str!("Region").as_ptr(),
+                0,
+            );
+        }
+        notify(&mut events);
+        unsafe {
+            low.DeleteProjectMarker(raw_project, marker_id, false);
+            low.DeleteProjectMarker(raw_project, region_id, true);
+        }
+        notify(&mut events);
+        // Then
+        let marker_id = BookmarkId::new(marker_id as u32);
+        let region_id = BookmarkId::new(region_id as u32);
+        let is_marker =
+            |t: BookmarkType, id: BookmarkId| t == BookmarkType::Marker && id == marker_id;
+        let is_region =
+            |t: BookmarkType, id: BookmarkId| t == BookmarkType::Region && id == region_id;
+        // Markers come before regions
+        assert_eq!(events.len(), 8);
+        assert!(
+            matches!(&events[0], ChangeEvent::MarkerAdded(e) if is_marker(e.bookmark_type, e.id))
+        );
+        assert!(
+            matches!(&events[1], ChangeEvent::MarkerAdded(e) if is_region(e.bookmark_type, e.id))
+        );
+        assert!(matches!(
+            &events[2],
+            ChangeEvent::MarkerMoved(e) if is_marker(e.bookmark_type, e.id)
+                && e.old_position == PositionInSeconds::new(1.0)
+                && e.new_position == PositionInSeconds::new(1.5)
+        ));
+        assert!(matches!(
+            &events[3],
+            ChangeEvent::MarkerRenamed(e) if is_marker(e.bookmark_type, e.id)
+                && e.old_name == "Marker"
+                && e.new_name == "Renamed marker"
+        ));
+        assert!(matches!(
+            &events[4],
+            ChangeEvent::RegionResized(e) if e.id == region_id
+                && e.old_end_position == PositionInSeconds::new(4.0)
+                && e.new_end_position == PositionInSeconds::new(5.0)
+        ));
+        assert!(matches!(
+            &events[5],
+            ChangeEvent::MarkerMoved(e) if is_region(e.bookmark_type, e.id)
+                && e.old_position == PositionInSeconds::new(2.0)
+                && e.new_position == PositionInSeconds::new(3.0)
+        ));
+        assert!(
+            matches!(&events[6], ChangeEvent::MarkerRemoved(e) if is_marker(e.bookmark_type, e.id))
+        );
+        assert!(
+            matches!(&events[7], ChangeEvent::MarkerRemoved(e) if is_region(e.bookmark_type, e.id))
+        );
+        Ok(())
+    })
+}
+
+fn detect_undo_history_changes() -> TestStep {
+    step(AllVersions, "Detect undo history changes", |reaper, _| {
+        // Given
+        let project = reaper.current_project();
+        let track = get_track(0)?;
+        let middleware = ChangeDetectionMiddleware::new();
+        let mut events = Vec::new();
+        middleware.run(|e| events.push(e));
+        // When
+        project.undoable("reaper-rs undo detection", || {
+            track.set_name("Undo detection");
+        });
+        middleware.run(|e| events.push(e));
+        project.undo();
+        middleware.run(|e| events.push(e));
+        project.redo();
+        middleware.run(|e| events.push(e));
+        // Then
+        let undo_events: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                ChangeEvent::UndoPointCreated(e) => Some(("created", e.label.as_str())),
+                ChangeEvent::UndoPerformed(e) => Some(("undone", e.label.as_str())),
+                ChangeEvent::RedoPerformed(e) => Some(("redone", e.label.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            undo_events,
+            vec![
+                ("created", "reaper-rs undo detection"),
+                ("undone", "reaper-rs undo detection"),
+                ("redone", "reaper-rs undo detection"),
+            ]
+        );
+        Ok(())
+    })
+}
+
+fn register_project_config_extension() -> TestStep {
+    step(
+        AllVersions,
+        "Register project config extension",
+        |reaper, _| {
+            // Given
+            let project = reaper.current_project();
+            let middleware = ChangeDetectionMiddleware::new();
+            let extension = middleware.project_lifecycle_extension();
+            let mut events = Vec::new();
+            middleware.run(|e| events.push(e));
+            // When
+            let mut extension = {
+                let mut session = reaper.medium_session();
+                let handle = session
+                    .plugin_register_add_project_config(Box::new(extension))
+                    .map_err(|_| "couldn't register project config extension")?;
+                session
+                    .plugin_register_remove_project_config(handle)
+                    .ok_or("couldn't unregister project config extension")?
+            };
+            // Simulate what REAPER does when loading and saving projects
+            let mut callback_target: Box<dyn reaper_low::ProjectStateContext> =
+                Box::new(NullProjectStateContext);
+            let mut raw_ctx = unsafe {
+                reaper_low::create_cpp_to_rust_project_state_context(NonNull::from(
+                    &mut callback_target,
+                ))
+            };
+            let ctx = BorrowedProjectStateContext::from_raw_mut(unsafe { raw_ctx.as_mut() });
+            extension.begin_load_project_state(BeginLoadProjectStateArgs { is_undo: false });
+            extension.save_extension_config(SaveExtensionConfigArgs {
+                ctx: &mut *ctx,
+                is_undo: false,
+            });
+            // Undo/redo shouldn't be reported as load/save
+            extension.begin_load_project_state(BeginLoadProjectStateArgs { is_undo: true });
+            extension.save_extension_config(SaveExtensionConfigArgs { ctx, is_undo: true });
+            unsafe { reaper_low::delete_cpp_project_state_context(raw_ctx) };
+            middleware.run(|e| events.push(e));
+            // Then
+            let lifecycle_events: Vec<_> = events
+                .iter()
+                .filter_map(|e| match e {
+                    ChangeEvent::ProjectLoaded(e) => Some(("loaded", e.project)),
+                    ChangeEvent::ProjectSaved(e) => Some(("saved", e.project)),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(
+                lifecycle_events,
+                vec![("loaded", project), ("saved", project)]
+            );
+            Ok(())
+        },
+    )
+}
+
+fn save_and_load_project_data() -> TestStep {
+    step(AllVersions, "Save and load project data", |reaper, _| {
+        // Given
+        let project = reaper.current_project();
+        let data = TestProjectData {
+            name: "x".repeat(300),
+            values: vec![1, 2, 3],
+        };
+        let mut extension = SerdeProjectConfigExtension::new(
+            "REAPER_RS_TEST",
+            TestProjectDataHandler {
+                data_to_save: Some(data.clone()),
+                loaded_data: Vec::new(),
+            },
+        );
+        let mut callback_target: Box<dyn reaper_low::ProjectStateContext> =
+            Box::new(RecordingProjectStateContext::default());
+        let mut raw_ctx = unsafe {
+            reaper_low::create_cpp_to_rust_project_state_context(NonNull::from(
+                &mut callback_target,
+            ))
+        };
+        let ctx = BorrowedProjectStateContext::from_raw_mut(unsafe { raw_ctx.as_mut() });
+        // When
+        extension.save_extension_config(SaveExtensionConfigArgs {
+            ctx: &mut *ctx,
+            is_undo: false,
+        });
+        // A block of another extension follows
+        ctx.add_line("<OTHER_TAG");
+        // REAPER passes the first line of the block and lets the extension read the rest
+        let first_line = ctx.get_line(4096).ok_or("no line written")?;
+        let processed = extension.process_extension_line(ProcessExtensionLineArgs {
+            line: first_line.as_reaper_str(),
+            ctx: &mut *ctx,
+            is_undo: false,
+        });
+        let next_line = ctx.get_line(4096).ok_or("other block missing")?;
+        let foreign_processed = extension.process_extension_line(ProcessExtensionLineArgs {
+            line: next_line.as_reaper_str(),
+            ctx: &mut *ctx,
+            is_undo: false,
+        });
+        let remaining_line = ctx.get_line(4096);
+        unsafe { reaper_low::delete_cpp_project_state_context(raw_ctx) };
+        // Then
+        assert_eq!(first_line.to_str(), "<REAPER_RS_TEST");
+        assert!(processed);
+        assert_eq!(extension.handler().loaded_data, vec![(project, data)]);
+        // The closing ">" has been consumed
+        assert_eq!(next_line.to_str(), "<OTHER_TAG");
+        assert!(!foreign_processed);
+        assert!(remaining_line.is_none());
+        Ok(())
+    })
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+struct TestProjectData {
+    name: String,
+    values: Vec<u32>,
+}
+
+struct TestProjectDataHandler {
+    data_to_save: Option<TestProjectData>,
+    loaded_data: Vec<(Project, TestProjectData)>,
+}
+
+impl ProjectDataHandler for TestProjectDataHandler {
+    type Data = TestProjectData;
+
+    fn save_data(&mut self, _project: Project, _is_undo: bool) -> Option<TestProjectData> {
+        self.data_to_save.clone()
+    }
+
+    fn load_data(&mut self, project: Project, data: TestProjectData, _is_undo: bool) {
+        self.loaded_data.push((project, data));
+    }
+}
+
+/// Project state context which records written lines and returns them in the same order when
+/// reading.
+#[derive(Default)]
+struct RecordingProjectStateContext {
+    lines: VecDeque<CString>,
+}
+
+impl reaper_low::ProjectStateContext for RecordingProjectStateContext {
+    fn AddLine(&mut self, line: *const c_char) {
+        let line = unsafe { CStr::from_ptr(line) };
+        self.lines.push_back(line.to_owned());
+    }
+
+    fn GetLine(&mut self, buf: *mut c_char, buflen: c_int) -> c_int {
+        let line = match self.lines.pop_front() {
+            None => return -1,
+            Some(l) => l,
+        };
+        let bytes = line.as_bytes();
+        let len = bytes.len().min(buflen as usize - 1);
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr() as *const c_char, buf, len);
+            *buf.add(len) = 0;
+        }
+        0
+    }
+
+    fn GetOutputSize(&mut self) -> c_longlong {
+        self.lines
+            .iter()
+            .map(|l| l.as_bytes().len() as c_longlong)
+            .sum()
+    }
+
+    fn GetTempFlag(&mut self) -> c_int {
+        0
+    }
+
+    fn SetTempFlag(&mut self, _flag: c_int) {}
+}
+
+/// Project state context which discards written lines and has nothing to read.
+struct NullProjectStateContext;
+
+impl reaper_low::ProjectStateContext for NullProjectStateContext {
+    fn AddLine(&mut self, _line: *const c_char) {}
+
+    fn GetLine(&mut self, _buf: *mut c_char, _buflen: c_int) -> c_int {
+        -1
+    }
+
+    fn GetOutputSize(&mut self) -> c_longlong {
+        0
+    }
+
+    fn GetTempFlag(&mut self) -> c_int {
+        0
+    }
+
+    fn SetTempFlag(&mut self, _flag: c_int) {}
+}
+
+fn set_track_razor_edits() -> TestStep {
+    step(
+        VersionRestriction::Min(ReaperVersion::new("6.24")),
+        "Set track razor edits",
+        |reaper, _| {
+            // Given
+            let project = reaper.current_project();
+            let track = get_track(0)?;
+            let edits = vec![RazorEdit {
+                start: PositionInSeconds::new(1.0),
+                end: PositionInSeconds::new(3.5),
+                envelope_guid: None,
+                lane: None,
+            }];
+            // When
+            track.set_razor_edits(&edits)?;
+            // Then
+            assert_eq!(track.razor_edits()?, edits);
+            let project_edits = project.razor_edits()?;
+            assert_eq!(project_edits.len(), 1);
+            assert_eq!(project_edits[0].track, track);
+            assert_eq!(project_edits[0].edits, edits);
+            track.set_razor_edits(&[])?;
+            assert!(track.razor_edits()?.is_empty());
+            assert!(project.razor_edits()?.is_empty());
+            Ok(())
+        },
+    )
+}
+
+fn set_track_custom_color() -> TestStep {
+    step(AllVersions, "Set track custom color", |_, _| {
+        // Given
+        let track = get_track(0)?;
+        let color = RgbColor {
+            r: 200,
+            g: 100,
+            b: 50,
+        };
+        // When
+        track.set_custom_color(Some(color));
+        let color_after_set = track.custom_color();
+        track.set_custom_color(None);
+        // Then
+        assert_eq!(color_after_set, Some(color));
+        assert_eq!(track.custom_color(), None);
+        Ok(())
+    })
+}
+
+fn query_theme_color() -> TestStep {
+    step(AllVersions, "Query theme color", |reaper, _| {
+        // Given
+        // When
+        let color = reaper.theme_color("col_main_bg2");
+        let invalid_color = reaper.theme_color("col_doesnt_exist");
+        // Then
+        assert!(color.is_ok());
+        assert!(invalid_color.is_err());
+        Ok(())
+    })
+}
+
+fn move_and_duplicate_tracks() -> TestStep {
+    step(AllVersions, "Move and duplicate tracks", |_, _| {
+        // Given
+        let project = Reaper::get().current_project();
+        let track_1 = project.add_track()?;
+        track_1.set_name("Track 1");
+        let track_2 = project.add_track()?;
+        let index_1 = track_1.index().ok_or("no index")?;
+        track_2.select_exclusively();
+        // When
+        project.move_tracks(&[track_2.clone()], index_1, TrackReorderBehavior::Normal)?;
+        let duplicate = track_1.duplicate()?;
+        // Then
+        assert_eq!(track_2.index(), Some(index_1));
+        assert!(track_2.is_selected());
+        assert_eq!(duplicate.index(), Some(index_1 + 1));
+        assert_eq!(track_1.index(), Some(index_1 + 2));
+        assert_ne!(duplicate.guid(), track_1.guid());
+        assert_eq!(duplicate.name().ok_or("no track name")?.to_str(), "Track 1");
+        project.remove_track(&duplicate);
+        project.remove_track(&track_2);
+        project.remove_track(&track_1);
+        Ok(())
+    })
+}
+
+fn move_tracks_into_and_out_of_folders() -> TestStep {
+    step(
+        AllVersions,
+        "Move tracks into and out of folders",
+        |_, _| {
+            // Given
+            let project = Reaper::get().current_project();
+            let folder = project.add_track()?;
+            let child = project.add_track()?;
+            let top_level_count = project.track_tree().len();
+            // When
+            child.move_into_folder(&folder)?;
+            // Then
+            assert!(folder.is_folder());
+            assert_eq!(child.parent().as_ref(), Some(&folder));
+            assert_eq!(folder.children(), vec![child.clone()]);
+            assert_eq!(folder.descendants(), vec![child.clone()]);
+            let tree = project.track_tree();
+            assert_eq!(tree.len(), top_level_count - 1);
+            let folder_node = tree.last().ok_or("no tree nodes")?;
+            assert_eq!(folder_node.track(), &folder);
+            assert_eq!(folder_node.tracks(), vec![&folder, &child]);
+            folder.set_folder_compact_state(TrackFolderCompactState::Small)?;
+            assert_eq!(
+                folder.folder_compact_state(),
+                TrackFolderCompactState::Small
+            );
+            // When
+            child.move_out_of_folder()?;
+            // Then
+            assert!(!folder.is_folder());
+            assert_eq!(child.parent(), None);
+            assert!(folder.children().is_empty());
+            assert_eq!(project.track_tree().len(), top_level_count);
+            project.remove_track(&child);
+            project.remove_track(&folder);
+            Ok(())
+        },
+    )
+}
+
+fn control_eq_bands() -> TestStep {
+    step(AllVersions, "Control EQ bands", |_, _| {
+        // Given
+        let track = get_track(0)?;
+        let eq = track.rea_eq_or_add().ok_or("couldn't add ReaEQ")?;
+        let band = eq
+            .bands()
+            .into_iter()
+            .find(|b| b.band_type() == EqBandType::Band)
+            .ok_or("ReaEQ has no band of type band")?;
+        // When
+        band.set_frequency(Hz::new(1000.0))?;
+        band.set_gain(Db::new(-6.0))?;
+        band.set_enabled(false)?;
+        // Then
+        assert_eq!(track.rea_eq().as_ref(), Some(&eq));
+        let frequency = band.frequency().ok_or("no frequency")?;
+        assert!(abs_diff_eq!(frequency.get(), 1000.0, epsilon = 1.0));
+        let gain = band.gain().ok_or("no gain")?;
+        assert!(abs_diff_eq!(gain.get(), -6.0, epsilon = 0.1));
+        assert!(!band.is_enabled());
+        eq.fx().chain().remove_fx(eq.fx())?;
+        Ok(())
+    })
+}
+
+fn query_installed_fx() -> TestStep {
+    step(AllVersions, "Query installed FX", |reaper, _| {
+        // Given
+        // When
+        let installed_fx = reaper.installed_fx();
+        // Then
+        let rea_eq = installed_fx
+            .search("reaeq cockos")
+            .next()
+            .ok_or("ReaEQ not found in installed FX")?;
+        assert_eq!(rea_eq.original_name, "VST: ReaEQ (Cockos)");
+        assert!(!rea_eq.is_instrument);
+        assert!(installed_fx
+            .find_by_original_name("VSTi: ReaSynth (Cockos)")
+            .is_some());
+        Ok(())
+    })
+}
+
 fn set_project_tempo() -> TestStep {
     step(AllVersions, "Set project tempo", |_, step| {
         // Given
@@ -259,6 +1181,36 @@ fn get_project_play_rate() -> TestStep {
     })
 }
 
+fn detect_project_dirty_state_changes() -> TestStep {
+    step(
+        AllVersions,
+        "Detect project dirty state changes",
+        |reaper, _| {
+            // Given
+            let project = reaper.current_project();
+            let middleware = ChangeDetectionMiddleware::new();
+            let mut events = Vec::new();
+            middleware.run(|e| events.push(e));
+            if project.is_dirty() {
+                return Err("new project is dirty already".into());
+            }
+            // When
+            project.mark_as_dirty();
+            middleware.run(|e| events.push(e));
+            // Then
+            let dirty_events: Vec<_> = events
+                .iter()
+                .filter_map(|e| match e {
+                    ChangeEvent::ProjectDirtyStateChanged(e) => Some((e.project, e.new_value)),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(dirty_events, vec![(project, true)]);
+            Ok(())
+        },
+    )
+}
+
 fn mark_project_as_dirty() -> TestStep {
     step(AllVersions, "Mark project as dirty", |_session, _| {
         // Given
@@ -2553,9 +3505,11 @@ fn create_fx_steps(
         enable_track_fx(get_fx_chain.clone()),
         check_track_fx_with_2_fx(get_fx_chain.clone()),
         check_fx_parameter(get_fx_chain.clone()),
+        check_fx_parameter_ident(get_fx_chain.clone()),
         check_fx_presets(get_fx_chain.clone()),
         set_fx_parameter_value(get_fx_chain.clone()),
         fx_parameter_value_changed_with_heuristic_fail(get_fx_chain.clone()),
+        snapshot_and_restore_fx_chain(get_fx_chain.clone()),
         move_fx(get_fx_chain.clone()),
         remove_fx(get_fx_chain.clone()),
         add_fx_by_chunk(get_fx_chain.clone()),
@@ -2759,6 +3713,41 @@ fn query_fx_floating_window(get_fx_chain: GetFxChain) -> TestStep {
     )
 }
 
+fn snapshot_and_restore_fx_chain(get_fx_chain: GetFxChain) -> TestStep {
+    step(AllVersions, "Snapshot and restore fx chain", move |_, _| {
+        // Given
+        let fx_chain = get_fx_chain()?;
+        let fx = fx_chain.fx_by_index(1).ok_or("Couldn't find second fx")?;
+        let p = fx.parameter_by_index(5);
+        let snapshot = fx_chain.snapshot()?;
+        // When
+        fx.disable();
+        p.set_reaper_normalized_value(0.7)?;
+        let changed_snapshot = fx_chain.snapshot()?;
+        let differences = snapshot.diff(&changed_snapshot);
+        fx_chain.restore_snapshot(&snapshot)?;
+        // Then
+        assert_eq!(snapshot.fxs().len(), 2);
+        assert!(snapshot.has_same_structure_as(&changed_snapshot));
+        assert!(differences.contains(&FxChainDifference::EnabledChanged {
+            guid: fx.guid().ok_or("no FX GUID")?,
+            old_value: true,
+            new_value: false,
+        }));
+        assert!(differences.iter().any(|d| matches!(
+            d,
+            FxChainDifference::ParameterChanged { param_index: 5, .. }
+        )));
+        assert!(fx.is_enabled());
+        assert_eq!(
+            p.reaper_normalized_value(),
+            snapshot.fxs()[1].parameter_values[5]
+        );
+        assert!(snapshot.diff(&fx_chain.snapshot()?).is_empty());
+        Ok(())
+    })
+}
+
 fn set_fx_chain_chunk(get_fx_chain: GetFxChain) -> TestStep {
     step(AllVersions, "Set fx chain chunk", move |_, _| {
         // Given
@@ -3216,6 +4205,28 @@ fn check_fx_parameter(get_fx_chain: GetFxChain) -> TestStep {
     })
 }
 
+fn check_fx_parameter_ident(get_fx_chain: GetFxChain) -> TestStep {
+    step(
+        VersionRestriction::Min(ReaperVersion::new("6.37")),
+        "Check fx parameter ident",
+        move |_, _| {
+            // Given
+            let fx_chain = get_fx_chain()?;
+            let fx = fx_chain.fx_by_index(0).ok_or("Couldn't find first fx")?;
+            let p = fx.parameter_by_index(5);
+            // When
+            let ident = p.ident()?;
+            let descriptor = p.descriptor()?;
+            // Then
+            assert_eq!(fx.parameter_by_ident(ident.to_str()), Some(p.clone()));
+            assert_eq!(descriptor.fx_guid, fx.guid().ok_or("no FX GUID")?);
+            assert_eq!(descriptor.param_ident, ident.to_str());
+            assert_eq!(descriptor.resolve(&fx_chain), Some(p));
+            Ok(())
+        },
+    )
+}
+
 fn check_track_fx_with_2_fx(get_fx_chain: GetFxChain) -> TestStep {
     #[allow(clippy::cognitive_complexity)]
     step(